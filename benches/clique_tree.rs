@@ -0,0 +1,155 @@
+//! Benchmarks of the clique tree construction, the global optima calculation, the fitness evaluation and writing codomains.
+//! Run them with `cargo bench --features bench`. By default only small parameter sets are used, so that they finish quickly;
+//!  set the PROBLEM_GENERATOR_BENCH_LARGE environment variable to also benchmark larger parameter sets.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::Rng;
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters, SolutionFit, DEFAULT_FITNESS_EPSILON},
+    codomain::{generate_codomain, write_codomain_to_writer, DEFAULT_FLUSH_EVERY},
+    codomain_subclasses::CodomainFunction,
+    configuration::get_rng,
+};
+
+///Environment variable that enables the larger parameter sets
+const LARGE_ENV_VAR: &str = "PROBLEM_GENERATOR_BENCH_LARGE";
+
+const SEED: u64 = 2398;
+
+///Get the parameter sets (M, k, o, b) to benchmark: separable, overlapping chain and overlapping tree problems
+fn get_parameter_sets() -> Vec<InputParameters> {
+    let mut parameter_sets = vec![(32, 4, 0, 1), (32, 4, 1, 1), (32, 5, 2, 2), (64, 4, 1, 3)];
+    if std::env::var_os(LARGE_ENV_VAR).is_some() {
+        parameter_sets.extend([(512, 5, 2, 2), (256, 8, 3, 2), (1024, 4, 1, 4)]);
+    }
+    parameter_sets
+        .into_iter()
+        .map(|(m, k, o, b)| InputParameters::new_from_primitives(m, k, o, b))
+        .collect()
+}
+
+fn get_benchmark_name(input_parameters: &InputParameters) -> String {
+    format!(
+        "{}x{}x{}x{}",
+        input_parameters.m, input_parameters.k, input_parameters.o, input_parameters.b
+    )
+}
+
+fn generate_clique_tree(input_parameters: &InputParameters) -> CliqueTree {
+    CliqueTree::new_seeded(input_parameters.clone(), CodomainFunction::Random, SEED)
+}
+
+fn bench_construct(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construct");
+    for input_parameters in get_parameter_sets() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(get_benchmark_name(&input_parameters)),
+            &input_parameters,
+            |b, input_parameters| {
+                let mut rng = get_rng(Some(SEED));
+                b.iter(|| CliqueTree::construct(black_box(input_parameters), &mut rng))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_calculate_global_optima(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_global_optima");
+    for input_parameters in get_parameter_sets() {
+        let mut rng = get_rng(Some(SEED));
+        let codomain = generate_codomain(&input_parameters, &CodomainFunction::Random, &mut rng);
+        let (cliques, separators) = CliqueTree::construct(&input_parameters, &mut rng);
+        group.bench_function(get_benchmark_name(&input_parameters), |b| {
+            b.iter(|| {
+                CliqueTree::calculate_global_optima(
+                    black_box(&input_parameters),
+                    &CodomainFunction::Random,
+                    &codomain,
+                    &cliques,
+                    &separators,
+                    DEFAULT_FITNESS_EPSILON,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_calculate_fitness(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_fitness");
+    for input_parameters in get_parameter_sets() {
+        let clique_tree = generate_clique_tree(&input_parameters);
+        let mut rng = get_rng(Some(SEED));
+        let solution: Vec<u32> = (0..clique_tree.get_problem_size())
+            .map(|_| rng.gen_range(0..2))
+            .collect();
+        let mut number_evaluations = 0;
+        group.bench_function(get_benchmark_name(&input_parameters), |b| {
+            b.iter(|| clique_tree.calculate_fitness(black_box(&solution), &mut number_evaluations))
+        });
+    }
+    group.finish();
+}
+
+fn bench_calculate_fitness_delta(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_fitness_delta");
+    for input_parameters in get_parameter_sets() {
+        let clique_tree = generate_clique_tree(&input_parameters);
+        let mut rng = get_rng(Some(SEED));
+        let problem_size = clique_tree.get_problem_size();
+        let solution: Vec<u32> = (0..problem_size).map(|_| rng.gen_range(0..2)).collect();
+        let mut number_evaluations = 0;
+        let solutionfit = SolutionFit::evaluate(&clique_tree, solution, &mut number_evaluations);
+        group.bench_function(get_benchmark_name(&input_parameters), |b| {
+            let mut index_mutation = 0;
+            b.iter(|| {
+                index_mutation = (index_mutation + 1) % problem_size;
+                clique_tree.calculate_fitness_delta(
+                    black_box(&solutionfit),
+                    &mut number_evaluations,
+                    index_mutation,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_write_codomain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_codomain");
+    for input_parameters in get_parameter_sets() {
+        let mut rng = get_rng(Some(SEED));
+        let codomain = generate_codomain(&input_parameters, &CodomainFunction::Random, &mut rng);
+        for flush_every in [1, DEFAULT_FLUSH_EVERY] {
+            group.bench_with_input(
+                BenchmarkId::new(get_benchmark_name(&input_parameters), flush_every),
+                &flush_every,
+                |b, &flush_every| {
+                    b.iter(|| {
+                        write_codomain_to_writer(
+                            std::io::sink(),
+                            &input_parameters,
+                            &CodomainFunction::Random,
+                            black_box(&codomain),
+                            false,
+                            flush_every,
+                        )
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_construct,
+    bench_calculate_global_optima,
+    bench_calculate_fitness,
+    bench_calculate_fitness_delta,
+    bench_write_codomain
+);
+criterion_main!(benches);
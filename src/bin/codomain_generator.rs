@@ -1,11 +1,19 @@
 use problem_generator::problem::codomain::CodomainOpt;
+use problem_generator::problem::configuration::get_seed_or_random;
 use structopt::StructOpt;
 
 use std::process;
 
 fn main() {
-    let codomain_opt = CodomainOpt::from_args();
-    println!("{:?}", codomain_opt);
+    let mut codomain_opt = CodomainOpt::from_args();
+    problem_generator::init_logger(codomain_opt.quiet);
+    //Resolve the seed here, so that a run without a seed can be reproduced with the printed seed
+    let seed = get_seed_or_random(codomain_opt.seed);
+    codomain_opt.seed = Some(seed);
+    if !codomain_opt.quiet {
+        println!("Using seed: {}", seed);
+        println!("{:?}", codomain_opt);
+    }
 
     problem_generator::problem::codomain::run_opt(codomain_opt).unwrap_or_else(|err| {
         eprintln!("Problem encountered while generating the codomain: {}", err);
@@ -1,11 +1,19 @@
 use problem_generator::problem::problem_generation::ProblemOpt;
+use problem_generator::problem::configuration::get_seed_or_random;
 use structopt::StructOpt;
 
 use std::process;
 
 fn main() {
-    let problem_opt = ProblemOpt::from_args();
-    println!("{:?}", problem_opt);
+    let mut problem_opt = ProblemOpt::from_args();
+    problem_generator::init_logger(problem_opt.quiet);
+    //Resolve the seed here, so that a run without a seed can be reproduced with the printed seed
+    let seed = get_seed_or_random(problem_opt.seed);
+    problem_opt.seed = Some(seed);
+    if !problem_opt.quiet {
+        println!("Using seed: {}", seed);
+        println!("{:?}", problem_opt);
+    }
 
     problem_generator::problem::problem_generation::run_opt(problem_opt).unwrap_or_else(|err| {
         eprintln!("Problem encountered while generating the problem: {}", err);
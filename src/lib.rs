@@ -12,13 +12,41 @@ To use problem_generator in your project, you can simply add problem_generator i
 [dependencies]
 problem_generator = "^0.3.1"
 ```
+
+## Features
+
+The `std` feature is enabled by default and contains everything except the evaluation core in [`problem::evaluation`].
+To only use the evaluation core in a `no_std` environment with an allocator, disable the default features:
+
+```toml
+[dependencies]
+problem_generator = { version = "^0.3.1", default-features = false }
+```
 */
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate log;
 
+#[cfg(feature = "std")]
 extern crate structopt;
+#[cfg(feature = "std")]
 extern crate structopt_derive;
 
 ///The parent module for all the functional modules
 pub mod problem;
+
+///Initialize the logger for the binaries, with the log level taken from the RUST_LOG environment variable.
+/// If quiet, all log messages are turned off, regardless of the environment variable.
+#[cfg(feature = "std")]
+pub fn init_logger(quiet: bool) {
+    let mut builder = env_logger::Builder::from_default_env();
+    if quiet {
+        builder.filter_level(log::LevelFilter::Off);
+    }
+    builder.init();
+}
@@ -1,6 +1,21 @@
+#[cfg(feature = "std")]
 pub mod clique_tree;
+#[cfg(feature = "std")]
 pub mod codomain;
+#[cfg(feature = "std")]
+pub mod codomain_registry;
+#[cfg(feature = "std")]
 pub mod codomain_subclasses;
+#[cfg(feature = "std")]
 pub mod configuration;
+#[cfg(feature = "std")]
+pub mod error;
+pub mod evaluation;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
 pub mod io;
+#[cfg(feature = "std")]
 pub mod problem_generation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -1,36 +1,79 @@
 /*!
-Module for the 1) Clique Tree construction and global optimum calculation, 2) and the struct to contain solutions.
+Module for the Clique Tree construction and global optimum calculation. The input parameters and the struct to contain solutions are defined in the evaluation module and re-exported here.
 */
 
 use rand_chacha::ChaChaRng;
-use rand::seq::SliceRandom;
-use serde::{Deserialize, Serialize};
-
-use std::{error::Error, str::Lines};
-
-use crate::problem::codomain_subclasses::CodomainFunction;
-use crate::problem::problem_generation::Problem;
-
-const FITNESS_EPSILON: f64 = 0.0000000001;
-
-///Struct to contain the solution and its fitness, with the solution stored as a vector of u32 values (0 or 1) and the fitness as a f64 value
-#[derive(Debug, Clone)]
-pub struct SolutionFit {
-    pub solution: Vec<u32>,
-    pub fitness: f64,
+use rand::seq::index;
+use rand::Rng;
+use rayon::prelude::*;
+
+use std::{
+    collections::HashMap, convert::TryFrom, error::Error, ops::Range, path::Path, str::FromStr,
+    str::Lines,
+};
+
+use crate::problem::codomain::{
+    generate_codomain, read_codomain_values_only, update_fnv_hash, FNV_OFFSET_BASIS,
+};
+use crate::problem::codomain_subclasses::{
+    get_hamming_distance_to_solution, get_random_solution, CodomainFunction,
+};
+use crate::problem::configuration::{get_rng, shuffle};
+use crate::problem::error::ProblemGenError;
+use crate::problem::io::{codomain_file_has_codomain_function, get_clique_tree_from_codomain_file};
+use crate::problem::problem_generation::{read_problem_from_file, write_problem_to_file, Problem};
+
+pub use crate::problem::evaluation::{InputParameters, SolutionFit};
+pub(crate) use crate::problem::evaluation::get_variable_cliques;
+use crate::problem::evaluation::{self, get_cliques_of_variable, CliqueTreeEvaluator};
+
+///Default epsilon below which two fitnesses are considered equal
+pub const DEFAULT_FITNESS_EPSILON: f64 = 0.0000000001;
+
+///Maximum number of global optima strings that are constructed for a separable problem.
+/// When there are more global optima, only this many are constructed, see CliqueTree::glob_optima_truncated.
+pub const MAX_MATERIALIZED_GLOBAL_OPTIMA: usize = 1 << 14;
+
+///Minimum number of leaves of a clique tree for which the best scores of the leaves are calculated in parallel
+pub const MIN_PARALLEL_LEAVES: usize = 64;
+
+///Minimum amount added to the codomain values of the global optimum's substrings to make the global optimum unique
+pub const UNIQUE_OPTIMUM_PERTURBATION: f64 = 0.000001;
+
+///Maximum number of perturbations applied when making the global optimum unique
+pub const MAX_UNIQUE_OPTIMUM_RETRIES: u32 = 10;
+
+///Maximum problem size for which all solutions are enumerated by solutions_by_fitness
+pub const MAX_ENUMERATED_PROBLEM_SIZE: u32 = 24;
+
+///A (sub)string together with the score it attains
+type SubstringScore = (Vec<u32>, f64);
+
+impl SolutionFit {
+    ///Create a solution together with its fitness, calculated with calculate_fitness of the clique tree,
+    /// so that the solution and its fitness are consistent
+    pub fn evaluate(
+        clique_tree: &CliqueTree,
+        solution: Vec<u32>,
+        number_evaluations: &mut u32,
+    ) -> SolutionFit {
+        let fitness = clique_tree.calculate_fitness(&solution, number_evaluations);
+        SolutionFit::new(solution, fitness)
+    }
 }
 
-///Struct to contain the input parameters of the TD Mk Landscape:
-/// Number of cliques/subfunctions M,
-/// size k of each clique/subfunction,
-/// number of overlapping variables between cliques/subfunctions o,
-/// number of branches in the clique tree / tree decomposition b
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub struct InputParameters {
-    pub m: u32,
-    pub k: u32,
-    pub o: u32,
-    pub b: u32,
+///Resolution to which the codomain values are quantized when calculating the content hash of a problem
+pub const CONTENT_HASH_RESOLUTION: f64 = 1e-9;
+
+///Summary statistics of all codomain values of a problem
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodomainStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    ///Population standard deviation of the codomain values
+    pub std_dev: f64,
+    pub distinct_values: usize,
 }
 
 impl InputParameters {
@@ -57,25 +100,97 @@ impl InputParameters {
             .map_err(|_| "Could not parse b to u32")?;
 
         //let codomain_file = args[5].clone();
-        Ok(InputParameters { m, k, o, b })
+        Ok(InputParameters::new_from_primitives(m, k, o, b))
+    }
+
+    ///Check that the input parameters describe a valid clique tree. There should be at least one clique, and o has to be smaller than k:
+    /// with o == k, a child clique would consist of only its separator and add no new variables.
+    /// b should be at least 1, unless the problem is separable, as then the cliques are constructed as a chain.
+    pub fn validate(&self) -> Result<(), ProblemGenError> {
+        if self.m == 0 {
+            return Err(ProblemGenError::InvalidParameters(
+                "M should be at least 1, as the clique tree should contain at least one clique"
+                    .to_owned(),
+            ));
+        }
+        if self.branching_factor() == 0 {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "b should be at least 1 for o ({}) larger than 0, as every clique that is not a leaf should have a child",
+                self.o
+            )));
+        }
+        if self.o >= self.k {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "o ({}) should be smaller than k ({}), as every clique should add at least one variable to its parent",
+                self.o, self.k
+            )));
+        }
+        Ok(())
+    }
+
+    ///Check that the input parameters can be written on the parameters line (M k o b) of the problem, structure, and codomain files.
+    /// This is not the case if o varies per level, as the parameters line has no room for o_per_level;
+    ///  such clique trees can be stored with `to_bytes` instead.
+    pub fn check_parameters_line(&self) -> Result<(), ProblemGenError> {
+        if !self.o_per_level.is_empty() {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "o per level ({:?}) can't be written to the parameters line of a problem or codomain file",
+                self.o_per_level
+            )));
+        }
+        Ok(())
     }
 
-    pub fn new_from_primitives(m: u32, k: u32, o: u32, b: u32) -> InputParameters {
-        InputParameters { m, k, o, b }
+    ///Set the number of overlapping variables per level of the clique tree, see `o_per_level`.
+    /// Returns an error if the number of overlapping variables of any level is not smaller than k.
+    pub fn with_o_per_level(
+        mut self,
+        o_per_level: Vec<u32>,
+    ) -> Result<InputParameters, ProblemGenError> {
+        if let Some((level, o)) = o_per_level
+            .iter()
+            .enumerate()
+            .find(|(_, &o)| o >= self.k)
+        {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "o of level {} ({}) should be smaller than k ({})",
+                level + 1,
+                o,
+                self.k
+            )));
+        }
+        self.o_per_level = o_per_level;
+        Ok(self)
+    }
+
+    ///Get the input parameters with the branching factor b of the given topology.
+    /// The balanced topology keeps b, the path topology uses b = 1, and the star topology uses b = M - 1 (at least 1).
+    pub fn with_topology(mut self, topology: Topology) -> InputParameters {
+        self.b = match topology {
+            Topology::Balanced => self.b,
+            Topology::Path => 1,
+            Topology::Star => self.m.saturating_sub(1).max(1),
+        };
+        self
     }
 
     ///Get the input parameters from an iterator containing the line on which the parameters are listed
     pub fn from_line_iterator(
         content_iterator: &mut Lines,
-    ) -> Result<InputParameters, Box<dyn Error>> {
+    ) -> Result<InputParameters, ProblemGenError> {
         //Get the line that contains the parameters
-        let line = content_iterator
-            .next()
-            .ok_or("Input file does not contain enough entries")?;
+        let line = content_iterator.next().ok_or_else(|| {
+            ProblemGenError::InvalidParameters(
+                "Input file does not contain enough entries".to_owned(),
+            )
+        })?;
         //Split the line
         let parameters: Vec<&str> = line.split(' ').collect();
         if parameters.len() != 4 {
-            return Err("not enough input parameters on first line of input file".into());
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "expected 4 input parameters (M k o b) on the parameters line, found {}",
+                parameters.len()
+            )));
         }
         //And set the parameters
         let m: u32 = parameters[0].parse()?;
@@ -83,20 +198,136 @@ impl InputParameters {
         let o: u32 = parameters[2].parse()?;
         let b: u32 = parameters[3].parse()?;
 
-        Ok(InputParameters::new_from_primitives(m, k, o, b))
+        let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
+        input_parameters.validate()?;
+        Ok(input_parameters)
+    }
+
+    ///Get the input parameters from a compact string, either as "MxKxOxB" (e.g. "10x3x1x2")
+    /// or as comma-separated named values (e.g. "m=10,k=3,o=1,b=2", in any order)
+    pub fn from_compact_str(compact_str: &str) -> Result<InputParameters, ProblemGenError> {
+        let compact_str = compact_str.trim();
+        //The values of M, k, o, and b, in that order
+        let mut values: [Option<u32>; 4] = [None; 4];
+
+        if compact_str.contains('=') {
+            for named_value in compact_str.split(',') {
+                let (name, value) = named_value.split_once('=').ok_or_else(|| {
+                    ProblemGenError::InvalidParameters(format!(
+                        "expected name=value, found {}",
+                        named_value
+                    ))
+                })?;
+                let name = name.trim();
+                let index = match name {
+                    "m" | "M" => 0,
+                    "k" => 1,
+                    "o" => 2,
+                    "b" => 3,
+                    _ => {
+                        return Err(ProblemGenError::InvalidParameters(format!(
+                            "unknown input parameter {}",
+                            name
+                        )))
+                    }
+                };
+                if values[index].is_some() {
+                    return Err(ProblemGenError::InvalidParameters(format!(
+                        "input parameter {} is given more than once",
+                        name
+                    )));
+                }
+                values[index] = Some(parse_compact_value(name, value)?);
+            }
+        } else {
+            let tokens: Vec<&str> = compact_str.split('x').collect();
+            if tokens.len() != 4 {
+                return Err(ProblemGenError::InvalidParameters(format!(
+                    "expected 4 input parameters as MxKxOxB, found {}",
+                    tokens.len()
+                )));
+            }
+            for (index, (name, token)) in ["M", "k", "o", "b"].iter().zip(tokens).enumerate() {
+                values[index] = Some(parse_compact_value(name, token)?);
+            }
+        }
+
+        match values {
+            [Some(m), Some(k), Some(o), Some(b)] => {
+                Ok(InputParameters::new_from_primitives(m, k, o, b))
+            }
+            _ => {
+                let missing: Vec<&str> = ["M", "k", "o", "b"]
+                    .iter()
+                    .zip(values.iter())
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(name, _)| *name)
+                    .collect();
+                Err(ProblemGenError::InvalidParameters(format!(
+                    "missing input parameters: {}",
+                    missing.join(", ")
+                )))
+            }
+        }
+    }
+}
+
+///Topology of the clique tree, which determines the branching factor b that is used to construct it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    ///Every clique has b children, as far as there are cliques left
+    #[default]
+    Balanced,
+    ///Every clique has exactly one child, except for the last clique, so b = 1
+    Path,
+    ///The root has all other cliques as children, so b = M - 1
+    Star,
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "balanced" => Ok(Topology::Balanced),
+            "path" => Ok(Topology::Path),
+            "star" => Ok(Topology::Star),
+            _ => Err(format!(
+                "unknown topology '{}', expected 'balanced', 'path' or 'star'",
+                s
+            )),
+        }
     }
 }
 
+///Parse the value of an input parameter in a compact string, naming the input parameter if it can not be parsed
+fn parse_compact_value(name: &str, value: &str) -> Result<u32, ProblemGenError> {
+    value.trim().parse().map_err(|_| {
+        ProblemGenError::Parse(format!(
+            "could not parse input parameter {} from {}",
+            name, value
+        ))
+    })
+}
+
 #[repr(C)]
-#[derive(Debug)]
-///The CliqueTree struct with properties input parameters, clique variable indices, the used codomain function, codomain values, global optimum strings and score
+#[derive(Debug, Clone)]
+///The CliqueTree struct with properties input parameters, clique variable indices, separator variable indices, the used codomain function, codomain values, global optimum strings and score.
+/// Cloning makes a deep copy, including the calculated global optima, so a copy can be changed (e.g. with mutate_codomain)
+///  without changing the original and without recalculating the global optima of the copy.
 pub struct CliqueTree {
     pub input_parameters: InputParameters,
     pub codomain_function: CodomainFunction,
     pub cliques: Vec<Vec<u32>>,
+    pub separators: Vec<Vec<u32>>,
     pub codomain_values: Vec<Vec<f64>>,
     pub glob_optima_strings: Vec<Vec<u32>>,
     pub glob_optima_score: f64,
+    ///Epsilon below which two fitnesses are considered equal, DEFAULT_FITNESS_EPSILON by default
+    pub fitness_epsilon: f64,
+    ///Indices of the cliques that contain each variable, as returned by variable_clique_membership, used by the delta evaluation.
+    /// Calculated from the cliques on construction; it is private so that it can't get out of sync with them, see variable_cliques()
+    pub(crate) variable_cliques: Vec<Vec<u32>>,
 }
 
 impl CliqueTree {
@@ -105,6 +336,24 @@ impl CliqueTree {
         codomain_function: CodomainFunction,
         codomain_values: Vec<Vec<f64>>,
         rng: &mut ChaChaRng,
+    ) -> CliqueTree {
+        CliqueTree::new_with_fitness_epsilon(
+            input_parameters,
+            codomain_function,
+            codomain_values,
+            DEFAULT_FITNESS_EPSILON,
+            rng,
+        )
+    }
+
+    ///Construct the clique tree like `new`, but calculate the global optima with the given epsilon
+    /// below which two fitnesses are considered equal, instead of DEFAULT_FITNESS_EPSILON
+    pub fn new_with_fitness_epsilon(
+        input_parameters: InputParameters,
+        codomain_function: CodomainFunction,
+        codomain_values: Vec<Vec<f64>>,
+        fitness_epsilon: f64,
+        rng: &mut ChaChaRng,
     ) -> CliqueTree {
         //Create a new clique tree (as its cliques and separators)
         let (cliques, separators) = CliqueTree::construct(&input_parameters, rng);
@@ -116,32 +365,192 @@ impl CliqueTree {
             &codomain_values,
             &cliques,
             &separators,
+            fitness_epsilon,
         );
 
         let glob_optima_score = global_opt_tuples[0].1;
         let glob_optima_strings = global_opt_tuples.into_iter().map(|tuple| tuple.0).collect();
 
         // and return the resulting CliqueTree struct
-        CliqueTree {
+        let clique_tree = CliqueTree {
             input_parameters,
             codomain_function,
+            variable_cliques: get_variable_cliques(&cliques),
             cliques,
+            separators,
             codomain_values,
             glob_optima_strings,
             glob_optima_score,
-        }
+            fitness_epsilon,
+        };
+        debug!("{}", clique_tree.describe());
+        clique_tree
+    }
+
+    ///Generate the codomain values and construct the clique tree, with a random number generator created from the seed.
+    /// As the clique tree does not need a random number generator from the caller, clique trees can be built from multiple threads
+    ///  without sharing a random number generator. The result is the same as generating the codomain and calling `new`
+    ///  with one random number generator seeded with the seed.
+    pub fn new_seeded(
+        input_parameters: InputParameters,
+        codomain_function: CodomainFunction,
+        seed: u64,
+    ) -> CliqueTree {
+        let mut rng = get_rng(Some(seed));
+        let codomain_values = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+        CliqueTree::new(input_parameters, codomain_function, codomain_values, &mut rng)
     }
 
-    ///Construct the clique tree from the problem struct and codomain values
+    ///Construct the clique tree for the input parameters, with the codomain values read from a file that contains only the values,
+    /// such as the codomain of a published benchmark instance. The structure is generated with the random number generator.
+    pub fn from_parameters_and_codomain_file(
+        input_parameters: InputParameters,
+        codomain_file_path: &Path,
+        rng: &mut ChaChaRng,
+    ) -> Result<CliqueTree, ProblemGenError> {
+        input_parameters.validate()?;
+        let codomain_values = read_codomain_values_only(codomain_file_path, &input_parameters)?;
+        let codomain_function = CodomainFunction::FromFile {
+            codomain_file_path: codomain_file_path.to_path_buf(),
+        };
+        Ok(CliqueTree::new(input_parameters, codomain_function, codomain_values, rng))
+    }
+
+    ///Generate the codomain values and construct the clique tree, using separate seeds for the codomain and the structure.
+    /// The codomain values only depend on the codomain seed and the structure (cliques and separators) only depends on the structure seed,
+    ///  so one can be varied while the other is fixed. Note that the results differ from `new` with a shared random number generator,
+    ///  as there the codomain generation and the structure construction draw from the same stream.
+    pub fn new_with_seeds(
+        input_parameters: InputParameters,
+        codomain_function: CodomainFunction,
+        codomain_seed: u64,
+        structure_seed: u64,
+    ) -> CliqueTree {
+        let mut codomain_rng = get_rng(Some(codomain_seed));
+        let mut structure_rng = get_rng(Some(structure_seed));
+
+        let codomain_values =
+            generate_codomain(&input_parameters, &codomain_function, &mut codomain_rng);
+        CliqueTree::new(
+            input_parameters,
+            codomain_function,
+            codomain_values,
+            &mut structure_rng,
+        )
+    }
+
+    ///Write the problem to a problem file, in the same format as the problem generator: the input parameters,
+    /// the global optimum score, the number of global optima, the global optima strings, and the variable indices of every clique.
+    /// See the problem file structure in the documentation.
+    pub fn write_problem_file(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        write_problem_to_file(self, path)
+    }
+
+    ///Read a problem file as written by `write_problem_file` or the problem generator.
+    /// Combine it with the codomain values using `construct_from_problem_codomain` to get the clique tree.
+    pub fn read_problem_file(path: &Path) -> Result<Problem, ProblemGenError> {
+        read_problem_from_file(path)
+    }
+
+    ///Load the clique tree from a codomain file, detecting whether the file contains the codomain function.
+    /// The structure is constructed with a random number generator seeded with the given seed, or from entropy if no seed is given.
+    pub fn load(
+        codomain_file_path: &Path,
+        seed: Option<u64>,
+    ) -> Result<CliqueTree, ProblemGenError> {
+        let file_has_codomain_function = codomain_file_has_codomain_function(codomain_file_path)?;
+        let mut rng = get_rng(seed);
+        get_clique_tree_from_codomain_file(codomain_file_path, file_has_codomain_function, &mut rng)
+    }
+
+    ///Construct the clique tree from the problem struct and codomain values.
+    /// The separators are reconstructed from the cliques, and the global optima are recalculated if the problem does not contain any.
     pub fn construct_from_problem_codomain(problem: Problem, codomain: Vec<Vec<f64>>) -> Self {
+        let separators =
+            CliqueTree::reconstruct_separators(&problem.input_parameters, &problem.cliques);
+
+        let (glob_optima_strings, glob_optima_score) = if problem.glob_optima_strings.is_empty() {
+            let global_opt_tuples = CliqueTree::calculate_global_optima(
+                &problem.input_parameters,
+                &CodomainFunction::Unknown,
+                &codomain,
+                &problem.cliques,
+                &separators,
+                DEFAULT_FITNESS_EPSILON,
+            );
+            let glob_optima_score = global_opt_tuples[0].1;
+            (
+                global_opt_tuples.into_iter().map(|tuple| tuple.0).collect(),
+                glob_optima_score,
+            )
+        } else {
+            (problem.glob_optima_strings, problem.glob_optima_score)
+        };
+
         CliqueTree {
             input_parameters: problem.input_parameters,
             codomain_function: CodomainFunction::Unknown,
+            variable_cliques: get_variable_cliques(&problem.cliques),
             cliques: problem.cliques,
+            separators,
             codomain_values: codomain,
-            glob_optima_strings: problem.glob_optima_strings,
-            glob_optima_score: problem.glob_optima_score,
+            glob_optima_strings,
+            glob_optima_score,
+            fitness_epsilon: DEFAULT_FITNESS_EPSILON,
+        }
+    }
+
+    ///Get the branching factor used to construct the clique tree; separable problems are constructed as a chain (b = 1)
+    fn get_branching_factor(input_parameters: &InputParameters) -> u32 {
+        input_parameters.branching_factor()
+    }
+
+    ///Get the indices of the children of the clique at the given index, derived from M and b:
+    /// the children of clique p are the cliques 1 + p * b up to (excl.) 1 + (p + 1) * b, as far as they exist.
+    pub fn get_child_indices(input_parameters: &InputParameters, clique_index: u32) -> Range<u32> {
+        let b = CliqueTree::get_branching_factor(input_parameters);
+        let start_index_children = (1 + clique_index * b).min(input_parameters.m);
+        start_index_children..(start_index_children + b).min(input_parameters.m)
+    }
+
+    ///Get the index of the parent of the clique at the given index, derived from b, or None for the root
+    pub fn get_parent_index(input_parameters: &InputParameters, clique_index: u32) -> Option<u32> {
+        input_parameters.parent_index(clique_index)
+    }
+
+    ///Get the indices of the children of the clique at the given index
+    pub fn children(&self, clique_index: u32) -> Vec<u32> {
+        CliqueTree::get_child_indices(&self.input_parameters, clique_index).collect()
+    }
+
+    ///Get the index of the parent of the clique at the given index, or None for the root
+    pub fn parent(&self, clique_index: u32) -> Option<u32> {
+        CliqueTree::get_parent_index(&self.input_parameters, clique_index)
+    }
+
+    ///Reconstruct the separators from the cliques, using the same branching arithmetic as `construct`.
+    /// The separator of a child clique consists of the variables it shares with its parent, in the order of the child clique.
+    /// The first separator is a filler, as the root has no separator.
+    pub fn reconstruct_separators(
+        input_parameters: &InputParameters,
+        cliques: &[Vec<u32>],
+    ) -> Vec<Vec<u32>> {
+        let mut separators = Vec::with_capacity(cliques.len());
+        separators.push(Vec::new()); //filler, there is no separator 0!
+
+        for (i, clique) in cliques.iter().enumerate().skip(1) {
+            let parent_index = CliqueTree::get_parent_index(input_parameters, i as u32)
+                .expect("only the root has no parent");
+            let parent_clique = &cliques[parent_index as usize];
+            let separator = clique
+                .iter()
+                .filter(|variable_index| parent_clique.contains(variable_index))
+                .cloned()
+                .collect();
+            separators.push(separator);
         }
+
+        separators
     }
 
     ///Calculate the global optimum for a separable problem
@@ -149,45 +558,55 @@ impl CliqueTree {
         input_parameters: &InputParameters,
         codomain_values: &[Vec<f64>],
         cliques: &[Vec<u32>],
+        fitness_epsilon: f64,
     ) -> Vec<(Vec<u32>, f64)> {
-        //Set score to 0 and glob_optimum string to all zeroes.
+        //The global optimum score is the sum of the highest scores of the cliques, which can be negative
         let mut glob_opt_score = 0.0;
 
         //Store the optimas per clique. The optima are stored as a number whose bit representation is the actual solution substring.
         let mut clique_optimas = Vec::with_capacity(input_parameters.m as usize);
 
-        let mut number_global_optima_strings = 1;
+        //Count the number of global optima, saturating instead of overflowing for highly symmetric problems
+        let mut number_global_optima_strings: u128 = 1;
+        //Count the number of global optima we will actually construct, which is limited to prevent impossible allocations
+        let mut number_materialized_optima_strings: usize = 1;
 
         //Go over all 'cliques/subfunctions'
         for i in 0..input_parameters.m {
-            //Set the current highest score for this subfunction to the string with all zeroes.
-            let mut highest_score = codomain_values[i as usize][0];
-            let mut highest_score_indices = vec![0];
-
-            //Go over the rest of the possible permutations of the string.
-            for j in 1..(1 << input_parameters.k) as usize {
-                //And determine whether they have a higher score
-                let score = codomain_values[i as usize][j as usize];
-                if is_equal_fitness(score, highest_score) {
-                    highest_score_indices.push(j as u32);
-                } else if is_better_fitness(score, highest_score) {
-                    highest_score = score;
-                    highest_score_indices.clear();
-                    highest_score_indices.push(j as u32);
-                }
-            }
+            let (highest_score, mut highest_score_indices) = get_clique_optima(
+                &codomain_values[i as usize],
+                input_parameters.k,
+                fitness_epsilon,
+            );
 
             //Add the highest score to the global optimum score
             glob_opt_score += highest_score;
 
             //Calculate the number of global optima
-            number_global_optima_strings *= highest_score_indices.len() as u32;
+            number_global_optima_strings =
+                number_global_optima_strings.saturating_mul(highest_score_indices.len() as u128);
+
+            //If constructing all of this clique's optima would exceed the limit, only use its first optimum
+            if number_materialized_optima_strings.saturating_mul(highest_score_indices.len())
+                > MAX_MATERIALIZED_GLOBAL_OPTIMA
+            {
+                highest_score_indices.truncate(1);
+            }
+            number_materialized_optima_strings *= highest_score_indices.len();
+
             //And push this clique's optima to the clique_optima list
             clique_optimas.push(highest_score_indices);
         }
 
+        if number_global_optima_strings > number_materialized_optima_strings as u128 {
+            warn!(
+                "Number of global optima (saturated at u128::MAX) is {}, only constructing {} of them",
+                number_global_optima_strings, number_materialized_optima_strings
+            );
+        }
+
         //Construct the global optima strings. First reserve space equal to the number of global optima, then add a first element.
-        let mut result_optima_strings = Vec::with_capacity(number_global_optima_strings as usize);
+        let mut result_optima_strings = Vec::with_capacity(number_materialized_optima_strings);
         result_optima_strings.push(vec![0; (input_parameters.m * input_parameters.k) as usize]);
 
         //Construct the global optima
@@ -206,6 +625,24 @@ impl CliqueTree {
             .collect()
     }
 
+    ///Count the number of global optima for a separable problem, which is the product of the number of optima per clique.
+    /// The count saturates at u128::MAX instead of overflowing.
+    pub fn count_global_optima_separable(
+        input_parameters: &InputParameters,
+        codomain_values: &[Vec<f64>],
+        fitness_epsilon: f64,
+    ) -> u128 {
+        codomain_values
+            .iter()
+            .take(input_parameters.m as usize)
+            .map(|clique_codomain| {
+                get_clique_optima(clique_codomain, input_parameters.k, fitness_epsilon)
+                    .1
+                    .len() as u128
+            })
+            .fold(1, |count, clique_count| count.saturating_mul(clique_count))
+    }
+
     ///Construct the global optima, by inserting a clique's optimal substrings into the global optima strings and calling itself recursively for the next clique.
     ///When there are more than one optimal substrings for a clique, we clone the current global optima and then set all the values.
     fn set_optimal_clique_substrings(
@@ -254,28 +691,104 @@ impl CliqueTree {
         );
     }
 
-    ///Calculate the global optima strings and fitnesses
+    ///Calculate the global optima strings and fitnesses.
+    /// When the clique tree has at least MIN_PARALLEL_LEAVES leaves, the best scores of the leaves are calculated in parallel.
+    /// The global optima are sorted lexicographically, so the first one, which is written to the problem file as the representative optimum,
+    ///  is the smallest optimum, regardless of the order in which the optima are enumerated.
     pub fn calculate_global_optima(
         input_parameters: &InputParameters,
         codomain_function: &CodomainFunction,
         codomain_values: &[Vec<f64>],
         cliques: &[Vec<u32>],
         separators: &[Vec<u32>],
+        fitness_epsilon: f64,
+    ) -> Vec<(Vec<u32>, f64)> {
+        CliqueTree::calculate_global_optima_with_parallelism(
+            input_parameters,
+            codomain_function,
+            codomain_values,
+            cliques,
+            separators,
+            fitness_epsilon,
+            true,
+        )
+    }
+
+    ///Calculate the global optima strings and fitnesses, processing all cliques sequentially.
+    /// The result is identical to that of `calculate_global_optima`.
+    pub fn calculate_global_optima_sequential(
+        input_parameters: &InputParameters,
+        codomain_function: &CodomainFunction,
+        codomain_values: &[Vec<f64>],
+        cliques: &[Vec<u32>],
+        separators: &[Vec<u32>],
+        fitness_epsilon: f64,
+    ) -> Vec<(Vec<u32>, f64)> {
+        CliqueTree::calculate_global_optima_with_parallelism(
+            input_parameters,
+            codomain_function,
+            codomain_values,
+            cliques,
+            separators,
+            fitness_epsilon,
+            false,
+        )
+    }
+
+    ///Calculate the global optima strings and fitnesses, calculating the best scores of the leaves in parallel if parallel_leaves is set
+    /// and there are at least MIN_PARALLEL_LEAVES leaves. The leaves have no children, so their best scores only depend on their own codomain.
+    /// The cliques with children still have to wait for their children, so they are processed sequentially, in reversed order.
+    fn calculate_global_optima_with_parallelism(
+        input_parameters: &InputParameters,
+        codomain_function: &CodomainFunction,
+        codomain_values: &[Vec<f64>],
+        cliques: &[Vec<u32>],
+        separators: &[Vec<u32>],
+        fitness_epsilon: f64,
+        parallel_leaves: bool,
+    ) -> Vec<(Vec<u32>, f64)> {
+        let mut global_optima = CliqueTree::enumerate_global_optima(
+            input_parameters,
+            codomain_function,
+            codomain_values,
+            cliques,
+            separators,
+            fitness_epsilon,
+            parallel_leaves,
+        );
+        //Break the ties between the optima deterministically, the order of enumeration depends on the shuffled variables.
+        // If not all optima of a separable problem are constructed, this is the smallest of the constructed optima.
+        global_optima.sort_by(|optimum, other_optimum| optimum.0.cmp(&other_optimum.0));
+        global_optima
+    }
+
+    ///Enumerate the global optima strings and fitnesses, in the order in which they are found
+    fn enumerate_global_optima(
+        input_parameters: &InputParameters,
+        codomain_function: &CodomainFunction,
+        codomain_values: &[Vec<f64>],
+        cliques: &[Vec<u32>],
+        separators: &[Vec<u32>],
+        fitness_epsilon: f64,
+        parallel_leaves: bool,
     ) -> Vec<(Vec<u32>, f64)> {
         //If the problem is separable, we use a simple optimizer.
-        if input_parameters.o == 0 {
+        if input_parameters.is_separable() {
             return CliqueTree::calculate_global_optimum_separable(
                 input_parameters,
                 codomain_values,
                 cliques,
+                fitness_epsilon,
             );
         }
 
         //Capacity set to 2 right now, as I assume the number of global optima is low;
         // every time there are more than 2 C/S instances with the same score for a given seperator instance,
         // we will need to allocate memory, which is unwanted. Better be safe than sorry here.
-        let size_per_separator_instance = if let CodomainFunction::NKq { q: _ } = codomain_function
-        {
+        let size_per_separator_instance = if matches!(
+            codomain_function,
+            CodomainFunction::NKq { .. } | CodomainFunction::NKqSigned { .. }
+        ) {
             1 << (input_parameters.k - input_parameters.o)
         } else {
             2
@@ -285,7 +798,7 @@ impl CliqueTree {
         //   the best strings with their score for each x_a and x_b value
         //possible TODO: Can't we store the index of the substring instead of the substring, i.e. u32 instead of Vec<u32>?
         //This should make sure that the inner vectors are initialized
-        let mut best_scores: Vec<Vec<Vec<(Vec<u32>, f64)>>> =
+        let mut best_scores: Vec<Vec<Vec<SubstringScore>>> =
             vec![
                 vec![
                     Vec::with_capacity(size_per_separator_instance);
@@ -294,46 +807,43 @@ impl CliqueTree {
                 input_parameters.m as usize
             ];
 
-        //Determine number of levels to detect whether a clique has any children, and how to reach that child.
-        //Also store the start indices for each level
-        let mut sum = 0;
-        let mut l = 0;
-        let mut start_indices = Vec::new();
-        while sum < input_parameters.m {
-            start_indices.push(sum);
-            sum += input_parameters.b.pow(l);
-            l += 1;
-        }
-
-        //Set lowest level and its start index
-        let start_index_lowest_level = sum - input_parameters.b.pow(l - 1);
-        let lowest_level = l - 1;
-
-        //Set current level and its start index
-        let mut start_index_current_level = start_index_lowest_level;
-        let mut current_level = lowest_level;
+        //The separator size of every clique, which only differs between cliques if o varies per level
+        let separator_sizes: Vec<u32> = (0..input_parameters.m)
+            .map(|i| input_parameters.separator_size(i))
+            .collect();
 
         //Calculate all possible substrings, so that we can easily store and retrieve the substrings for the given index.
         // This way, we don't need to use intermediate representations that use the substrings, but simply an index that points to the substring.
+        //The separator substrings and the clique substrings without the separator are calculated for every separator size o that occurs.
         let possible_clique_substrings = get_possible_substrings(input_parameters.k);
-        let possible_separator_substrings = get_possible_substrings(input_parameters.o);
-        let possible_clique_without_separator_substrings =
-            get_possible_substrings(input_parameters.k - input_parameters.o);
-
-        //Go over all nodes but the root, in reversed order.
-        for i in (1..input_parameters.m).rev() {
-            //Keep track of current level in the tree, and the current start index for that level
-            if i < start_index_current_level {
-                current_level -= 1;
-                start_index_current_level = start_indices[current_level as usize];
+        let mut possible_substrings_by_length: HashMap<u32, Vec<Vec<u32>>> = HashMap::new();
+        for &o in separator_sizes.iter().skip(1) {
+            for length in [o, input_parameters.k - o] {
+                possible_substrings_by_length
+                    .entry(length)
+                    .or_insert_with(|| get_possible_substrings(length));
             }
+        }
+
+        //Calculate the best scores of the clique at index i for all separator values, given the best scores of its children.
+        let calculate_clique_best_scores = |i: u32, best_scores: &[Vec<Vec<SubstringScore>>]| {
+            let children = CliqueTree::get_child_indices(input_parameters, i);
+            let o = separator_sizes[i as usize];
+            let possible_separator_substrings = &possible_substrings_by_length[&o];
+            let possible_clique_without_separator_substrings =
+                &possible_substrings_by_length[&(input_parameters.k - o)];
+            let mut clique_best_scores: Vec<Vec<SubstringScore>> = vec![
+                Vec::with_capacity(size_per_separator_instance);
+                possible_separator_substrings.len()
+            ];
 
             //Iterate over all possible values for the separator, so that we can calculate h_i(x_a, x_b) for these values (of x_a and x_b).
             for j in 0..possible_separator_substrings.len() {
                 //Keep track of highest score and the highest scoring Ci/Si values, for these Si values (j)
                 //TODONE: replace this with another value as soon as we allow for multiple global optima. I can make these quite a bit bigger, as it's a small structure.
-                let mut scores = Vec::with_capacity(1 << (input_parameters.k - input_parameters.o));
-                let mut highest_score = 0.0;
+                let mut scores = Vec::with_capacity(1 << (input_parameters.k - o));
+                //Start below every score, so that the first score is accepted, also when all scores are negative
+                let mut highest_score = f64::NEG_INFINITY;
                 //Iterate over all possible values for Ci/Si. Store the score in the list if it has a higher score than the current highest score.
                 for k in 0..possible_clique_without_separator_substrings.len() {
                     //Calculate f(x_p x_q x_r), which is given by the codomain values passed as input.
@@ -341,43 +851,37 @@ impl CliqueTree {
                     let mut score = codomain_values[i as usize]
                         [j * possible_clique_without_separator_substrings.len() + k]; //f
                                                                                       //Then, if it's a parent, add h_l for each child l.
-                    if i < start_index_lowest_level {
-                        let start_index_children = start_indices[(current_level + 1) as usize]
-                            + input_parameters.b * (i - start_index_current_level);
-                        for child_index in
-                            start_index_children..(start_index_children + input_parameters.b)
-                        {
-                            //Make sure child exists!
-                            if child_index >= input_parameters.m {
-                                break;
-                            }
-                            //Maakt niet uit welke optie we kiezen toch? Want ze hebben allemaal dezelfde score en er hoeft verder nog niet gebrancht te worden,
-                            // het enige dat belangrijk is, is dat we de hoogste score selecteren. Toch? Daarna kunnen we aangeven dat er meerdere globale optima zijn.
-                            //Calculate the separator substring values for the current child, from the parent clique substring.
-                            let separator_substring = get_child_separator_substring(
-                                &cliques[i as usize],
-                                &separators[child_index as usize],
-                                &possible_clique_substrings
-                                    [j * possible_clique_without_separator_substrings.len() + k],
-                            );
-                            //separators shouldn't break here, as we have now inserted a filler for 'separator 0', which doesn't exist,
-                            // so everything should be aligned well.
-                            //Add the h_l for this child l to the parent's score, by first transforming into an index variant (easier storage) and
-                            // then retrieving the stored score of the child using the separator substring index.
-                            let separator_substring_index_version =
-                                transform_substring_vector_to_index(&separator_substring);
-                            score += best_scores[child_index as usize]
-                                [separator_substring_index_version as usize][0]
-                                .1;
-                            //h_child
-                        }
+                    for child_index in children.clone() {
+                        //Maakt niet uit welke optie we kiezen toch? Want ze hebben allemaal dezelfde score en er hoeft verder nog niet gebrancht te worden,
+                        // het enige dat belangrijk is, is dat we de hoogste score selecteren. Toch? Daarna kunnen we aangeven dat er meerdere globale optima zijn.
+                        //Calculate the separator substring values for the current child, from the parent clique substring.
+                        let separator_substring = get_child_separator_substring(
+                            &cliques[i as usize],
+                            &separators[child_index as usize],
+                            &possible_clique_substrings
+                                [j * possible_clique_without_separator_substrings.len() + k],
+                        );
+                        //separators shouldn't break here, as we have now inserted a filler for 'separator 0', which doesn't exist,
+                        // so everything should be aligned well.
+                        //Add the h_l for this child l to the parent's score, by first transforming into an index variant (easier storage) and
+                        // then retrieving the stored score of the child using the separator substring index.
+                        let separator_substring_index_version =
+                            transform_substring_vector_to_index(&separator_substring);
+                        score += best_scores[child_index as usize]
+                            [separator_substring_index_version as usize][0]
+                            .1;
+                        //h_child
                     }
                     //store temporarily highest score in scores
                     //This already allows for multiple highest scores
-                    if !scores.is_empty() && is_better_fitness(score, highest_score) {
+                    if is_better_fitness_with_epsilon(score, highest_score, fitness_epsilon) {
                         scores.clear();
                     }
-                    if scores.is_empty() || is_better_or_equal_fitness(score, highest_score) {
+                    if is_better_or_equal_fitness_with_epsilon(
+                        score,
+                        highest_score,
+                        fitness_epsilon,
+                    ) {
                         //TODO: Here I could store k instead of the substring!
                         scores.push((
                             possible_clique_without_separator_substrings[k].clone(),
@@ -390,9 +894,35 @@ impl CliqueTree {
                 //store the highest score into h for that separator (i) and for these values of the separator(j)
                 for tuple in scores.into_iter() {
                     //This shouldn't break anymore, as we should now have initialized the inner array (j as usize)
-                    best_scores[i as usize][j as usize].push(tuple);
+                    clique_best_scores[j].push(tuple);
                 }
             }
+            clique_best_scores
+        };
+
+        //The children of a clique have higher indices, so all cliques from the first leaf onwards are leaves
+        let first_leaf_index = (1..input_parameters.m)
+            .find(|&i| CliqueTree::get_child_indices(input_parameters, i).is_empty())
+            .unwrap_or(input_parameters.m);
+        let first_sequential_leaf_index = if parallel_leaves
+            && (input_parameters.m - first_leaf_index) as usize >= MIN_PARALLEL_LEAVES
+        {
+            let leaf_best_scores: Vec<Vec<Vec<SubstringScore>>> = (first_leaf_index
+                ..input_parameters.m)
+                .into_par_iter()
+                .map(|i| calculate_clique_best_scores(i, &best_scores))
+                .collect();
+            for (i, clique_best_scores) in (first_leaf_index..).zip(leaf_best_scores) {
+                best_scores[i as usize] = clique_best_scores;
+            }
+            first_leaf_index
+        } else {
+            input_parameters.m
+        };
+
+        //Go over all remaining nodes but the root, in reversed order.
+        for i in (1..first_sequential_leaf_index).rev() {
+            best_scores[i as usize] = calculate_clique_best_scores(i, &best_scores);
         }
 
         //Now we need to process the root to get the global optimum, which is just one more calculation,
@@ -400,25 +930,20 @@ impl CliqueTree {
 
         //Store the scores again in a list
         let mut scores = Vec::with_capacity(1 << input_parameters.k);
-        let mut highest_score = 0.0;
+        //Start below every score, so that the first score is accepted, also when all scores are negative
+        let mut highest_score = f64::NEG_INFINITY;
 
         //Iterate over all possible clique substrings / values for the root
         for c in 0..possible_clique_substrings.len() {
             //I assume codomain is structured [M][k] = score
             //Add f
-            let mut score = codomain_values[0][c as usize]; //f
+            let mut score = codomain_values[0][c]; //f
 
             //Add the h_l scores for each child l.
-            let start_index_children = 1;
-            for child_index in start_index_children..(start_index_children + input_parameters.b) {
+            for child_index in CliqueTree::get_child_indices(input_parameters, 0) {
                 //Maakt niet uit welke optie we kiezen toch? Want ze hebben allemaal dezelfde score en er hoeft verder nog niet gebrancht te worden,
                 // het enige dat belangrijk is, is dat we de hoogste score selecteren. Toch? Daarna kunnen we aangeven dat er meerdere globale optima zijn.
 
-                //Make sure child exists!
-                if child_index >= input_parameters.m {
-                    break;
-                }
-
                 //Calculate the separator substring values for the current child, from the parent clique substring.
                 let separator_substring = get_child_separator_substring(
                     &cliques[0],
@@ -436,10 +961,10 @@ impl CliqueTree {
 
             //store temporarily highest score in scores
             //This already allows for multiple highest scores
-            if !scores.is_empty() && is_better_fitness(score, highest_score) {
+            if is_better_fitness_with_epsilon(score, highest_score, fitness_epsilon) {
                 scores.clear();
             }
-            if scores.is_empty() || is_better_or_equal_fitness(score, highest_score) {
+            if is_better_or_equal_fitness_with_epsilon(score, highest_score, fitness_epsilon) {
                 //TODO: Here I could store k instead of the substring!
                 scores.push((possible_clique_substrings[c].clone(), score));
                 highest_score = score;
@@ -456,8 +981,7 @@ impl CliqueTree {
         //possible TODO: Count the number of multiple maximizing instances so that we can make
         //          an estimate of the number of global optima. I can just use a high number, as the structure is quite small and won't take much space
 
-        let problem_size = (input_parameters.m - 1) * (input_parameters.k - input_parameters.o)
-            + input_parameters.k;
+        let problem_size = input_parameters.problem_size();
 
         //initialize string that will store resulting global optimum string to zeroes
         let mut glob_opt_strings = Vec::with_capacity(40);
@@ -479,8 +1003,8 @@ impl CliqueTree {
         for clique_opt in &scores {
             let mut new_glob_opt_string = vec![0; problem_size as usize];
             for index_in_clique in 0..input_parameters.k as usize {
-                new_glob_opt_string[cliques[0][index_in_clique as usize] as usize] =
-                    clique_opt.0[index_in_clique as usize];
+                new_glob_opt_string[cliques[0][index_in_clique] as usize] =
+                    clique_opt.0[index_in_clique];
             }
             glob_opt_strings.push(new_glob_opt_string);
         }
@@ -489,145 +1013,106 @@ impl CliqueTree {
         //        scores[0].0[index_in_clique as usize];
         //}
 
-        //Set level and start index to the first clique, as we're starting from the root and iterate to the end
-        start_index_current_level = 0;
-        current_level = 0;
-
-        //Calculate the end of the loop
-        let mut division = (input_parameters.m - 1) / input_parameters.b;
-        if (input_parameters.m - 1) % input_parameters.b > 0 {
-            division += 1;
-        }
-
-        //Go until latest node/clique with children
-        for i in 0..division {
-            if (current_level as usize) < (start_indices.len() - 1) {
-                //Increase the current level in the tree when the considered index is at the next level's start index
-                if i >= start_indices[(current_level + 1) as usize] {
-                    current_level += 1;
-                    start_index_current_level = start_indices[current_level as usize];
-                }
-
-                //Calculate the start_index for this clique's children
-                let start_index_children = start_indices[(current_level + 1) as usize]
-                    + input_parameters.b * (i - start_index_current_level);
-
-                //Go over all its b children
-                for j in 0..input_parameters.b {
-                    //Break if the index of the child to consider goes out of the M range
-                    if (i * input_parameters.b) + j >= input_parameters.m - 1 {
-                        break;
-                    }
-
-                    //Get current considered child's index
-                    let current_child_index = start_index_children + j;
-
-                    //For all current global optimum strings, either fill in the only maximizing instance for this separator instance,
-                    // or clone the global optimum string x times, for the x maximizing instances of this separator instance.
-                    let glob_opt_strings_length = glob_opt_strings.len();
-                    let mut glob_opt_strings_marked_deletion =
-                        Vec::with_capacity(glob_opt_strings_length);
-                    for k in 0..glob_opt_strings_length {
-                        let glob_opt_string = &mut glob_opt_strings[k];
-
-                        //Construct child's separator values using the global string values and the stored indices of the separator.
-                        let separator_substring = get_separator_substring_from_string(
-                            &separators[current_child_index as usize],
-                            glob_opt_string,
+        //Go over all cliques from the root to the end, so that a clique's values are set before those of its children
+        for i in 0..input_parameters.m {
+            //Go over all its children
+            for current_child_index in CliqueTree::get_child_indices(input_parameters, i) {
+                //For all current global optimum strings, either fill in the only maximizing instance for this separator instance,
+                // or clone the global optimum string x times, for the x maximizing instances of this separator instance.
+                let glob_opt_strings_length = glob_opt_strings.len();
+                let mut glob_opt_strings_marked_deletion =
+                    Vec::with_capacity(glob_opt_strings_length);
+                for k in 0..glob_opt_strings_length {
+                    let glob_opt_string = &mut glob_opt_strings[k];
+
+                    //Construct child's separator values using the global string values and the stored indices of the separator.
+                    let separator_substring = get_separator_substring_from_string(
+                        &separators[current_child_index as usize],
+                        glob_opt_string,
+                    );
+
+                    //Get index for that substring, to index into h
+                    let separator_substring_index_version =
+                        transform_substring_vector_to_index(&separator_substring);
+
+                    //For each maximizing instance for the given separator instance, clone the global string and
+                    // set the maximizing instance values. These maximizing instance values are retrieved from h
+                    //Get best tuple for that child's separator values from h:
+                    let c_without_s_substrings: Vec<&Vec<u32>> = best_scores
+                        [current_child_index as usize]
+                        [separator_substring_index_version as usize]
+                        .iter()
+                        .map(|tuple| &tuple.0)
+                        .collect();
+
+                    //Remove the item currently in consideration? (check if loops don't break then)
+                    // Then clone it a number of times equal to the number of maximizing instances for this separator,
+                    //  and assign the bits from the maximizing instances.
+
+                    //If there is just one maximizing instance for this seperator,
+                    // then just insert the values for this instance into the current global optimum string
+                    let number_maximizing_instances = c_without_s_substrings.len();
+                    let o = separator_sizes[current_child_index as usize];
+                    if number_maximizing_instances == 1 {
+                        //Insert Ci/Si values into global optimum string
+                        for index in 0..(input_parameters.k - o) {
+                            glob_opt_string[cliques[current_child_index as usize]
+                                [(index + o) as usize]
+                                as usize] = c_without_s_substrings[0][index as usize];
+                        }
+                    } else {
+                        //otherwise, clone the global optimum under consideration x times, where x is equal to the number of maximizing instances
+                        // for this clique.
+
+                        // make sure there are more than 0 maximizing instances
+                        assert_ne!(
+                            number_maximizing_instances, 0,
+                            "there are 0 maximizing instances, which is impossible"
                         );
 
-                        //Get index for that substring, to index into h
-                        let separator_substring_index_version =
-                            transform_substring_vector_to_index(&separator_substring);
-
-                        //For each maximizing instance for the given separator instance, clone the global string and
-                        // set the maximizing instance values. These maximizing instance values are retrieved from h
-                        //Get best tuple for that child's separator values from h:
-                        let c_without_s_substrings: Vec<&Vec<u32>> = (&best_scores
-                            [current_child_index as usize]
-                            [separator_substring_index_version as usize])
-                            .iter()
-                            .map(|tuple| &tuple.0)
-                            .collect();
-
-                        //Remove the item currently in consideration? (check if loops don't break then)
-                        // Then clone it a number of times equal to the number of maximizing instances for this separator,
-                        //  and assign the bits from the maximizing instances.
-
-                        //If there is just one maximizing instance for this seperator,
-                        // then just insert the values for this instance into the current global optimum string
-                        let number_maximizing_instances = c_without_s_substrings.len();
-                        if number_maximizing_instances == 1 {
-                            //Insert Ci/Si values into global optimum string
-                            for index in 0..(input_parameters.k - input_parameters.o) {
-                                glob_opt_string[cliques[current_child_index as usize]
-                                    [(index + input_parameters.o) as usize]
-                                    as usize] = c_without_s_substrings[0][index as usize];
-                            }
-                        } else {
-                            //otherwise, clone the global optimum under consideration x times, where x is equal to the number of maximizing instances
-                            // for this clique.
-
-                            // make sure there are more than 0 maximizing instances
-                            assert_ne!(
-                                number_maximizing_instances, 0,
-                                "there are 0 maximizing instances, which is impossible"
-                            );
-
-                            //direct naar glob_opt_strings pushen ipv eerst naar nieuwe array? -> Dit kan niet, doordat we nog een mutable borrow in scope hebben
-                            //Clone the global optimum string under consideration and add to vector
-                            let mut new_glob_opt_strings =
-                                Vec::with_capacity(number_maximizing_instances);
-                            for _l in 0..number_maximizing_instances {
-                                new_glob_opt_strings.push(glob_opt_string.clone());
-                            }
+                        //direct naar glob_opt_strings pushen ipv eerst naar nieuwe array? -> Dit kan niet, doordat we nog een mutable borrow in scope hebben
+                        //Clone the global optimum string under consideration and add to vector
+                        let mut new_glob_opt_strings =
+                            Vec::with_capacity(number_maximizing_instances);
+                        for _l in 0..number_maximizing_instances {
+                            new_glob_opt_strings.push(glob_opt_string.clone());
+                        }
 
-                            //For each maximizing instance, write the maximizing values to one of the cloned global optimum strings
-                            for (num, maximizing_instance) in
-                                c_without_s_substrings.iter().enumerate()
-                            {
-                                for index in 0..(input_parameters.k - input_parameters.o) {
-                                    new_glob_opt_strings[num][cliques[current_child_index as usize]
-                                        [(index + input_parameters.o) as usize]
-                                        as usize] = maximizing_instance[index as usize];
-                                }
+                        //For each maximizing instance, write the maximizing values to one of the cloned global optimum strings
+                        for (num, maximizing_instance) in c_without_s_substrings.iter().enumerate()
+                        {
+                            for index in 0..(input_parameters.k - o) {
+                                new_glob_opt_strings[num][cliques[current_child_index as usize]
+                                    [(index + o) as usize]
+                                    as usize] = maximizing_instance[index as usize];
                             }
-
-                            //Append the newly created global optimum strings to the global optimum strings vector,
-                            // and mark the global optimum string currenly under consideration as to be deleted.
-                            glob_opt_strings.append(&mut new_glob_opt_strings);
-                            glob_opt_strings_marked_deletion.push(k);
                         }
-                    }
 
-                    //Remove the global optimum strings that were marked as to be deleted,
-                    // in reversed order, as we want to make sure that the indices correctly point to the strings to be deleted
-                    for marked_index in glob_opt_strings_marked_deletion.into_iter().rev() {
-                        glob_opt_strings.remove(marked_index);
+                        //Append the newly created global optimum strings to the global optimum strings vector,
+                        // and mark the global optimum string currenly under consideration as to be deleted.
+                        glob_opt_strings.append(&mut new_glob_opt_strings);
+                        glob_opt_strings_marked_deletion.push(k);
                     }
                 }
+
+                //Remove the global optimum strings that were marked as to be deleted,
+                // in reversed order, as we want to make sure that the indices correctly point to the strings to be deleted
+                for marked_index in glob_opt_strings_marked_deletion.into_iter().rev() {
+                    glob_opt_strings.remove(marked_index);
+                }
             }
         }
 
         for i in 1..input_parameters.m {
-            for j in 0..(1 << input_parameters.o) {
+            for (j, separator_best_scores) in best_scores[i as usize].iter().enumerate() {
                 debug!(
                     "Best score for clique {:?} for index {:?}: {:?} with score {:?}",
-                    i,
-                    j,
-                    best_scores[i as usize][j as usize][0].0,
-                    best_scores[i as usize][j as usize][0].1
+                    i, j, separator_best_scores[0].0, separator_best_scores[0].1
                 );
             }
         }
 
         let glob_opt_score = scores.swap_remove(0).1;
-        for glob_opt_string in &glob_opt_strings {
-            debug!(
-                "Glob opt string: {:?} and glob opt score: {:?}",
-                glob_opt_string, glob_opt_score
-            );
-        }
         //Return the global optimum string and its fitness
         glob_opt_strings
             .into_iter()
@@ -636,17 +1121,18 @@ impl CliqueTree {
     }
 
     ///Construct the clique tree, using the input paramters and the codomain values. It returns a tuple (cliques, separators)
+    /// Panics if the input parameters are invalid, see `InputParameters::validate`.
     pub fn construct(input_parameters: &InputParameters, rng: &mut ChaChaRng) -> (Vec<Vec<u32>>, Vec<Vec<u32>>) {
+        if let Err(error) = input_parameters.validate() {
+            panic!("can not construct clique tree: {}", error);
+        }
         let mut cliques: Vec<Vec<u32>> = Vec::with_capacity(input_parameters.m as usize);
         let mut separators: Vec<Vec<u32>> = Vec::with_capacity(input_parameters.m as usize);
 
         //Shuffle the variable indices, so that we don't get an easy tree.
-        let mut indices: Vec<u32> = (0..((input_parameters.m - 1)
-            * (input_parameters.k - input_parameters.o)
-            + input_parameters.k))
-            .collect();
+        let mut indices: Vec<u32> = (0..input_parameters.problem_size()).collect();
 
-        indices.shuffle(rng);
+        shuffle(&mut indices, rng);
         debug!("{:?}", indices);
 
         //Initialize clique 0, C0, by  just taking the first k variable indices from the list.
@@ -659,21 +1145,17 @@ impl CliqueTree {
         cliques.push(clique0);
         separators.push(Vec::new()); //filler, there is no separator 0!
 
-        //We set the number of currenlty constructed cliques to 1
+        //We set the number of currenlty constructed cliques to 1, and the index of the next unused variable index to k
         let mut count = 1;
+        let mut start_index = input_parameters.k;
 
-        let b = if input_parameters.o == 0 {
-            1
-        } else {
-            input_parameters.b
-        };
+        //Use the same branching factor as get_parent_index and get_child_indices, so that the constructed
+        // parent-child relations always match the derived ones, also when the last level is only partially filled.
+        let b = CliqueTree::get_branching_factor(input_parameters);
 
         //We calculate the index of the first clique that should not get any children.
-        let mut division = (input_parameters.m - 1) / b;
         //If a clique should construct at least one child, it is considered as well.
-        if (input_parameters.m - 1) % b > 0 {
-            division += 1;
-        }
+        let division = (input_parameters.m - 1).div_ceil(b);
 
         //Dit kan nog geoptimaliseerd worden door die separator_count en variables_to_add ertussenuit te halen,
         // want daarna plaats ik het toch samen in een nieuwe vector...
@@ -686,34 +1168,34 @@ impl CliqueTree {
                     break;
                 }
 
+                //The number of variables o the new clique shares with its parent, which can depend on its level
+                let o = input_parameters.separator_size(count);
+
                 //Choose o random variable indices from Ci
                 //Here, we first clone Ci, shuffle it, and push the first o variable indices to the separator.
                 let mut clique_copy = cliques[i as usize].clone();
-                clique_copy.shuffle(rng);
+                shuffle(&mut clique_copy, rng);
 
-                let mut new_separator: Vec<u32> = Vec::with_capacity(input_parameters.o as usize);
-                for k in 0..input_parameters.o {
+                let mut new_separator: Vec<u32> = Vec::with_capacity(o as usize);
+                for k in 0..o {
                     new_separator.push(clique_copy[k as usize]);
                 }
 
-                //Set the start index of the k variable indices we will copy from the variable index list
-                let start_index =
-                    (count - 1) * (input_parameters.k - input_parameters.o) + input_parameters.k;
-                //Copy the variable indices into a list
+                //Copy the (k - o) variable indices from the start index of the variable index list into a list
                 let mut variables_to_add: Vec<u32> =
-                    Vec::with_capacity((input_parameters.k - input_parameters.o) as usize);
-                for k in 0..(input_parameters.k - input_parameters.o) {
+                    Vec::with_capacity((input_parameters.k - o) as usize);
+                for k in 0..(input_parameters.k - o) {
                     variables_to_add.push(indices[(start_index + k) as usize]);
                 }
 
                 //Construct new clique for the child, by taking the o variables indices from the separator and
                 // (k - o) variable indices from the variable index list
-                let mut new_clique: Vec<u32> = Vec::with_capacity(input_parameters.o as usize);
-                for k in 0..input_parameters.o {
+                let mut new_clique: Vec<u32> = Vec::with_capacity(input_parameters.k as usize);
+                for k in 0..o {
                     new_clique.push(new_separator[k as usize]);
                 }
 
-                for k in 0..(input_parameters.k - input_parameters.o) {
+                for k in 0..(input_parameters.k - o) {
                     new_clique.push(variables_to_add[k as usize]);
                 }
 
@@ -721,6 +1203,7 @@ impl CliqueTree {
                 cliques.push(new_clique);
                 separators.push(new_separator);
                 count += 1;
+                start_index += input_parameters.k - o;
             }
         }
 
@@ -735,49 +1218,43 @@ impl CliqueTree {
         current_solutionfit: &SolutionFit,
         number_evaluations: &mut u32,
         index_mutation: u32,
+    ) -> f64 {
+        *number_evaluations += 1;
+
+        evaluation::calculate_fitness_delta(
+            &self.cliques,
+            &self.codomain_values,
+            self.get_cliques_of_variable(index_mutation),
+            current_solutionfit,
+            index_mutation,
+        )
+    }
+
+    ///Calculate the fitness of a passed solution using the knowledge that only one bit will be flipped,
+    /// reusing the per-clique substring indices of the current solution as returned by calculate_fitness_with_indices.
+    /// The solution has **not** been mutated at the given index yet.
+    pub fn calculate_fitness_delta_with_indices(
+        &self,
+        current_solutionfit: &SolutionFit,
+        clique_substring_indices: &[u32],
+        number_evaluations: &mut u32,
+        index_mutation: u32,
     ) -> f64 {
         //First set the fitness to the current fitness
         let mut fitness = current_solutionfit.fitness;
 
-        //Then loop over all the cliques
-        for clique_index in 0..self.cliques.len() {
+        //Only the cliques containing the mutated variable change their contribution
+        for &clique_index in self.get_cliques_of_variable(index_mutation) {
+            let clique_index = clique_index as usize;
             let clique = &self.cliques[clique_index];
-            if clique.contains(&index_mutation) {
-                //And for each clique calculate the solution substring for this clique, as an index into an array of these substrings.
-                let mut clique_substring_as_index = 0;
-                //Create variable to conveniently store reference to the current clique in.
-                let clique = &self.cliques[clique_index];
-
-                //We will store the index in the clique of the bit that will be flipped
-                let mut clique_mutation_index = 0;
-
-                //Go over each variable index in the clique and for each one, take the bit value from the solution string and add it to the clique substring.
-                for j in (0..clique.len()).rev() {
-                    //If the solution index of the considered index is equal to the index of the mutated bit, we store the index (in this clique) for future use.
-                    if clique[j] == index_mutation {
-                        clique_mutation_index = j;
-                    }
-
-                    //As we would otherwise do, add all the bits from the solution to the clique's subsolution, to be evaluated hereafter
-                    clique_substring_as_index +=
-                        current_solutionfit.solution[clique[j] as usize] << (clique.len() - j - 1);
-                }
+            if let Some(clique_mutation_index) = clique.iter().position(|&x| x == index_mutation) {
+                let clique_substring_as_index = clique_substring_indices[clique_index];
+                let flipped_substring_as_index =
+                    clique_substring_as_index ^ (1 << (clique.len() - clique_mutation_index - 1));
 
-                //Substract the fitness contribution of this clique, as this has been previously added to get to the current fitness.
+                //Replace the old contribution of this clique by its contribution after the mutation
                 fitness -= self.codomain_values[clique_index][clique_substring_as_index as usize];
-
-                //Now set the bit in the clique's subsolution to the value it would be after mutation.
-                // It looks a bit involved, as we use u32 values.
-                if current_solutionfit.solution[clique[clique_mutation_index] as usize] == 0 {
-                    clique_substring_as_index += 1 << (clique.len() - clique_mutation_index - 1);
-                } else {
-                    clique_substring_as_index -= 1 << (clique.len() - clique_mutation_index - 1);
-                }
-
-                //Add the fitness contribution of this clique, taking into account the mutation.
-                fitness += self.codomain_values[clique_index][clique_substring_as_index as usize];
-
-                //Now we subtracted the old codomain value of this clique and have added the new value.
+                fitness += self.codomain_values[clique_index][flipped_substring_as_index as usize];
             }
         }
 
@@ -786,24 +1263,102 @@ impl CliqueTree {
         fitness
     }
 
+    ///Flip the bit at the given index of the current solution and return the new solution with its fitness,
+    /// which is calculated with calculate_fitness_delta before the bit is flipped. The current solution is not changed.
+    pub fn apply_bit_flip(
+        &self,
+        current_solutionfit: &SolutionFit,
+        index_mutation: u32,
+        number_evaluations: &mut u32,
+    ) -> SolutionFit {
+        let fitness =
+            self.calculate_fitness_delta(current_solutionfit, number_evaluations, index_mutation);
+        let mut solution = current_solutionfit.solution.clone();
+        solution[index_mutation as usize] ^= 1;
+        SolutionFit { solution, fitness }
+    }
+
+    ///Flip the bit at the given index of the solution in place, updating its fitness with calculate_fitness_delta.
+    /// Returns the fitness before the flip, which can be passed to revert_flip to undo the flip without allocating.
+    pub fn try_flip(
+        &self,
+        solutionfit: &mut SolutionFit,
+        index_mutation: u32,
+        number_evaluations: &mut u32,
+    ) -> f64 {
+        let previous_fitness = solutionfit.fitness;
+        solutionfit.fitness =
+            self.calculate_fitness_delta(solutionfit, number_evaluations, index_mutation);
+        solutionfit.solution[index_mutation as usize] ^= 1;
+        previous_fitness
+    }
+
+    ///Undo a flip done by try_flip, by flipping the bit back and restoring the fitness before the flip exactly.
+    /// No evaluation is needed for this.
+    pub fn revert_flip(
+        &self,
+        solutionfit: &mut SolutionFit,
+        index_mutation: u32,
+        previous_fitness: f64,
+    ) {
+        solutionfit.solution[index_mutation as usize] ^= 1;
+        solutionfit.fitness = previous_fitness;
+    }
+
     ///Calculate the fitnesss of a passed solution
     pub fn calculate_fitness(&self, solution: &[u32], number_evaluations: &mut u32) -> f64 {
-        //First set the fitness to 0.0
+        *number_evaluations += 1;
+
+        evaluation::calculate_fitness(&self.cliques, &self.codomain_values, solution)
+    }
+
+    ///Calculate the fitness of a passed solution, together with the substring index of the solution in every clique.
+    /// The indices can be cached and passed to calculate_fitness_delta_with_indices to avoid recomputing them.
+    pub fn calculate_fitness_with_indices(&self, solution: &[u32]) -> (f64, Vec<u32>) {
         let mut fitness = 0.0;
+        let mut clique_substring_indices = Vec::with_capacity(self.cliques.len());
 
-        //Then loop over all the cliques
-        for clique_index in 0..self.cliques.len() {
-            //And for each clique calculate the solution substring for this clique, as an index into an array of these substrings.
+        for (clique_index, clique) in self.cliques.iter().enumerate() {
+            //Calculate the solution substring for this clique, as an index into an array of these substrings.
             let mut clique_substring_as_index = 0;
-            //Create variable to conveniently store reference to the current clique in.
-            let clique = &self.cliques[clique_index];
-            //Go over each variable index in the clique and for each one, take the bit value from the solution string and add it to the clique substring.
             for j in (0..clique.len()).rev() {
                 clique_substring_as_index += solution[clique[j] as usize] << (clique.len() - j - 1);
             }
 
-            //Add the fitness contribution of this clique
+            //Add the fitness contribution of this clique and store its index
             fitness += self.codomain_values[clique_index][clique_substring_as_index as usize];
+            clique_substring_indices.push(clique_substring_as_index);
+        }
+
+        (fitness, clique_substring_indices)
+    }
+
+    ///Calculate the fitness of a passed solution of n variables, packed as a bitset with one bit per variable:
+    /// variable i is bit (i % 64) of word (i / 64), with bit 0 the least significant bit.
+    pub fn calculate_fitness_packed(
+        &self,
+        bits: &[u64],
+        n: u32,
+        number_evaluations: &mut u32,
+    ) -> f64 {
+        assert_eq!(n, self.get_problem_size());
+        assert!(bits.len() * 64 >= n as usize);
+
+        //First set the fitness to 0.0
+        let mut fitness = 0.0;
+
+        //Then loop over all the cliques
+        for (clique, clique_codomain) in self.cliques.iter().zip(self.codomain_values.iter()) {
+            //And for each clique calculate the solution substring for this clique, as an index into an array of these substrings.
+            let mut clique_substring_as_index = 0;
+            //Go over each variable index in the clique and for each one, take the bit value from the packed solution and add it to the clique substring.
+            for (j, &variable_index) in clique.iter().enumerate() {
+                let bit = (bits[variable_index as usize / 64] >> (variable_index % 64)) & 1;
+                clique_substring_as_index |= (bit as usize) << (clique.len() - j - 1);
+            }
+
+            //Add the fitness contribution of this clique
+            fitness += clique_codomain[clique_substring_as_index];
         }
 
         *number_evaluations += 1;
@@ -811,6 +1366,688 @@ impl CliqueTree {
         fitness
     }
 
+    ///Calculate a lower and upper bound on the fitness of a partial assignment, where unassigned variables are None.
+    /// For each clique, the minimum and maximum codomain value consistent with the assigned variables is added to the bounds,
+    ///  so fully assigned cliques add their exact contribution.
+    /// The cost per clique is exponential in its number of unassigned variables, as 2^(unassigned in clique) substrings are enumerated.
+    pub fn evaluate_partial(&self, assignment: &[Option<u32>]) -> (f64, f64) {
+        let mut lower_bound = 0.0;
+        let mut upper_bound = 0.0;
+
+        for (clique, clique_codomain) in self.cliques.iter().zip(&self.codomain_values) {
+            //Calculate the substring index of the assigned bits, and store the bit shift of the unassigned bits
+            let mut assigned_substring_index = 0;
+            let mut unassigned_bit_shifts = Vec::new();
+            for (j, &variable_index) in clique.iter().enumerate() {
+                let bit_shift = clique.len() - j - 1;
+                match assignment[variable_index as usize] {
+                    Some(bit) => assigned_substring_index += (bit as usize) << bit_shift,
+                    None => unassigned_bit_shifts.push(bit_shift),
+                }
+            }
+
+            //Enumerate all values for the unassigned bits and keep track of the lowest and highest codomain value
+            let mut lowest_value = f64::INFINITY;
+            let mut highest_value = f64::NEG_INFINITY;
+            for unassigned_bits in 0..(1usize << unassigned_bit_shifts.len()) {
+                let mut substring_index = assigned_substring_index;
+                for (l, bit_shift) in unassigned_bit_shifts.iter().enumerate() {
+                    substring_index += ((unassigned_bits >> l) & 1) << bit_shift;
+                }
+                let value = clique_codomain[substring_index];
+                lowest_value = lowest_value.min(value);
+                highest_value = highest_value.max(value);
+            }
+
+            lower_bound += lowest_value;
+            upper_bound += highest_value;
+        }
+
+        (lower_bound, upper_bound)
+    }
+
+    ///Calculate the minimum achievable fitness, using the same dynamic programming over the clique tree
+    /// as for the global optima, but minimizing instead of maximizing
+    pub fn min_fitness(&self) -> f64 {
+        self.calculate_optimal_fitness(is_worse_fitness_with_epsilon)
+    }
+
+    ///Get the minimum and maximum achievable fitness, as the tuple (min, max)
+    pub fn fitness_range(&self) -> (f64, f64) {
+        (self.min_fitness(), self.glob_optima_score)
+    }
+
+    ///Get a hash of the problem content (the input parameters, including o per level, cliques, and codomain values), to deduplicate or cache problems.
+    /// The hash is a 64-bit FNV-1a hash over little-endian bytes, so it is stable across runs and platforms.
+    /// The codomain values are quantized to multiples of CONTENT_HASH_RESOLUTION first,
+    ///  so that values that only differ by floating point noise hash equally.
+    pub fn content_hash(&self) -> u64 {
+        let input_parameters = &self.input_parameters;
+        let mut hash = FNV_OFFSET_BASIS;
+        for parameter in &[
+            input_parameters.m,
+            input_parameters.k,
+            input_parameters.o,
+            input_parameters.b,
+        ] {
+            hash = update_fnv_hash(hash, &parameter.to_le_bytes());
+        }
+        //o per level is only hashed if it is set, so that the hash of other problems is unchanged
+        if !input_parameters.o_per_level.is_empty() {
+            hash = update_fnv_hash(
+                hash,
+                &(input_parameters.o_per_level.len() as u32).to_le_bytes(),
+            );
+            for o in &input_parameters.o_per_level {
+                hash = update_fnv_hash(hash, &o.to_le_bytes());
+            }
+        }
+        for clique in &self.cliques {
+            hash = update_fnv_hash(hash, &(clique.len() as u32).to_le_bytes());
+            for variable_index in clique {
+                hash = update_fnv_hash(hash, &variable_index.to_le_bytes());
+            }
+        }
+        for value in self.codomain_values.iter().flatten() {
+            let quantized_value = (value / CONTENT_HASH_RESOLUTION).round() as i64;
+            hash = update_fnv_hash(hash, &quantized_value.to_le_bytes());
+        }
+        hash
+    }
+
+    ///Get the min, max, mean, standard deviation, and number of distinct values of all codomain values of the problem
+    pub fn codomain_statistics(&self) -> CodomainStats {
+        let values: Vec<f64> = self.codomain_values.iter().flatten().copied().collect();
+        let number_of_values = values.len() as f64;
+
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean = values.iter().sum::<f64>() / number_of_values;
+        let variance = values
+            .iter()
+            .map(|value| (value - mean) * (value - mean))
+            .sum::<f64>()
+            / number_of_values;
+
+        //Values are distinct if their bit representations differ
+        let mut value_bits: Vec<u64> = values.iter().map(|value| value.to_bits()).collect();
+        value_bits.sort_unstable();
+        value_bits.dedup();
+
+        CodomainStats {
+            min,
+            max,
+            mean,
+            std_dev: variance.sqrt(),
+            distinct_values: value_bits.len(),
+        }
+    }
+
+    ///Calculate the optimal fitness, where is_improvement decides whether the first fitness is an improvement over the second.
+    /// For each clique (children first) and each instance of its separator, we store the optimal score of the clique and its subtree.
+    fn calculate_optimal_fitness(&self, is_improvement: fn(f64, f64, f64) -> bool) -> f64 {
+        let k = self.input_parameters.k;
+        let number_cliques = self.cliques.len();
+
+        // [M][separator instance] = optimal score of the clique and its subtree, for that separator instance
+        let mut optimal_scores: Vec<Vec<Option<f64>>> = self
+            .separators
+            .iter()
+            .map(|separator| vec![None; 1 << separator.len()])
+            .collect();
+
+        //Go over all cliques in reversed order, so that all children are handled before their parent
+        for i in (0..number_cliques).rev() {
+            let clique = &self.cliques[i];
+            let children: Vec<usize> = self
+                .children(i as u32)
+                .into_iter()
+                .map(|child| child as usize)
+                .collect();
+            let children_separator_positions: Vec<Vec<usize>> = children
+                .iter()
+                .map(|&child| get_variable_positions(clique, &self.separators[child]))
+                .collect();
+            let separator_positions = get_variable_positions(clique, &self.separators[i]);
+
+            for substring_index in 0..(1usize << k) {
+                //Add f and the optimal scores of the children, given this clique's substring
+                let mut score = self.codomain_values[i][substring_index];
+                for (&child, positions) in children.iter().zip(&children_separator_positions) {
+                    score += optimal_scores[child]
+                        [get_substring_index_at_positions(substring_index, positions, k)]
+                    .expect("child clique should be handled before its parent");
+                }
+
+                //And store the score if it is the optimal score for this separator instance
+                let separator_index =
+                    get_substring_index_at_positions(substring_index, &separator_positions, k);
+                match optimal_scores[i][separator_index] {
+                    Some(optimal_score)
+                        if !is_improvement(score, optimal_score, self.fitness_epsilon) => {}
+                    _ => optimal_scores[i][separator_index] = Some(score),
+                }
+            }
+        }
+
+        //The root has no separator, so it has only one entry
+        optimal_scores[0][0].expect("root clique should have an optimal score")
+    }
+
+    ///Calculate the fitness-distance correlation (FDC), by sampling random solutions and calculating
+    /// the Pearson correlation between their fitness and their Hamming distance to the nearest global optimum.
+    /// Returns NaN if the fitness or the distance is the same for all samples.
+    pub fn fitness_distance_correlation(&self, samples: usize, rng: &mut ChaChaRng) -> f64 {
+        let problem_size = self.get_problem_size();
+        let mut number_evaluations = 0;
+
+        let (fitnesses, distances): (Vec<f64>, Vec<f64>) = (0..samples)
+            .map(|_| {
+                let solution = get_random_solution(problem_size, rng);
+                let fitness = self.calculate_fitness(&solution, &mut number_evaluations);
+                let distance = self
+                    .glob_optima_strings
+                    .iter()
+                    .map(|glob_optimum| get_hamming_distance_to_solution(glob_optimum, &solution))
+                    .min()
+                    .expect("clique tree should have at least one global optimum");
+                (fitness, distance as f64)
+            })
+            .unzip();
+
+        pearson_correlation(&fitnesses, &distances)
+    }
+
+    ///Calculate the autocorrelation of the fitness along a random walk, a measure of the ruggedness of the landscape.
+    /// The walk starts at a random solution and flips one random bit per step, evaluating each step with calculate_fitness_delta.
+    /// Returns the autocorrelation of the steps + 1 fitnesses at the given lag, which is close to 1 for smooth landscapes,
+    ///  or NaN if the fitness is the same for the whole walk.
+    pub fn random_walk_autocorrelation(&self, steps: usize, lag: usize, rng: &mut ChaChaRng) -> f64 {
+        assert!(
+            lag <= steps,
+            "lag {} is larger than the number of steps {}",
+            lag,
+            steps
+        );
+        let problem_size = self.get_problem_size();
+        let mut number_evaluations = 0;
+
+        let mut solutionfit =
+            SolutionFit::evaluate(self, get_random_solution(problem_size, rng), &mut number_evaluations);
+        let mut fitnesses = Vec::with_capacity(steps + 1);
+        fitnesses.push(solutionfit.fitness);
+        for _ in 0..steps {
+            let index_mutation = rng.gen_range(0..problem_size);
+            self.try_flip(&mut solutionfit, index_mutation, &mut number_evaluations);
+            fitnesses.push(solutionfit.fitness);
+        }
+
+        autocorrelation(&fitnesses, lag)
+    }
+
+    ///Run first-improvement hill climbing from a random solution until no single bit flip improves the fitness
+    /// by more than the fitness epsilon, and return the local optimum it ends in.
+    /// Every pass goes over the variables in order, keeping each improving flip, evaluated with calculate_fitness_delta.
+    pub fn random_local_optimum(&self, rng: &mut ChaChaRng) -> SolutionFit {
+        let problem_size = self.get_problem_size();
+        let mut number_evaluations = 0;
+
+        let mut solutionfit =
+            SolutionFit::evaluate(self, get_random_solution(problem_size, rng), &mut number_evaluations);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for index_mutation in 0..problem_size {
+                let previous_fitness =
+                    self.try_flip(&mut solutionfit, index_mutation, &mut number_evaluations);
+                if solutionfit.fitness > previous_fitness + self.fitness_epsilon {
+                    improved = true;
+                } else {
+                    self.revert_flip(&mut solutionfit, index_mutation, previous_fitness);
+                }
+            }
+        }
+
+        solutionfit
+    }
+
+    ///Estimate the size of the basin of attraction of the target solution (e.g. a local or global optimum),
+    /// as the fraction of the samples random_local_optimum ends in the target.
+    pub fn estimate_basin_size(&self, target: &[u32], samples: usize, rng: &mut ChaChaRng) -> f64 {
+        if samples == 0 {
+            return 0.0;
+        }
+        let hits = (0..samples)
+            .filter(|_| self.random_local_optimum(rng).solution == target)
+            .count();
+        hits as f64 / samples as f64
+    }
+
+    ///Get a solution at exactly Hamming distance d from the global optimum at optimum_index,
+    /// by flipping d randomly chosen bits of the global optimum.
+    pub fn solution_at_distance(
+        &self,
+        optimum_index: usize,
+        d: u32,
+        rng: &mut ChaChaRng,
+    ) -> Vec<u32> {
+        let problem_size = self.get_problem_size();
+        assert!(
+            optimum_index < self.glob_optima_strings.len(),
+            "optimum index {} is out of bounds, there are {} global optima",
+            optimum_index,
+            self.glob_optima_strings.len()
+        );
+        assert!(
+            d <= problem_size,
+            "distance {} is larger than the problem size {}",
+            d,
+            problem_size
+        );
+
+        let mut solution = self.glob_optima_strings[optimum_index].clone();
+        for variable_index in index::sample(rng, problem_size as usize, d as usize) {
+            solution[variable_index] = 1 - solution[variable_index];
+        }
+        solution
+    }
+
+    ///Get all 2^n solutions of the problem with their fitness, sorted by descending fitness.
+    /// Solutions with equal fitness are ordered by their value as a bit string, with the first variable as the most significant bit.
+    /// As all solutions are materialized, the problem size may be at most MAX_ENUMERATED_PROBLEM_SIZE.
+    pub fn solutions_by_fitness(&self) -> impl Iterator<Item = SolutionFit> {
+        let problem_size = self.get_problem_size();
+        assert!(
+            problem_size <= MAX_ENUMERATED_PROBLEM_SIZE,
+            "problem size {} is larger than the maximum of {} for enumerating all solutions",
+            problem_size,
+            MAX_ENUMERATED_PROBLEM_SIZE
+        );
+
+        let mut number_evaluations = 0;
+        let mut solutionfits: Vec<SolutionFit> = get_possible_substrings_iter(problem_size)
+            .map(|solution| SolutionFit::evaluate(self, solution, &mut number_evaluations))
+            .collect();
+        solutionfits.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+
+        solutionfits.into_iter()
+    }
+
+    ///Get a histogram of the fitnesses of all 2^n solutions, as (low, high, count) tuples of bins equal-width bins
+    /// between the lowest and highest fitness. A fitness on the boundary of two bins is counted in the higher bin,
+    ///  except for the highest fitness, which is counted in the last bin.
+    /// As all solutions are evaluated, the problem size may be at most MAX_ENUMERATED_PROBLEM_SIZE.
+    pub fn fitness_histogram(&self, bins: usize) -> Vec<(f64, f64, usize)> {
+        let problem_size = self.get_problem_size();
+        assert!(
+            problem_size <= MAX_ENUMERATED_PROBLEM_SIZE,
+            "problem size {} is larger than the maximum of {} for enumerating all solutions",
+            problem_size,
+            MAX_ENUMERATED_PROBLEM_SIZE
+        );
+        assert!(bins > 0, "the histogram should have at least one bin");
+
+        let mut number_evaluations = 0;
+        let fitnesses: Vec<f64> = get_possible_substrings_iter(problem_size)
+            .map(|solution| self.calculate_fitness(&solution, &mut number_evaluations))
+            .collect();
+        let min_fitness = fitnesses.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_fitness = fitnesses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let bin_width = (max_fitness - min_fitness) / bins as f64;
+
+        let mut counts = vec![0; bins];
+        for fitness in fitnesses {
+            let bin = if bin_width > 0.0 {
+                (((fitness - min_fitness) / bin_width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(bin, count)| {
+                let low = min_fitness + bin as f64 * bin_width;
+                let high = if bin == bins - 1 {
+                    max_fitness
+                } else {
+                    min_fitness + (bin + 1) as f64 * bin_width
+                };
+                (low, high, count)
+            })
+            .collect()
+    }
+
+    ///Get the problem size, the number of variables in the clique tree
+    pub fn get_problem_size(&self) -> u32 {
+        self.input_parameters.problem_size()
+    }
+
+    ///Get the expected fitness of a uniformly random solution, calculated exactly without sampling.
+    /// Every substring of a clique is equally likely, so this is the sum over the cliques of the mean of their codomain values.
+    pub fn expected_random_fitness(&self) -> f64 {
+        self.codomain_values
+            .iter()
+            .map(|clique_codomain| {
+                clique_codomain.iter().sum::<f64>() / clique_codomain.len() as f64
+            })
+            .sum()
+    }
+
+    ///Get a human-readable, multi-line summary of the clique tree, for logging and debugging:
+    /// the input parameters, the problem size, the number of cliques, the separator size, the codomain function,
+    /// the global optimum score, the number of global optima, and the first global optimum.
+    pub fn describe(&self) -> String {
+        let input_parameters = &self.input_parameters;
+        let separator_size = if input_parameters.o_per_level.is_empty() {
+            input_parameters.o.to_string()
+        } else {
+            format!("{:?} per level", input_parameters.o_per_level)
+        };
+        let first_global_optimum = self.glob_optima_strings.first().map_or_else(
+            || "none".to_owned(),
+            |glob_optimum_string| glob_optimum_string.iter().map(|bit| bit.to_string()).collect(),
+        );
+
+        format!(
+            "Parameters: M = {}, k = {}, o = {}, b = {}\n\
+             Problem size (n): {}\n\
+             Number of cliques: {}\n\
+             Separator size: {}\n\
+             Codomain function: {}\n\
+             Global optimum score: {}\n\
+             Number of global optima: {}\n\
+             First global optimum: {}\n",
+            input_parameters.m,
+            input_parameters.k,
+            input_parameters.o,
+            input_parameters.b,
+            self.get_problem_size(),
+            self.cliques.len(),
+            separator_size,
+            self.codomain_function,
+            self.glob_optima_score,
+            self.glob_optima_strings.len(),
+            first_global_optimum
+        )
+    }
+
+    ///Get, for every variable index, the indices of the cliques that contain the variable, in ascending order.
+    /// This is the transpose of the cliques.
+    pub fn variable_clique_membership(&self) -> Vec<Vec<u32>> {
+        get_variable_cliques(&self.cliques)
+    }
+
+    ///Get, for every variable index, the indices of the cliques that contain the variable, as calculated on construction.
+    /// Unlike variable_clique_membership, this does not calculate them again.
+    pub fn variable_cliques(&self) -> &[Vec<u32>] {
+        &self.variable_cliques
+    }
+
+    ///Get the indices of the cliques that contain the variable, or no cliques if the variable index is out of range
+    fn get_cliques_of_variable(&self, variable_index: u32) -> &[u32] {
+        get_cliques_of_variable(&self.variable_cliques, variable_index)
+    }
+
+    ///Get the variable interaction graph, as the number of variables and the (deduplicated) edge list.
+    /// Two variables are connected if they occur together in any clique, and every edge (u, v) has u < v.
+    pub fn interaction_graph(&self) -> (usize, Vec<(u32, u32)>) {
+        let mut edges: Vec<(u32, u32)> = self
+            .cliques
+            .iter()
+            .flat_map(|clique| {
+                clique.iter().enumerate().flat_map(move |(i, &u)| {
+                    clique[i + 1..].iter().map(move |&v| (u.min(v), u.max(v)))
+                })
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        (self.get_problem_size() as usize, edges)
+    }
+
+    ///Make the global optimum unique, by adding UNIQUE_OPTIMUM_PERTURBATION to the codomain value of the (first) global optimum's substring
+    /// in every clique, and recalculating the global optima. The perturbation is at least twice the fitness epsilon of the clique tree,
+    ///  as smaller differences in fitness are considered ties. Every other string differs from this global optimum in at least one clique,
+    ///  so its fitness increases less, which makes this global optimum strictly better than all other strings without changing which string is optimal.
+    /// The perturbation is repeated (at most MAX_UNIQUE_OPTIMUM_RETRIES times) until the global optimum is unique.
+    /// Returns whether the global optimum is unique.
+    pub fn make_global_optimum_unique(&mut self) -> bool {
+        let perturbation = UNIQUE_OPTIMUM_PERTURBATION.max(2.0 * self.fitness_epsilon);
+        for _ in 0..MAX_UNIQUE_OPTIMUM_RETRIES {
+            if self.glob_optima_strings.len() == 1 {
+                return true;
+            }
+
+            let global_optimum = &self.glob_optima_strings[0];
+            for (clique, clique_codomain) in
+                self.cliques.iter().zip(self.codomain_values.iter_mut())
+            {
+                let clique_substring: Vec<u32> = clique
+                    .iter()
+                    .map(|&variable_index| global_optimum[variable_index as usize])
+                    .collect();
+                clique_codomain[transform_substring_vector_to_index(&clique_substring) as usize] +=
+                    perturbation;
+            }
+
+            self.recalculate_global_optima();
+        }
+        self.glob_optima_strings.len() == 1
+    }
+
+    ///Check whether the problem is separable, i.e. whether the cliques do not overlap
+    pub fn is_separable(&self) -> bool {
+        self.input_parameters.is_separable()
+    }
+
+    ///Check whether glob_optima_strings only contains some of the global optima. This happens for separable problems
+    /// with more than MAX_MATERIALIZED_GLOBAL_OPTIMA global optima; count_global_optima_separable gives their number.
+    pub fn glob_optima_truncated(&self) -> bool {
+        self.is_separable()
+            && CliqueTree::count_global_optima_separable(
+                &self.input_parameters,
+                &self.codomain_values,
+                self.fitness_epsilon,
+            ) > self.glob_optima_strings.len() as u128
+    }
+
+    ///Get the connected components of the problem, as groups of clique indices of cliques that (indirectly) share variables.
+    /// The components are found with a union-find over the edges of the interaction graph,
+    ///  and are ordered by their first clique index, with the clique indices in every component in ascending order.
+    pub fn connected_components(&self) -> Vec<Vec<u32>> {
+        let (number_of_variables, edges) = self.interaction_graph();
+
+        //Union-find over the variables, where every variable starts as its own root
+        let mut parents: Vec<u32> = (0..number_of_variables as u32).collect();
+        for (u, v) in edges {
+            let root_u = find_root(&mut parents, u);
+            let root_v = find_root(&mut parents, v);
+            parents[root_u.max(root_v) as usize] = root_u.min(root_v);
+        }
+
+        //Group the cliques by the root of (any of) their variables
+        let mut components: Vec<Vec<u32>> = Vec::new();
+        let mut component_indices: HashMap<u32, usize> = HashMap::new();
+        for (clique_index, clique) in self.cliques.iter().enumerate() {
+            let root = find_root(&mut parents, clique[0]);
+            let component_index = *component_indices.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component_index].push(clique_index as u32);
+        }
+        components
+    }
+
+    ///Restrict the problem to a single clique, returning that clique's variable indices and its codomain table (of length 2^k),
+    /// indexed by the clique's substring in the order of the returned variable indices.
+    pub fn restrict_to_clique(&self, clique_index: usize) -> (Vec<u32>, Vec<f64>) {
+        (
+            self.cliques[clique_index].clone(),
+            self.codomain_values[clique_index].clone(),
+        )
+    }
+
+    ///Get every clique paired with its separator, the variables it shares with its parent clique, e.g. for message-passing solvers.
+    /// The root clique has no parent, so its separator is empty.
+    pub fn clique_separator_pairs(&self) -> Vec<(&[u32], &[u32])> {
+        self.cliques
+            .iter()
+            .zip(self.separators.iter())
+            .map(|(clique, separator)| (clique.as_slice(), separator.as_slice()))
+            .collect()
+    }
+
+    ///Iterate over the subfunctions of the problem: for every clique, its variable indices paired with its codomain table,
+    /// without copying them. Useful to export the problem to other formats or for custom analysis.
+    pub fn subfunctions(&self) -> impl Iterator<Item = (&[u32], &[f64])> {
+        self.cliques
+            .iter()
+            .zip(self.codomain_values.iter())
+            .map(|(clique, codomain_values)| (clique.as_slice(), codomain_values.as_slice()))
+    }
+
+    ///Extract the subtree rooted at the given clique as a standalone problem.
+    /// The cliques of the subtree are collected in breadth-first order, so that they keep the branching arithmetic of `get_child_indices`,
+    ///  and the variables are renumbered in order of first appearance. The separators and global optima are recalculated for the subtree.
+    pub fn subtree(&self, root_clique: u32) -> CliqueTree {
+        //Collect the clique indices of the subtree in breadth-first order
+        let mut clique_indices = vec![root_clique];
+        let mut current = 0;
+        while current < clique_indices.len() {
+            clique_indices.extend(self.children(clique_indices[current]));
+            current += 1;
+        }
+
+        //Renumber the variables in order of first appearance
+        let mut variable_mapping: HashMap<u32, u32> = HashMap::new();
+        let cliques: Vec<Vec<u32>> = clique_indices
+            .iter()
+            .map(|&clique_index| {
+                self.cliques[clique_index as usize]
+                    .iter()
+                    .map(|variable_index| {
+                        let next_index = variable_mapping.len() as u32;
+                        *variable_mapping.entry(*variable_index).or_insert(next_index)
+                    })
+                    .collect()
+            })
+            .collect();
+        let codomain_values: Vec<Vec<f64>> = clique_indices
+            .iter()
+            .map(|&clique_index| self.codomain_values[clique_index as usize].clone())
+            .collect();
+
+        //The levels of the subtree start at the level of its root, so the overlaps of the levels above it are dropped
+        let root_level = std::iter::successors(Some(root_clique), |&clique_index| self.parent(clique_index))
+            .count()
+            - 1;
+        let input_parameters = InputParameters {
+            m: cliques.len() as u32,
+            o_per_level: self
+                .input_parameters
+                .o_per_level
+                .iter()
+                .skip(root_level)
+                .copied()
+                .collect(),
+            ..self.input_parameters.clone()
+        };
+        let separators = CliqueTree::reconstruct_separators(&input_parameters, &cliques);
+
+        let global_opt_tuples = CliqueTree::calculate_global_optima(
+            &input_parameters,
+            &self.codomain_function,
+            &codomain_values,
+            &cliques,
+            &separators,
+            self.fitness_epsilon,
+        );
+        let glob_optima_score = global_opt_tuples[0].1;
+        let glob_optima_strings = global_opt_tuples.into_iter().map(|tuple| tuple.0).collect();
+
+        CliqueTree {
+            input_parameters,
+            codomain_function: self.codomain_function.clone(),
+            variable_cliques: get_variable_cliques(&cliques),
+            cliques,
+            separators,
+            codomain_values,
+            glob_optima_strings,
+            glob_optima_score,
+            fitness_epsilon: self.fitness_epsilon,
+        }
+    }
+
+    ///Set the epsilon below which two fitnesses are considered equal, and recalculate the global optima with it.
+    /// A smaller epsilon distinguishes fitnesses that differ only slightly, e.g. for nk-q codomains with a large q.
+    pub fn set_fitness_epsilon(&mut self, fitness_epsilon: f64) {
+        self.fitness_epsilon = fitness_epsilon;
+
+        self.recalculate_global_optima();
+    }
+
+    ///Set the codomain value of the substring with the given index in the given clique, and recalculate the global optima.
+    /// To change many values at once, use mutate_codomain, which recalculates the global optima only once.
+    pub fn set_codomain_value(&mut self, clique_index: usize, substring_index: usize, value: f64) {
+        self.mutate_codomain(&[(clique_index, substring_index, value)]);
+    }
+
+    ///Apply the changes (clique index, substring index, value) to the codomain values, and recalculate the global optima.
+    /// This allows changing the landscape over time, e.g. for dynamic optimization experiments.
+    pub fn mutate_codomain(&mut self, changes: &[(usize, usize, f64)]) {
+        for &(clique_index, substring_index, value) in changes {
+            self.codomain_values[clique_index][substring_index] = value;
+        }
+        self.recalculate_global_optima();
+    }
+
+    ///Recalculate the global optima strings and score from the current codomain values and fitness epsilon
+    fn recalculate_global_optima(&mut self) {
+        let global_opt_tuples = CliqueTree::calculate_global_optima(
+            &self.input_parameters,
+            &self.codomain_function,
+            &self.codomain_values,
+            &self.cliques,
+            &self.separators,
+            self.fitness_epsilon,
+        );
+        self.glob_optima_score = global_opt_tuples[0].1;
+        self.glob_optima_strings = global_opt_tuples.into_iter().map(|tuple| tuple.0).collect();
+    }
+
+    ///Write the global optima to the passed destinations, writing at most as many optima as there are destinations,
+    /// and return the number of optima written. Returns an error, without writing anything,
+    ///  if a destination does not have the length of the problem size.
+    pub fn write_global_optima(
+        &self,
+        destinations: &mut [&mut [u32]],
+    ) -> Result<usize, ProblemGenError> {
+        let problem_size = self.get_problem_size() as usize;
+        if let Some(destination) = destinations
+            .iter()
+            .find(|destination| destination.len() != problem_size)
+        {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "every destination should have the length of the problem size ({}), found a destination of length {}",
+                problem_size,
+                destination.len()
+            )));
+        }
+
+        let number_written = destinations.len().min(self.glob_optima_strings.len());
+        for (destination, global_optimum) in
+            destinations.iter_mut().zip(self.glob_optima_strings.iter())
+        {
+            destination.copy_from_slice(global_optimum);
+        }
+        Ok(number_written)
+    }
+
     pub fn is_global_optimum(&self, solution_fit: &SolutionFit) -> bool {
         // if solution_fit.fitness != self.glob_optima_score
         //     && (self.glob_optima_score - solution_fit.fitness).abs() < 0.0000000001
@@ -819,48 +2056,145 @@ impl CliqueTree {
         //         panic!("global optimum found, but my current accepted range is too small: ");
         //     }
         solution_fit.fitness == self.glob_optima_score
-            || ((self.glob_optima_score - solution_fit.fitness).abs() < FITNESS_EPSILON
+            || ((self.glob_optima_score - solution_fit.fitness).abs() < self.fitness_epsilon
                 && self.glob_optima_strings.contains(&solution_fit.solution))
     }
 }
 
+///Load the clique tree from a codomain file, with the structure constructed using a random number generator seeded from entropy
+impl TryFrom<&Path> for CliqueTree {
+    type Error = ProblemGenError;
+
+    fn try_from(codomain_file_path: &Path) -> Result<Self, Self::Error> {
+        CliqueTree::load(codomain_file_path, None)
+    }
+}
+
+impl From<&CliqueTree> for CliqueTreeEvaluator {
+    fn from(clique_tree: &CliqueTree) -> Self {
+        CliqueTreeEvaluator {
+            cliques: clique_tree.cliques.clone(),
+            codomain_values: clique_tree.codomain_values.clone(),
+            variable_cliques: clique_tree.variable_cliques.clone(),
+        }
+    }
+}
+
 pub fn is_better_solutionfit(solutionfit1: &SolutionFit, solutionfit2: &SolutionFit) -> bool {
-    solutionfit1.fitness > solutionfit2.fitness
-        && (solutionfit1.fitness - solutionfit2.fitness).abs() >= FITNESS_EPSILON
+    is_better_solutionfit_with_epsilon(solutionfit1, solutionfit2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_worse_solutionfit(solutionfit1: &SolutionFit, solutionfit2: &SolutionFit) -> bool {
-    solutionfit1.fitness < solutionfit2.fitness
-        && (solutionfit1.fitness - solutionfit2.fitness).abs() >= FITNESS_EPSILON
+    is_worse_solutionfit_with_epsilon(solutionfit1, solutionfit2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_better_or_equal_solutionfit(
     solutionfit1: &SolutionFit,
     solutionfit2: &SolutionFit,
 ) -> bool {
-    solutionfit1.fitness > solutionfit2.fitness || is_equal_solutionfit(solutionfit1, solutionfit2)
+    is_better_or_equal_solutionfit_with_epsilon(solutionfit1, solutionfit2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_equal_solutionfit(solutionfit1: &SolutionFit, solutionfit2: &SolutionFit) -> bool {
-    solutionfit1.fitness == solutionfit2.fitness
-        || (solutionfit1.fitness - solutionfit2.fitness).abs() < FITNESS_EPSILON
-            && solutionfit1.solution == solutionfit2.solution
+    is_equal_solutionfit_with_epsilon(solutionfit1, solutionfit2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_better_fitness(fitness1: f64, fitness2: f64) -> bool {
-    fitness1 > fitness2 && (fitness1 - fitness2).abs() >= FITNESS_EPSILON
+    is_better_fitness_with_epsilon(fitness1, fitness2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_worse_fitness(fitness1: f64, fitness2: f64) -> bool {
-    fitness1 < fitness2 && (fitness1 - fitness2).abs() >= FITNESS_EPSILON
+    is_worse_fitness_with_epsilon(fitness1, fitness2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_better_or_equal_fitness(fitness1: f64, fitness2: f64) -> bool {
-    fitness1 > fitness2 || is_equal_fitness(fitness1, fitness2)
+    is_better_or_equal_fitness_with_epsilon(fitness1, fitness2, DEFAULT_FITNESS_EPSILON)
 }
 
 pub fn is_equal_fitness(fitness1: f64, fitness2: f64) -> bool {
-    (fitness1 - fitness2).abs() < FITNESS_EPSILON
+    is_equal_fitness_with_epsilon(fitness1, fitness2, DEFAULT_FITNESS_EPSILON)
+}
+
+///Same as is_better_solutionfit, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_better_solutionfit_with_epsilon(
+    solutionfit1: &SolutionFit,
+    solutionfit2: &SolutionFit,
+    epsilon: f64,
+) -> bool {
+    solutionfit1.fitness > solutionfit2.fitness
+        && (solutionfit1.fitness - solutionfit2.fitness).abs() >= epsilon
+}
+
+///Same as is_worse_solutionfit, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_worse_solutionfit_with_epsilon(
+    solutionfit1: &SolutionFit,
+    solutionfit2: &SolutionFit,
+    epsilon: f64,
+) -> bool {
+    solutionfit1.fitness < solutionfit2.fitness
+        && (solutionfit1.fitness - solutionfit2.fitness).abs() >= epsilon
+}
+
+///Same as is_better_or_equal_solutionfit, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_better_or_equal_solutionfit_with_epsilon(
+    solutionfit1: &SolutionFit,
+    solutionfit2: &SolutionFit,
+    epsilon: f64,
+) -> bool {
+    solutionfit1.fitness > solutionfit2.fitness
+        || is_equal_solutionfit_with_epsilon(solutionfit1, solutionfit2, epsilon)
+}
+
+///Same as is_equal_solutionfit, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_equal_solutionfit_with_epsilon(
+    solutionfit1: &SolutionFit,
+    solutionfit2: &SolutionFit,
+    epsilon: f64,
+) -> bool {
+    solutionfit1.fitness == solutionfit2.fitness
+        || (solutionfit1.fitness - solutionfit2.fitness).abs() < epsilon
+            && solutionfit1.solution == solutionfit2.solution
+}
+
+///Same as is_better_fitness, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_better_fitness_with_epsilon(fitness1: f64, fitness2: f64, epsilon: f64) -> bool {
+    fitness1 > fitness2 && (fitness1 - fitness2).abs() >= epsilon
+}
+
+///Same as is_worse_fitness, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_worse_fitness_with_epsilon(fitness1: f64, fitness2: f64, epsilon: f64) -> bool {
+    fitness1 < fitness2 && (fitness1 - fitness2).abs() >= epsilon
+}
+
+///Same as is_better_or_equal_fitness, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_better_or_equal_fitness_with_epsilon(fitness1: f64, fitness2: f64, epsilon: f64) -> bool {
+    fitness1 > fitness2 || is_equal_fitness_with_epsilon(fitness1, fitness2, epsilon)
+}
+
+///Same as is_equal_fitness, with fitnesses that differ by less than the epsilon considered equal
+pub fn is_equal_fitness_with_epsilon(fitness1: f64, fitness2: f64, epsilon: f64) -> bool {
+    (fitness1 - fitness2).abs() < epsilon
+}
+
+///Get the highest score for a clique and the indices of all substrings that attain it
+fn get_clique_optima(clique_codomain: &[f64], k: u32, epsilon: f64) -> (f64, Vec<u32>) {
+    //Set the current highest score for this subfunction to the string with all zeroes.
+    let mut highest_score = clique_codomain[0];
+    let mut highest_score_indices = vec![0];
+
+    //Go over the rest of the possible permutations of the string.
+    for (j, &score) in clique_codomain.iter().enumerate().take(1 << k).skip(1) {
+        //And determine whether they have a higher score
+        if is_equal_fitness_with_epsilon(score, highest_score, epsilon) {
+            highest_score_indices.push(j as u32);
+        } else if is_better_fitness_with_epsilon(score, highest_score, epsilon) {
+            highest_score = score;
+            highest_score_indices.clear();
+            highest_score_indices.push(j as u32);
+        }
+    }
+
+    (highest_score, highest_score_indices)
 }
 
 ///Get an iterator for all possible substrings of certain length
@@ -924,14 +2258,108 @@ fn get_child_separator_substring(
     separator_substring
 }
 
+///Calculate the Pearson correlation coefficient between two equally long samples
+fn pearson_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    assert_eq!(xs.len(), ys.len());
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    covariance / (variance_x * variance_y).sqrt()
+}
+
+///Calculate the autocorrelation of the series at the given lag,
+/// normalized by the variance of the whole series
+fn autocorrelation(series: &[f64], lag: usize) -> f64 {
+    let n = series.len() as f64;
+    let mean = series.iter().sum::<f64>() / n;
+    let variance = series.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+
+    let covariance = series
+        .iter()
+        .zip(&series[lag..])
+        .map(|(x, y)| (x - mean) * (y - mean))
+        .sum::<f64>()
+        / (series.len() - lag) as f64;
+
+    covariance / variance
+}
+
+///Get the positions in the clique of the passed variable indices
+fn get_variable_positions(clique: &[u32], variable_indices: &[u32]) -> Vec<usize> {
+    variable_indices
+        .iter()
+        .map(|variable_index| {
+            clique
+                .iter()
+                .position(|x| x == variable_index)
+                .expect("variable index not found in clique!")
+        })
+        .collect()
+}
+
+///Get the index of the substring formed by the bits at the passed positions of a clique substring (given as index)
+fn get_substring_index_at_positions(
+    clique_substring_index: usize,
+    positions: &[usize],
+    k: u32,
+) -> usize {
+    positions.iter().fold(0, |substring_index, &position| {
+        (substring_index << 1) | ((clique_substring_index >> (k as usize - position - 1)) & 1)
+    })
+}
+
 ///Transform the passed substring into an index(bit value) that would point to that substring
 pub fn transform_substring_vector_to_index(substring: &[u32]) -> u32 {
     let mut sum = 0;
-    let mut current_bit_shift_amount = 0;
     //Calculate bit value using the input bit string
-    for i in (0..substring.len()).rev() {
-        sum += substring[i as usize] << current_bit_shift_amount;
-        current_bit_shift_amount += 1;
+    for (current_bit_shift_amount, bit) in substring.iter().rev().enumerate() {
+        sum += bit << current_bit_shift_amount;
     }
     sum
 }
+
+///Transform the passed whole solution into the index (bit value) that points to it, with the first variable as the most significant bit,
+/// like transform_substring_vector_to_index. The solution should have fewer than 64 variables.
+pub fn solution_to_index(solution: &[u32]) -> u64 {
+    assert!(solution.len() < 64);
+
+    solution
+        .iter()
+        .fold(0, |index, &bit| (index << 1) | u64::from(bit))
+}
+
+///Transform the passed index into the solution of n variables it points to, the inverse of solution_to_index.
+/// n should be smaller than 64, and the index smaller than 2^n.
+pub fn index_to_solution(index: u64, n: u32) -> Vec<u32> {
+    assert!(n < 64);
+    assert!(index >> n == 0, "index {} does not fit in {} bits", index, n);
+
+    //bit shift to get vector representation of solution from bit string version
+    (0..n).rev().map(|i| ((index >> i) & 1) as u32).collect()
+}
+
+///Find the root of the variable in the union-find parent list, compressing the path along the way
+fn find_root(parents: &mut [u32], variable: u32) -> u32 {
+    let mut root = variable;
+    while parents[root as usize] != root {
+        root = parents[root as usize];
+    }
+    let mut current = variable;
+    while parents[current as usize] != root {
+        let next = parents[current as usize];
+        parents[current as usize] = root;
+        current = next;
+    }
+    root
+}
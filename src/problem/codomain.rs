@@ -3,19 +3,30 @@ Module for codomain generation, reading, and writing.
 */
 
 use indicatif::ProgressIterator;
+use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
 use structopt::StructOpt;
 use itertools::Itertools;
 
-use super::io::get_output_folder_path_from_configuration_file;
+use super::io::{
+    get_configuration_files, get_output_folder_path_from_configuration_file,
+    is_input_parameters_line,
+};
 
-use super::clique_tree::InputParameters;
+use super::clique_tree::{
+    get_variable_cliques, transform_substring_vector_to_index, CliqueTree, InputParameters,
+    Topology, DEFAULT_FITNESS_EPSILON, UNIQUE_OPTIMUM_PERTURBATION,
+};
+use super::codomain_registry::get_codomain_generator;
 use super::codomain_subclasses::*;
-use super::configuration::{ConfigurationParameters, get_rng};
+use super::configuration::{get_instance_rng, get_rng_logged, ConfigurationParameters};
+use super::error::ProblemGenError;
+use super::export::GraphFormat;
 
+use std::convert::TryFrom;
 use std::fmt::Write as fmtWrite;
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 use std::path::PathBuf;
 use std::{
@@ -34,6 +45,209 @@ pub struct CodomainOpt {
     pub codomain_command: CodomainCommand,
     #[structopt(short = "s", long = "seed")]
     pub seed: Option<u64>,
+    ///Do not print the parsed options and turn off all log messages
+    #[structopt(short = "q", long = "quiet")]
+    pub quiet: bool,
+    ///Directory to write the output folders to, instead of the parent of the configuration file's folder
+    #[structopt(long = "output-dir", parse(from_os_str))]
+    pub output_dir: Option<PathBuf>,
+    ///Skip the codomain files that already exist and are valid, instead of regenerating them, to resume an interrupted run.
+    /// Skipped files advance the random number generator just like generated files, so the results stay reproducible.
+    #[structopt(long = "skip-existing", alias = "append")]
+    pub skip_existing: bool,
+    #[structopt(flatten)]
+    pub generation_options: GenerationOptions,
+}
+
+///Options for the codomain generation, shared by the codomain generator and the problem generator
+#[derive(StructOpt, Debug, Default, Clone)]
+pub struct GenerationOptions {
+    ///Symmetrize the codomain, so that the complement of every global optimum is a global optimum as well
+    #[structopt(long = "complement-optimum")]
+    pub complement_optimum: bool,
+    ///Seed every instance separately from the seed, the input parameters and the instance number,
+    /// so that an instance does not depend on the instances generated before it
+    #[structopt(long = "seed-per-instance")]
+    pub seed_per_instance: bool,
+    ///Write a header with the format version and a checksum of the codomain values to the codomain files,
+    /// so that truncated or corrupted files are detected when reading them
+    #[structopt(long = "codomain-checksum")]
+    pub codomain_checksum: bool,
+    ///Perturb the codomain of every generated problem so that it has exactly one global optimum.
+    /// Only supported by the problem generator when generating from configurations, as the global optima depend on the problem structure.
+    #[structopt(long = "unique-optimum")]
+    pub unique_optimum: bool,
+    ///Number of decimals D of the epsilon (10^-D) below which two fitnesses are considered equal when calculating the global optima,
+    /// instead of the default epsilon of 10^-10. Only supported by the problem generator when generating from configurations.
+    #[structopt(long = "fitness-epsilon-decimals")]
+    pub fitness_epsilon_decimals: Option<u32>,
+    ///Only generate the structure (cliques) of the problems and write it to the problem folder,
+    /// without writing the codomain or calculating the global optima. Only supported by the problem generator when generating from configurations.
+    #[structopt(long = "structure-only")]
+    pub structure_only: bool,
+    ///Maximum number of codomain values (M * 2^k) of a single problem instance, checked before the codomain is generated,
+    /// to prevent accidentally allocating gigabytes of memory. Defaults to 2^28 values (2 GiB).
+    #[structopt(long = "max-codomain-entries")]
+    pub max_codomain_entries: Option<u64>,
+    ///Write the separator variable indices of every clique to the problem files, after the cliques.
+    /// Only supported by the problem generator when generating problems.
+    #[structopt(long = "write-separators")]
+    pub write_separators: bool,
+    ///Only generate the instances with these numbers (comma separated), e.g. to regenerate a subset after a partial failure.
+    /// The other instances advance the random number generator just like generated instances, so the file contents do not change.
+    #[structopt(long = "instances", use_delimiter = true)]
+    pub instances: Vec<u32>,
+    ///Only generate the instances with numbers from START (inclusive) to END (exclusive).
+    /// If --instances is passed as well, only the instances selected by both are generated.
+    #[structopt(long = "instances-range", number_of_values = 2, value_names = &["START", "END"])]
+    pub instances_range: Option<Vec<u32>>,
+    ///Write the variable interaction graph of every generated problem to the graphs folder, in the given format (edgelist or dimacs).
+    /// Only supported by the problem generator when generating from configurations.
+    #[structopt(long = "emit-graph", value_name = "FORMAT")]
+    pub emit_graph: Option<GraphFormat>,
+    ///Topology of the clique trees: balanced (every clique has b children), path (b = 1), or star (the root has all other cliques as children).
+    /// The branching factor b of the input parameters is replaced by the one of the topology, also in the file names and files.
+    /// Not supported when the structure is read from codomain files, and when verifying or analyzing problems.
+    #[structopt(long = "topology", default_value = "balanced")]
+    pub topology: Topology,
+    ///Number of codomain values that are collected before they are written to the codomain file.
+    /// Defaults to 4096 values; higher values mean fewer write calls, at the cost of a larger buffer.
+    #[structopt(long = "flush-every", value_name = "N")]
+    pub flush_every: Option<usize>,
+}
+
+impl GenerationOptions {
+    ///Get the epsilon below which two fitnesses are considered equal, as set by the number of decimals,
+    /// or the default epsilon if it was not set
+    pub fn fitness_epsilon(&self) -> f64 {
+        match self.fitness_epsilon_decimals {
+            Some(decimals) => i32::try_from(decimals).map_or(0.0, |decimals| 10f64.powi(-decimals)),
+            None => DEFAULT_FITNESS_EPSILON,
+        }
+    }
+
+    ///Get the maximum number of codomain values of a single problem instance, or the default maximum if it was not set
+    pub fn max_codomain_entries(&self) -> u64 {
+        self.max_codomain_entries
+            .unwrap_or(DEFAULT_MAX_CODOMAIN_ENTRIES)
+    }
+
+    ///Get the number of codomain values that are collected before writing them, or the default if it was not set
+    pub fn flush_every(&self) -> usize {
+        self.flush_every.unwrap_or(DEFAULT_FLUSH_EVERY)
+    }
+
+    ///Get the flags of the passed options that are not supported by every command:
+    /// the options that are only used when generating problems, and the topology
+    fn passed_problem_options(&self) -> Vec<&'static str> {
+        let problem_options = [
+            ("--unique-optimum", self.unique_optimum),
+            (
+                "--fitness-epsilon-decimals",
+                self.fitness_epsilon_decimals.is_some(),
+            ),
+            ("--structure-only", self.structure_only),
+            ("--write-separators", self.write_separators),
+            ("--emit-graph", self.emit_graph.is_some()),
+            ("--topology", self.topology != Topology::Balanced),
+        ];
+        problem_options
+            .iter()
+            .filter(|(_, passed)| *passed)
+            .map(|(flag, _)| *flag)
+            .collect()
+    }
+
+    ///Check that of the options that are not supported by every command (see passed_problem_options),
+    /// only the supported options are passed, to report an error instead of silently ignoring the others.
+    pub fn check_supported_options(
+        &self,
+        supported_options: &[&str],
+        command: &str,
+    ) -> Result<(), ProblemGenError> {
+        let unsupported_options: Vec<&str> = self
+            .passed_problem_options()
+            .into_iter()
+            .filter(|flag| !supported_options.contains(flag))
+            .collect();
+        if unsupported_options.is_empty() {
+            Ok(())
+        } else {
+            Err(ProblemGenError::InvalidParameters(format!(
+                "{} can't be used with {}",
+                unsupported_options.join(", "),
+                command
+            )))
+        }
+    }
+
+    ///Check whether the instance with the given number is selected by --instances and --instances-range.
+    /// All instances are selected if neither was passed.
+    pub fn is_instance_selected(&self, num: u32) -> bool {
+        let in_instances = self.instances.is_empty() || self.instances.contains(&num);
+        let in_instances_range = match self.instances_range.as_deref() {
+            Some([start, end]) => (*start..*end).contains(&num),
+            _ => true,
+        };
+        in_instances && in_instances_range
+    }
+}
+
+///Number of codomain instances that the codomain generator generates for every input parameter configuration
+pub const CODOMAIN_INSTANCES_PER_CONFIGURATION: u32 = 25;
+
+///Default maximum number of codomain values (M * 2^k) of a single problem instance
+pub const DEFAULT_MAX_CODOMAIN_ENTRIES: u64 = 1 << 28;
+
+///Default number of codomain values that are collected before they are written to the codomain file
+pub const DEFAULT_FLUSH_EVERY: usize = 4096;
+
+///Check that the codomain for the input parameters has at most the given number of values (M * 2^k),
+/// so that it can be generated without attempting a huge allocation.
+pub fn check_codomain_size(
+    input_parameters: &InputParameters,
+    max_codomain_entries: u64,
+) -> Result<(), ProblemGenError> {
+    let codomain_entries = 1u64
+        .checked_shl(input_parameters.k)
+        .and_then(|clique_entries| clique_entries.checked_mul(u64::from(input_parameters.m)));
+
+    match codomain_entries {
+        Some(codomain_entries) if codomain_entries <= max_codomain_entries => Ok(()),
+        _ => Err(ProblemGenError::InvalidParameters(format!(
+            "the codomain for M={} and k={} has M * 2^k = {} values, which exceeds the maximum of {} (see --max-codomain-entries)",
+            input_parameters.m,
+            input_parameters.k,
+            codomain_entries.map_or_else(|| "more than 2^64".to_owned(), |entries| entries.to_string()),
+            max_codomain_entries
+        ))),
+    }
+}
+
+///Get the number of overlapping bits o = round(overlap_ratio * k) for the given overlap ratio,
+/// which should result in an o smaller than k.
+pub fn get_overlap_from_ratio(overlap_ratio: f64, k: u32) -> Result<u32, ProblemGenError> {
+    let o = (overlap_ratio * k as f64).round();
+    if o.is_nan() || o < 0.0 || o >= k as f64 {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "overlap ratio {} results in o = {}, which should be at least 0 and smaller than k = {}",
+            overlap_ratio, o, k
+        )));
+    }
+    Ok(o as u32)
+}
+
+///Current version of the codomain file format, as written in the codomain file header
+pub const CODOMAIN_FILE_VERSION: u32 = 1;
+
+///Literal at the start of the (optional) codomain file header line
+const CODOMAIN_FILE_HEADER_PREFIX: &str = "#codomain-file";
+
+///The optional header of a codomain file. Files without header are version 0, without checksum.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CodomainFileHeader {
+    pub version: u32,
+    pub checksum: u64,
 }
 
 #[derive(StructOpt, Debug)]
@@ -59,8 +273,11 @@ pub enum CodomainCommand {
         m: u32,
         /// The size of the subfunctions
         k: u32,
-        /// The number of overlapping bits between subfunctions
-        o: u32,
+        /// The number of overlapping bits between subfunctions, or the fraction of k with --overlap-ratio
+        o: f64,
+        /// Interpret O as the number of overlapping bits as a fraction of k, so that o = round(O * k)
+        #[structopt(long = "overlap-ratio")]
+        overlap_ratio: bool,
         /// The branching factor
         b: u32,
         /// The output file
@@ -70,84 +287,265 @@ pub enum CodomainCommand {
         #[structopt(subcommand)]
         codomain_function: CodomainFunction,
     },
+    /// List all available codomain functions, with their parameters and a description
+    #[structopt(name = "list-functions")]
+    ListFunctions,
+}
+
+impl CodomainOpt {
+    ///Create the options for the given command and seed, with all other options set to their defaults.
+    /// The other options can be set on the returned options, before passing them to `run_opt`.
+    pub fn new(codomain_command: CodomainCommand, seed: Option<u64>) -> CodomainOpt {
+        CodomainOpt {
+            codomain_command,
+            seed,
+            quiet: false,
+            output_dir: None,
+            skip_existing: false,
+            generation_options: GenerationOptions::default(),
+        }
+    }
+
+    ///Create the options to generate the codomains for the configuration files in the 'problem_generation' folder of every passed folder
+    pub fn folder(folder_paths: Vec<PathBuf>, seed: Option<u64>) -> CodomainOpt {
+        CodomainOpt::new(CodomainCommand::Folder { folder_paths }, seed)
+    }
+
+    ///Create the options to generate the codomains for the configurations in the passed configuration file
+    pub fn file(file_path: PathBuf, seed: Option<u64>) -> CodomainOpt {
+        CodomainOpt::new(CodomainCommand::File { file_path }, seed)
+    }
+
+    ///Create the options to generate a single codomain for the passed input parameters and codomain function, and write it to the output file
+    pub fn instance(
+        input_parameters: InputParameters,
+        output_file_path: PathBuf,
+        codomain_function: CodomainFunction,
+        seed: Option<u64>,
+    ) -> CodomainOpt {
+        CodomainOpt::new(
+            CodomainCommand::Instance {
+                m: input_parameters.m,
+                k: input_parameters.k,
+                o: input_parameters.o as f64,
+                overlap_ratio: false,
+                b: input_parameters.b,
+                output_file_path,
+                codomain_function,
+            },
+            seed,
+        )
+    }
 }
 
 ///Run codomain generator from command line options (structopt)
 pub fn run_opt(codomain_opt: CodomainOpt) -> Result<(), Box<dyn Error>> {
-    let mut rng = get_rng(codomain_opt.seed);
+    let (mut rng, _seed) = get_rng_logged(codomain_opt.seed);
+    let output_dir = codomain_opt.output_dir.as_deref();
+    let generation_options = &codomain_opt.generation_options;
+    generation_options.check_supported_options(&["--topology"], "the codomain generator")?;
+    let skip_existing = codomain_opt.skip_existing;
     match codomain_opt.codomain_command {
         CodomainCommand::Folder { folder_paths} => {
             for folder_path in folder_paths {
-                handle_folder(folder_path, &mut rng)?;
+                handle_folder(
+                    folder_path,
+                    output_dir,
+                    generation_options,
+                    skip_existing,
+                    &mut rng,
+                )?;
             }
             Ok(())
         }
         CodomainCommand::File { file_path } => {
-            handle_input_configuration_file(file_path, &mut rng)
+            handle_input_configuration_file(
+                file_path,
+                output_dir,
+                generation_options,
+                skip_existing,
+                &mut rng,
+            )
         },
         CodomainCommand::Instance {
             m,
             k,
             o,
+            overlap_ratio,
             b,
             output_file_path,
             codomain_function
         } => {
-            let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
-            generate_and_write(&input_parameters, &codomain_function, &output_file_path, &mut rng)?;
+            let o = if overlap_ratio {
+                get_overlap_from_ratio(o, k)?
+            } else if o >= 0.0 && o.fract() == 0.0 && o <= u32::MAX as f64 {
+                o as u32
+            } else {
+                return Err(ProblemGenError::InvalidParameters(format!(
+                    "O ({}) should be a non-negative integer, unless --overlap-ratio is passed",
+                    o
+                ))
+                .into());
+            };
+            let input_parameters = InputParameters::new_from_primitives(m, k, o, b)
+                .with_topology(generation_options.topology);
+            input_parameters.validate()?;
+            check_codomain_size(&input_parameters, generation_options.max_codomain_entries())?;
+            check_codomain_function(&codomain_function, &input_parameters)?;
+            generate_and_write(
+                &input_parameters,
+                &codomain_function,
+                generation_options,
+                &output_file_path,
+                &mut rng,
+            )?;
+            Ok(())
+        }
+        CodomainCommand::ListFunctions => {
+            print!("{}", list_codomain_functions());
             Ok(())
         }
     }
 }
 
 ///Handle codomain generation for a folder: for every entry in it that is not a folder, pass the file to handle_input_file
-fn handle_folder(folder_path: PathBuf, rng: &mut ChaChaRng) -> Result<(), Box<dyn Error>> {
-    //First we remove all folders that are not named codomain_generation
-    folder_path
-        .read_dir()?
-        .map(|file| file.unwrap())
-        .filter(|file| {
-            file.file_type().unwrap().is_dir() && file.file_name() != "codomain_generation"
-        })
-        .map(|file| remove_dir_all(file.path()))
-        .collect::<Result<Vec<()>, std::io::Error>>()?;
+/// If existing files are skipped, the previously generated folders are kept.
+fn handle_folder(
+    folder_path: PathBuf,
+    output_dir: Option<&Path>,
+    generation_options: &GenerationOptions,
+    skip_existing: bool,
+    rng: &mut ChaChaRng,
+) -> Result<(), Box<dyn Error>> {
+    //First we remove all folders that are not named codomain_generation, unless we resume a previous run.
+    // The folders are removed in sorted order and logged, so that the cleanup is the same on every platform and can be audited.
+    if !skip_existing {
+        let removed_folders: Vec<PathBuf> = folder_path
+            .read_dir()?
+            .collect::<Result<Vec<fs::DirEntry>, std::io::Error>>()?
+            .into_iter()
+            .filter(|file| {
+                file.file_type().is_ok_and(|file_type| file_type.is_dir())
+                    && file.file_name() != "codomain_generation"
+            })
+            .map(|file| file.path())
+            .sorted()
+            .collect();
+        for removed_folder in removed_folders {
+            info!("Removing previously generated folder {:?}", removed_folder);
+            remove_dir_all(removed_folder)?;
+        }
+    }
 
     //Then we read every codomain generation file from the codomain_generation folder
     let mut codomain_generation_folder_path = folder_path;
     codomain_generation_folder_path.push("codomain_generation");
-    let file_entries: Vec<PathBuf> = codomain_generation_folder_path
-        .read_dir()?
-        .map(|file| file.unwrap())
-        .filter(|file| !file.file_type().unwrap().is_dir())
-        .map(|file| file.path())
-        .sorted()
-        .collect();
+    let file_entries = get_configuration_files(&codomain_generation_folder_path)?;
 
     //And handle each of them
-    file_entries.into_iter().progress().for_each(|path| {
-        handle_input_configuration_file(path, rng).unwrap();
-    });
+    file_entries.into_iter().progress().try_for_each(|path| {
+        handle_input_configuration_file(path, output_dir, generation_options, skip_existing, rng)
+    })
+}
 
+///Check that the codomain can be generated with the codomain function for the input parameters,
+/// to report an error instead of the panic in generate_codomain: the codomain function should be valid (see CodomainFunction::validate),
+/// the optimum distance of DeceptiveTrapDist and the plateau width of PlateauTrap should fit in a clique,
+///  a generator should be registered for every Custom name,
+///  and the codomain file of FromFile should be readable
+pub fn check_codomain_function(
+    codomain_function: &CodomainFunction,
+    input_parameters: &InputParameters,
+) -> Result<(), ProblemGenError> {
+    codomain_function.validate()?;
+    check_opt_distance(codomain_function, input_parameters.k)?;
+    check_plateau_width(codomain_function, input_parameters.k)?;
+    check_custom_generators(codomain_function)?;
+    if let CodomainFunction::FromFile { codomain_file_path } = codomain_function {
+        read_codomain_values_only(codomain_file_path, input_parameters)?;
+    }
     Ok(())
 }
 
+///Check that the optimum distance of DeceptiveTrapDist, also as a component of a mixture, is in 1..=k
+fn check_opt_distance(codomain_function: &CodomainFunction, k: u32) -> Result<(), ProblemGenError> {
+    match codomain_function {
+        CodomainFunction::DeceptiveTrapDist { opt_distance }
+            if *opt_distance == 0 || *opt_distance > k =>
+        {
+            Err(ProblemGenError::InvalidParameters(format!(
+                "opt_distance should be in 1..=k, found opt_distance = {} for k = {}",
+                opt_distance, k
+            )))
+        }
+        CodomainFunction::Mixture { components } => components
+            .iter()
+            .try_for_each(|(_, codomain_function)| check_opt_distance(codomain_function, k)),
+        _ => Ok(()),
+    }
+}
+
+///Check that the plateau width of PlateauTrap, also as a component of a mixture, is at most k
+fn check_plateau_width(
+    codomain_function: &CodomainFunction,
+    k: u32,
+) -> Result<(), ProblemGenError> {
+    match codomain_function {
+        CodomainFunction::PlateauTrap { w } if *w > k => Err(ProblemGenError::InvalidParameters(
+            format!("w should be at most k, found w = {} for k = {}", w, k),
+        )),
+        CodomainFunction::Mixture { components } => components
+            .iter()
+            .try_for_each(|(_, codomain_function)| check_plateau_width(codomain_function, k)),
+        _ => Ok(()),
+    }
+}
+
+///Check that a codomain generator is registered for every Custom codomain function, also as a component of a mixture
+fn check_custom_generators(codomain_function: &CodomainFunction) -> Result<(), ProblemGenError> {
+    match codomain_function {
+        CodomainFunction::Custom { name } if get_codomain_generator(name).is_none() => {
+            Err(ProblemGenError::InvalidParameters(format!(
+                "no codomain generator registered with name {}",
+                name
+            )))
+        }
+        CodomainFunction::Mixture { components } => components
+            .iter()
+            .try_for_each(|(_, codomain_function)| check_custom_generators(codomain_function)),
+        _ => Ok(()),
+    }
+}
+
 ///Generate codomain from an input file (path), by reading the parameters from it,
 /// getting the output directory path from the filename and generating the codomain 25 times for all input parameters.
+/// If an output directory is passed, the output folder is created in it.
+/// If existing files are skipped, codomain files that already exist and are valid are not written again.
+/// Only the instances selected by the generation options are written.
 fn handle_input_configuration_file(
     input_configuration_file_path: PathBuf,
+    output_dir: Option<&Path>,
+    generation_options: &GenerationOptions,
+    skip_existing: bool,
     rng: &mut ChaChaRng
 ) -> Result<(), Box<dyn Error>> {
     let experiment_parameters = ConfigurationParameters::from_file(&input_configuration_file_path)?;
+    experiment_parameters.check_topology(generation_options.topology)?;
     let codomain_function = experiment_parameters.codomain_function.clone();
     let directory_path_buf = get_output_folder_path_from_configuration_file(
         &input_configuration_file_path,
         "codomain_files",
+        output_dir,
     )?;
 
     //Loop over all input parameters (using custom iterator)
     for input_parameters in experiment_parameters {
+        let input_parameters = input_parameters.with_topology(generation_options.topology);
+        input_parameters.validate()?;
+        check_codomain_size(&input_parameters, generation_options.max_codomain_entries())?;
+        check_codomain_function(&codomain_function, &input_parameters)?;
         //Generate 25 different codomain instances for each input parameter configuration
-        for num in 0..25 {
+        for num in 0..CODOMAIN_INSTANCES_PER_CONFIGURATION {
             let mut output_file_path = directory_path_buf.clone();
             let output_file_name = format!(
                 "{}_{}_{}_{}_{}_{}.txt",
@@ -162,17 +560,56 @@ fn handle_input_configuration_file(
             output_file_path.push(output_file_name);
             //println!("constructed output file path: {:?}", output_file_path);
 
-            generate_and_write(&input_parameters, &codomain_function, &output_file_path, rng)?;
+            let mut seeded_rng = generation_options
+                .seed_per_instance
+                .then(|| get_instance_rng(rng, &input_parameters, num));
+            let instance_rng = seeded_rng.as_mut().unwrap_or(&mut *rng);
+
+            let skip_instance = !generation_options.is_instance_selected(num)
+                || (skip_existing && is_valid_codomain_file(&output_file_path, &input_parameters));
+            if skip_instance {
+                //Generate the codomain without writing it, to advance the rng the same way as when it is written
+                if !generation_options.seed_per_instance {
+                    generate_codomain(&input_parameters, &codomain_function, instance_rng);
+                }
+                continue;
+            }
+
+            generate_and_write(
+                &input_parameters,
+                &codomain_function,
+                generation_options,
+                &output_file_path,
+                instance_rng,
+            )?;
         }
     }
 
     Ok(())
 }
 
+///Check whether the codomain file exists, has the given input parameters, and contains all codomain values
+fn is_valid_codomain_file(codomain_file_path: &Path, input_parameters: &InputParameters) -> bool {
+    let contents = match fs::read_to_string(codomain_file_path) {
+        Ok(contents) => contents,
+        Err(_) => return false,
+    };
+    let mut content_iterator = match split_codomain_file_header(&contents) {
+        Ok((_header, content_iterator)) => content_iterator,
+        Err(_) => return false,
+    };
+    //Skip the codomain function line, and compare the input parameters on the line after it
+    content_iterator.next();
+    let parameters_match = InputParameters::from_line_iterator(&mut content_iterator)
+        .is_ok_and(|parameters| &parameters == input_parameters);
+    parameters_match && read_codomain(input_parameters, codomain_file_path, 2).is_ok()
+}
+
 ///Generate the codomain and write them to the file
 fn generate_and_write(
     input_parameters: &InputParameters,
     codomain_function: &CodomainFunction,
+    generation_options: &GenerationOptions,
     output_file_path: &Path,
     rng: &mut ChaChaRng
 ) -> Result<(), Box<dyn Error>> {
@@ -180,7 +617,9 @@ fn generate_and_write(
         input_parameters,
         codomain_function,
         output_file_path,
-        &generate_codomain(input_parameters, codomain_function, rng),
+        &generate_codomain_with_options(input_parameters, codomain_function, generation_options, rng),
+        generation_options.codomain_checksum,
+        generation_options.flush_every(),
     )?;
     Ok(())
 }
@@ -189,20 +628,27 @@ fn generate_and_write(
 pub fn generate_write_return(
     input_parameters: &InputParameters,
     codomain_function: &CodomainFunction,
+    generation_options: &GenerationOptions,
     output_file_path: &Path,
     rng: &mut ChaChaRng
 ) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
-    let codomain = generate_codomain(input_parameters, codomain_function, rng);
+    let codomain =
+        generate_codomain_with_options(input_parameters, codomain_function, generation_options, rng);
     write_codomain(
         input_parameters,
         codomain_function,
         output_file_path,
         &codomain,
+        generation_options.codomain_checksum,
+        generation_options.flush_every(),
     )?;
     Ok(codomain)
 }
 
-///Generate the codomain, by calling the right generation function
+///Generate the codomain, by calling the right generation function.
+/// Custom codomain functions are looked up in the codomain generator registry,
+///  and a codomain file is read for FromFile, panicking if the generator isn't registered or the file can't be read;
+///   check_codomain_function reports these as errors instead.
 pub fn generate_codomain(
     input_parameters: &InputParameters,
     codomain_function: &CodomainFunction,
@@ -213,29 +659,345 @@ pub fn generate_codomain(
         CodomainFunction::Trap => generate_trap(input_parameters, 2.5),
         CodomainFunction::DeceptiveTrap => generate_trap_general(input_parameters, rng), // generate_trap(input_parameters, 1.0),
         CodomainFunction::NKq { q } => generate_nk_q(input_parameters, *q, rng),
+        CodomainFunction::NKqSigned { q } => generate_nk_q_signed(input_parameters, *q, rng),
         CodomainFunction::NKp { p } => generate_nk_p(input_parameters, *p, rng),
         CodomainFunction::RandomDeceptiveTrap { p_deceptive } => {
             generate_random_trap(input_parameters, *p_deceptive, rng)
         }
+        CodomainFunction::DeceptiveTrapScaled {
+            deceptor_value,
+            optimum_value,
+        } => generate_trap_scaled(input_parameters, *deceptor_value, *optimum_value, rng),
+        CodomainFunction::DeceptiveTrapDist { opt_distance } => {
+            generate_trap_dist(input_parameters, *opt_distance, rng)
+        }
+        CodomainFunction::PlateauTrap { w } => generate_plateau_trap(input_parameters, *w, rng),
+        CodomainFunction::StepTrap { step_size } => {
+            generate_step_trap(input_parameters, *step_size, rng)
+        }
+        CodomainFunction::RoyalRoad { block_reward } => {
+            generate_royal_road(input_parameters, *block_reward)
+        }
+        CodomainFunction::MaxCut { edge_probability } => {
+            generate_max_cut(input_parameters, *edge_probability, rng)
+        }
+        CodomainFunction::GaussianRandom { mean, stddev } => {
+            generate_gaussian_random(input_parameters, *mean, *stddev, rng)
+        }
+        CodomainFunction::Mixture { components } => {
+            generate_mixture(input_parameters, components, rng)
+        }
+        CodomainFunction::FromFile { codomain_file_path } => {
+            read_codomain_values_only(codomain_file_path, input_parameters).unwrap_or_else(|error| {
+                panic!("Could not read codomain file {:?}: {}", codomain_file_path, error)
+            })
+        }
+        CodomainFunction::Custom { name } => match get_codomain_generator(name) {
+            Some(generator) => generator.generate(input_parameters, rng),
+            None => panic!("No codomain generator registered with name {}", name),
+        },
         CodomainFunction::Unknown => panic!("We can't generate codomain for unknown codomain"),
     }
 }
 
-///Write the codomain to the passed file
-fn write_codomain(
+///Generate the codomain with a separate random number generator per clique, and return the seed used for every clique as well.
+/// The seed of every clique is drawn from the passed rng, so that any single clique can be regenerated in isolation
+/// with generate_clique_codomain. Returns an error for FromFile, as the codomain of a single clique can't be read from a file.
+pub fn generate_codomain_per_clique(
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    rng: &mut ChaChaRng,
+) -> Result<(Vec<Vec<f64>>, Vec<u64>), ProblemGenError> {
+    check_per_clique_codomain_function(codomain_function, input_parameters)?;
+    let clique_seeds: Vec<u64> = (0..input_parameters.m).map(|_| rng.next_u64()).collect();
+    //The codomain of clique-invariant functions does not depend on the seed, so it is generated only once
+    let codomain = if codomain_is_clique_invariant(codomain_function) && !clique_seeds.is_empty() {
+        let clique_codomain =
+            generate_clique_codomain(input_parameters, codomain_function, clique_seeds[0])?;
+        vec![clique_codomain; clique_seeds.len()]
+    } else {
+        clique_seeds
+            .iter()
+            .map(|clique_seed| {
+                generate_clique_codomain(input_parameters, codomain_function, *clique_seed)
+            })
+            .collect::<Result<_, _>>()?
+    };
+    Ok((codomain, clique_seeds))
+}
+
+///Generate the codomain values of a single clique from its seed, as recorded by generate_codomain_per_clique.
+/// Returns an error for FromFile, as the codomain of a single clique can't be read from a file.
+pub fn generate_clique_codomain(
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    clique_seed: u64,
+) -> Result<Vec<f64>, ProblemGenError> {
+    check_per_clique_codomain_function(codomain_function, input_parameters)?;
+    let clique_parameters = InputParameters::new_from_primitives(
+        1,
+        input_parameters.k,
+        input_parameters.o,
+        input_parameters.b,
+    );
+    let mut clique_rng = ChaChaRng::seed_from_u64(clique_seed);
+    Ok(generate_codomain(&clique_parameters, codomain_function, &mut clique_rng)
+        .pop()
+        .unwrap())
+}
+
+///Check that the codomain function can generate the codomain of a single clique, which is not the case for FromFile
+fn check_per_clique_codomain_function(
+    codomain_function: &CodomainFunction,
+    input_parameters: &InputParameters,
+) -> Result<(), ProblemGenError> {
+    match codomain_function {
+        CodomainFunction::FromFile { .. } => Err(ProblemGenError::InvalidParameters(
+            "the codomain of a single clique can't be read from a codomain file".to_owned(),
+        )),
+        _ => check_codomain_function(codomain_function, input_parameters),
+    }
+}
+
+///Generate the codomain as a single contiguous vector of length M * 2^k, with the values of the cliques in order,
+/// together with the stride 2^k between the starts of consecutive cliques. The values are the same as those of generate_codomain,
+/// so that the flat codomain can be passed to numerical libraries without copying every clique separately.
+/// The flat codomain is allocated once, and the values of every clique are generated separately and copied into its slice of the flat codomain,
+///  so that only one clique's values are allocated besides it. FromFile and Custom can only provide the nested codomain, which is copied as a whole.
+pub fn generate_codomain_flat(
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    rng: &mut ChaChaRng,
+) -> (Vec<f64>, usize) {
+    let k = input_parameters.k;
+    let stride = 1usize << k;
+    let mut flat_codomain = vec![0.0; input_parameters.m as usize * stride];
+    match codomain_function {
+        CodomainFunction::FromFile { .. } | CodomainFunction::Custom { .. } => {
+            let codomain = generate_codomain(input_parameters, codomain_function, rng);
+            for (flat_clique_codomain, clique_codomain) in
+                flat_codomain.chunks_mut(stride).zip(codomain)
+            {
+                flat_clique_codomain.copy_from_slice(&clique_codomain);
+            }
+        }
+        //The shuffled indices of NKp carry over from one clique to the next, as in generate_nk_p
+        CodomainFunction::NKp { p } => {
+            let mut codomain_clique_indices: Vec<u32> = (0..(1 << k)).collect();
+            for flat_clique_codomain in flat_codomain.chunks_mut(stride) {
+                flat_clique_codomain.copy_from_slice(&generate_nk_p_clique(
+                    k,
+                    *p,
+                    &mut codomain_clique_indices,
+                    rng,
+                ));
+            }
+        }
+        _ => {
+            for flat_clique_codomain in flat_codomain.chunks_mut(stride) {
+                flat_clique_codomain.copy_from_slice(&generate_single_clique(
+                    input_parameters,
+                    codomain_function,
+                    rng,
+                ));
+            }
+        }
+    }
+    (flat_codomain, stride)
+}
+
+///Convert a flat codomain with the given stride, as returned by generate_codomain_flat, to the nested codomain [M][2^k]
+pub fn nest_flat_codomain(flat_codomain: &[f64], stride: usize) -> Vec<Vec<f64>> {
+    flat_codomain
+        .chunks(stride)
+        .map(|clique_codomain| clique_codomain.to_vec())
+        .collect()
+}
+
+///Generate the codomain and apply the post-processing enabled in the generation options
+pub fn generate_codomain_with_options(
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    generation_options: &GenerationOptions,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let mut codomain = generate_codomain(input_parameters, codomain_function, rng);
+    if generation_options.complement_optimum {
+        symmetrize_codomain(&mut codomain);
+    }
+    codomain
+}
+
+///Generate a codomain whose problem has (as close as possible to) the target number of global optima.
+/// The target is factorized greedily into a number of optimal substrings per clique (at most 2^k each),
+///  after which every clique gets that many optimal substrings with value 1, and random values in [0, 1) for all other substrings.
+/// Returns the codomain together with the achieved number of global optima, which is the product of the numbers of optima per clique.
+/// This count is only exact for separable problems (o = 0), and only targets that are such a product can be achieved exactly.
+pub fn generate_codomain_with_target_optima(
+    input_parameters: &InputParameters,
+    target: u64,
+    rng: &mut ChaChaRng,
+) -> (Vec<Vec<f64>>, u64) {
+    assert!(target > 0, "the target number of global optima should be positive");
+    let clique_size = 1u64 << input_parameters.k;
+
+    let mut remaining_target = target;
+    let mut codomain = Vec::with_capacity(input_parameters.m as usize);
+    for _ in 0..input_parameters.m {
+        //Use the largest divisor of the remaining target that fits in the clique as its number of optima
+        let number_clique_optima = (1..=remaining_target.min(clique_size))
+            .rev()
+            .find(|&divisor| remaining_target.is_multiple_of(divisor))
+            .unwrap_or(1);
+        remaining_target /= number_clique_optima;
+
+        let mut clique_codomain = generate_random_clique(input_parameters.k, rng);
+        for substring_index in
+            rand::seq::index::sample(rng, clique_size as usize, number_clique_optima as usize)
+        {
+            clique_codomain[substring_index] = 1.0;
+        }
+        codomain.push(clique_codomain);
+    }
+
+    let achieved_optima = CliqueTree::count_global_optima_separable(
+        input_parameters,
+        &codomain,
+        DEFAULT_FITNESS_EPSILON,
+    );
+    (codomain, u64::try_from(achieved_optima).unwrap_or(u64::MAX))
+}
+
+///Generate a clique tree whose unique global optimum is the planted target solution.
+/// First, the codomain is generated with the base codomain function and the structure is constructed, like in CliqueTree::new.
+/// Then, in every clique, the codomain value of the target's substring is bumped to at least UNIQUE_OPTIMUM_PERTURBATION
+///  above the highest value of the other substrings. Every other solution differs from the target in at least one clique,
+///  so the target is strictly better than all other solutions, which is confirmed by the calculated global optima.
+pub fn generate_with_planted_optimum(
+    input_parameters: &InputParameters,
+    target: &[u32],
+    base_function: &CodomainFunction,
+    rng: &mut ChaChaRng,
+) -> Result<CliqueTree, ProblemGenError> {
+    let problem_size = input_parameters.problem_size();
+    if target.len() != problem_size as usize || target.iter().any(|&bit| bit > 1) {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "the planted target should be a bit string of length {}, the problem size",
+            problem_size
+        )));
+    }
+
+    let mut codomain_values = generate_codomain(input_parameters, base_function, rng);
+    let (cliques, separators) = CliqueTree::construct(input_parameters, rng);
+
+    for (clique, clique_codomain) in cliques.iter().zip(codomain_values.iter_mut()) {
+        let clique_substring: Vec<u32> = clique
+            .iter()
+            .map(|&variable_index| target[variable_index as usize])
+            .collect();
+        let target_index = transform_substring_vector_to_index(&clique_substring) as usize;
+
+        let highest_other_value = clique_codomain
+            .iter()
+            .enumerate()
+            .filter(|&(substring_index, _)| substring_index != target_index)
+            .map(|(_, &value)| value)
+            .fold(f64::NEG_INFINITY, f64::max);
+        clique_codomain[target_index] =
+            clique_codomain[target_index].max(highest_other_value + UNIQUE_OPTIMUM_PERTURBATION);
+    }
+
+    let global_opt_tuples = CliqueTree::calculate_global_optima(
+        input_parameters,
+        base_function,
+        &codomain_values,
+        &cliques,
+        &separators,
+        DEFAULT_FITNESS_EPSILON,
+    );
+    if global_opt_tuples.len() != 1 || global_opt_tuples[0].0 != target {
+        return Err(ProblemGenError::InvalidParameters(
+            "the planted target is not the unique global optimum".to_owned(),
+        ));
+    }
+    let glob_optima_score = global_opt_tuples[0].1;
+
+    Ok(CliqueTree {
+        input_parameters: input_parameters.clone(),
+        codomain_function: base_function.clone(),
+        variable_cliques: get_variable_cliques(&cliques),
+        cliques,
+        separators,
+        codomain_values,
+        glob_optima_strings: vec![target.to_vec()],
+        glob_optima_score,
+        fitness_epsilon: DEFAULT_FITNESS_EPSILON,
+    })
+}
+
+///Symmetrize the codomain, by setting the values of every substring and its complement (bitwise inverse)
+/// to the highest of the two. This way, the complement of every global optimum is a global optimum as well.
+pub fn symmetrize_codomain(codomain: &mut [Vec<f64>]) {
+    for clique_codomain in codomain {
+        let complement_mask = clique_codomain.len() - 1;
+        for substring_index in 0..clique_codomain.len() / 2 {
+            let complement_index = complement_mask - substring_index;
+            let value = clique_codomain[substring_index].max(clique_codomain[complement_index]);
+            clique_codomain[substring_index] = value;
+            clique_codomain[complement_index] = value;
+        }
+    }
+}
+
+///Write the codomain to the passed file through a BufWriter, writing the codomain values in chunks of flush_every values
+pub(crate) fn write_codomain(
     input_parameters: &InputParameters,
     codomain_function: &CodomainFunction,
     file_path: &Path,
     codomain: &[Vec<f64>],
+    write_header: bool,
+    flush_every: usize,
 ) -> Result<(), Box<dyn Error>> {
+    input_parameters.check_parameters_line()?;
     let file = File::create(file_path)?;
-    let mut buf_writer = BufWriter::new(file);
+    write_codomain_to_writer(
+        BufWriter::new(file),
+        input_parameters,
+        codomain_function,
+        codomain,
+        write_header,
+        flush_every,
+    )
+}
+
+///Write the codomain to the writer, in the format of a codomain file.
+/// The lines are collected in a buffer, which is written to the writer after every flush_every codomain values (at least 1),
+///  so that the number of write calls does not grow with the number of values.
+pub fn write_codomain_to_writer<W: Write>(
+    mut writer: W,
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    codomain: &[Vec<f64>],
+    write_header: bool,
+    flush_every: usize,
+) -> Result<(), Box<dyn Error>> {
+    input_parameters.check_parameters_line()?;
+    let flush_every = flush_every.max(1);
     let mut write_buffer = String::new();
 
+    //Optionally, write the header with the format version and checksum before everything else
+    if write_header {
+        writeln!(
+            write_buffer,
+            "{} {} {:016x}",
+            CODOMAIN_FILE_HEADER_PREFIX,
+            CODOMAIN_FILE_VERSION,
+            calculate_codomain_checksum(codomain)
+        )?;
+    }
+
     //Write the codomain function on the first line
     writeln!(write_buffer, "{}", codomain_function)?;
-    buf_writer.write_all(write_buffer.as_bytes())?;
-    write_buffer.clear();
 
     //Write the input parameters on the second line
     writeln!(
@@ -243,20 +1005,19 @@ fn write_codomain(
         "{} {} {} {}",
         input_parameters.m, input_parameters.k, input_parameters.o, input_parameters.b
     )?;
-    buf_writer.write_all(write_buffer.as_bytes())?;
-    write_buffer.clear();
-
-    //Write all codomain values on the subsequent lines
-    for clique in codomain {
-        for value in clique {
-            writeln!(write_buffer, "{}", value)?;
-            buf_writer.write_all(write_buffer.as_bytes())?;
+
+    //Write all codomain values on the subsequent lines, writing the buffer after every flush_every values
+    for (value_index, value) in codomain.iter().flatten().enumerate() {
+        writeln!(write_buffer, "{}", value)?;
+        if (value_index + 1) % flush_every == 0 {
+            writer.write_all(write_buffer.as_bytes())?;
             write_buffer.clear();
         }
     }
 
-    //Flush all data still in the buffer
-    buf_writer.flush()?;
+    //Write the remaining lines and flush all data
+    writer.write_all(write_buffer.as_bytes())?;
+    writer.flush()?;
 
     Ok(())
 }
@@ -267,15 +1028,34 @@ pub fn get_codomain_from_iterator(
     content_iterator: &mut Lines,
     skip_number_lines: u32,
     input_parameters: &InputParameters,
-) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
-    let mut content_iterator = content_iterator.skip(skip_number_lines as usize);
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    get_codomain_from_lines(
+        content_iterator.map(Ok),
+        skip_number_lines,
+        input_parameters,
+    )
+}
+
+///Get the codomain values from an iterator over (fallible) lines, such as the lines of a BufRead.
+/// First skip a given number of lines and then read all the values, consuming only one line at a time.
+pub fn get_codomain_from_lines<S: AsRef<str>>(
+    lines: impl Iterator<Item = Result<S, ProblemGenError>>,
+    skip_number_lines: u32,
+    input_parameters: &InputParameters,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    let mut lines = lines.skip(skip_number_lines as usize);
+    let expected = input_parameters.m as usize * (1 << input_parameters.k) as usize;
     let mut codomain = Vec::with_capacity(input_parameters.m as usize);
-    for _i in 0..(input_parameters.m as usize) {
+    for i in 0..(input_parameters.m as usize) {
         let mut clique_codomain = Vec::with_capacity((1 << input_parameters.k) as usize);
-        for _j in 0..(1 << input_parameters.k) {
-            let fitness: f64 = content_iterator
+        for j in 0..(1 << input_parameters.k) {
+            let fitness: f64 = lines
                 .next()
-                .ok_or("Codomain file does not contain enough entries")?
+                .ok_or(ProblemGenError::CodomainDimensionMismatch {
+                    expected,
+                    found: i * (1 << input_parameters.k) as usize + j,
+                })??
+                .as_ref()
                 .parse()?;
             clique_codomain.push(fitness);
         }
@@ -285,14 +1065,246 @@ pub fn get_codomain_from_iterator(
     Ok(codomain)
 }
 
-///Read the codomain values from a file at the given path
+///Read the codomain values from a file at the given path.
+/// The number of lines to skip does not include the optional header; if the file has a header,
+///  the codomain values are verified against its checksum.
 pub fn read_codomain(
     input_parameters: &InputParameters,
     codomain_file: &Path,
     skip_number_lines: u32,
-) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
     let contents = fs::read_to_string(codomain_file)?;
     //println!("contents of file: {}", contents);
+    let (header, mut content_iterator) = split_codomain_file_header(&contents)?;
+    let codomain =
+        get_codomain_from_iterator(&mut content_iterator, skip_number_lines, input_parameters);
+
+    verify_codomain_with_header(header, codomain)
+}
+
+///Read the codomain values from a file at the given path, given the input parameters of the problem,
+/// whether or not the file contains the input parameters line. Legacy codomain files only contain the codomain function
+///  followed by the values, so the line after the (optional) codomain function is only skipped if it consists of four integers.
+///   If the file does contain the input parameters, they should match the given input parameters.
+pub fn read_codomain_with_known_parameters(
+    input_parameters: &InputParameters,
+    codomain_file: &Path,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    let contents = fs::read_to_string(codomain_file)?;
+    let (_codomain_function, codomain) =
+        parse_codomain_with_known_parameters(input_parameters, &contents)?;
+    Ok(codomain)
+}
+
+///Parse the codomain function and codomain values from the contents of a codomain file, given the input parameters of the problem,
+/// as described for read_codomain_with_known_parameters. The codomain function is Unknown if the contents don't contain it.
+pub(crate) fn parse_codomain_with_known_parameters(
+    input_parameters: &InputParameters,
+    contents: &str,
+) -> Result<(CodomainFunction, Vec<Vec<f64>>), ProblemGenError> {
+    let (header, mut content_iterator) = split_codomain_file_header(contents)?;
+
+    //Read the codomain function, if the contents contain it
+    let codomain_function = match content_iterator.clone().next() {
+        Some(first_line) if codomain_line_is_codomain_function(first_line) => {
+            content_iterator.next();
+            CodomainFunction::from_line(first_line)?
+        }
+        _ => CodomainFunction::Unknown,
+    };
+
+    //Skip the input parameters, if the file contains them, after checking that they match the given input parameters
+    if content_iterator
+        .clone()
+        .next()
+        .is_some_and(is_input_parameters_line)
+    {
+        let file_input_parameters = InputParameters::from_line_iterator(&mut content_iterator)?;
+        //The parameters line does not contain o_per_level, so only M, k, o, and b are compared
+        if file_input_parameters
+            != InputParameters::new_from_primitives(
+                input_parameters.m,
+                input_parameters.k,
+                input_parameters.o,
+                input_parameters.b,
+            )
+        {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "the input parameters in the codomain file ({} {} {} {}) do not match the given input parameters ({} {} {} {})",
+                file_input_parameters.m,
+                file_input_parameters.k,
+                file_input_parameters.o,
+                file_input_parameters.b,
+                input_parameters.m,
+                input_parameters.k,
+                input_parameters.o,
+                input_parameters.b
+            )));
+        }
+    }
+
+    let codomain = get_codomain_from_iterator(&mut content_iterator, 0, input_parameters);
+    let codomain = verify_codomain_with_header(header, codomain)?;
+    Ok((codomain_function, codomain))
+}
+
+///Check whether the line of a codomain file is the codomain function, i.e. whether it is neither a line of input parameters nor a value
+pub(crate) fn codomain_line_is_codomain_function(line: &str) -> bool {
+    !is_input_parameters_line(line) && line.trim().parse::<f64>().is_err()
+}
+
+///Read the codomain values from a file at the given path that contains only the values, e.g. exported by another tool.
+/// The values may be separated by any amount of whitespace, including newlines, so they don't need to be on separate lines,
+///  but the file should contain exactly the M * 2^k values the input parameters specify.
+pub fn read_codomain_values_only(
+    codomain_file: &Path,
+    input_parameters: &InputParameters,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    let contents = fs::read_to_string(codomain_file)?;
+    let values = contents
+        .split_whitespace()
+        .map(|value| value.parse())
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    let clique_codomain_size = 1 << input_parameters.k;
+    let expected = input_parameters.m as usize * clique_codomain_size;
+    if values.len() != expected {
+        return Err(ProblemGenError::CodomainDimensionMismatch {
+            expected,
+            found: values.len(),
+        });
+    }
+
+    Ok(values
+        .chunks(clique_codomain_size)
+        .map(|clique_codomain| clique_codomain.to_vec())
+        .collect())
+}
+
+///Read the codomain values from a file at the given path, like read_codomain,
+/// but reading the file line by line, so that the whole file is never loaded into memory
+pub fn read_codomain_buffered(
+    input_parameters: &InputParameters,
+    codomain_file: &Path,
+    skip_number_lines: u32,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    let reader = BufReader::new(File::open(codomain_file)?);
+    read_codomain_from_reader(reader, input_parameters, skip_number_lines)
+}
+
+///Read the codomain values from a reader with the contents of a codomain file, one line at a time.
+/// The number of lines to skip does not include the optional header; if there is a header,
+///  the codomain values are verified against its checksum.
+pub fn read_codomain_from_reader<R: BufRead>(
+    reader: R,
+    input_parameters: &InputParameters,
+    skip_number_lines: u32,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    let mut lines = reader
+        .lines()
+        .map(|line| line.map_err(ProblemGenError::from));
+
+    //Put the first line back in front of the other lines, if it is not the header
+    let first_line = lines.next().transpose()?;
+    let (header, first_line) = match first_line {
+        Some(line) if line.starts_with(CODOMAIN_FILE_HEADER_PREFIX) => {
+            (Some(parse_codomain_file_header(&line)?), None)
+        }
+        line => (None, line),
+    };
+    let lines = first_line.map(Ok).into_iter().chain(lines);
+
+    let codomain = get_codomain_from_lines(lines, skip_number_lines, input_parameters);
+    verify_codomain_with_header(header, codomain)
+}
+
+///Verify the read codomain values against the header of the codomain file, if there is one.
+/// With a header, missing values mean that the file was truncated, and the checksum of the values should match the header.
+fn verify_codomain_with_header(
+    header: Option<CodomainFileHeader>,
+    codomain: Result<Vec<Vec<f64>>, ProblemGenError>,
+) -> Result<Vec<Vec<f64>>, ProblemGenError> {
+    match header {
+        None => codomain,
+        Some(header) => {
+            //With a header, missing values mean the file was truncated
+            let codomain = codomain.map_err(|error| match error {
+                ProblemGenError::CodomainDimensionMismatch { expected, found } => {
+                    ProblemGenError::TruncatedFile { expected, found }
+                }
+                error => error,
+            })?;
+            let checksum = calculate_codomain_checksum(&codomain);
+            if checksum != header.checksum {
+                return Err(ProblemGenError::ChecksumMismatch {
+                    expected: header.checksum,
+                    found: checksum,
+                });
+            }
+            Ok(codomain)
+        }
+    }
+}
+
+///Split the optional header from the contents of a codomain file,
+/// returning the header (if any) and an iterator over the remaining lines
+pub fn split_codomain_file_header(
+    contents: &str,
+) -> Result<(Option<CodomainFileHeader>, Lines<'_>), ProblemGenError> {
     let mut content_iterator = contents.lines();
-    get_codomain_from_iterator(&mut content_iterator, skip_number_lines, input_parameters)
+    let first_line = match contents.lines().next() {
+        Some(line) if line.starts_with(CODOMAIN_FILE_HEADER_PREFIX) => line,
+        _ => return Ok((None, content_iterator)),
+    };
+    content_iterator.next();
+
+    Ok((
+        Some(parse_codomain_file_header(first_line)?),
+        content_iterator,
+    ))
+}
+
+///Parse the codomain file header line, with the format version and the checksum
+fn parse_codomain_file_header(line: &str) -> Result<CodomainFileHeader, ProblemGenError> {
+    let header_values: Vec<&str> = line.split_whitespace().skip(1).collect();
+    if header_values.len() != 2 {
+        return Err(ProblemGenError::Parse(format!(
+            "could not parse codomain file header: {}",
+            line
+        )));
+    }
+    let version: u32 = header_values[0].parse()?;
+    if version > CODOMAIN_FILE_VERSION {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "unsupported codomain file version {}, the latest supported version is {}",
+            version, CODOMAIN_FILE_VERSION
+        )));
+    }
+    let checksum = u64::from_str_radix(header_values[1], 16)?;
+
+    Ok(CodomainFileHeader { version, checksum })
+}
+
+///Offset basis of the 64-bit FNV-1a hash, the hash of no bytes at all
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+///Update the 64-bit FNV-1a hash with the passed bytes, and return the updated hash
+pub(crate) fn update_fnv_hash(mut hash: u64, bytes: &[u8]) -> u64 {
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+///Calculate the checksum of the codomain values, as the 64-bit FNV-1a hash of the bits of all values
+pub fn calculate_codomain_checksum(codomain: &[Vec<f64>]) -> u64 {
+    codomain
+        .iter()
+        .flatten()
+        .fold(FNV_OFFSET_BASIS, |hash, value| {
+            update_fnv_hash(hash, &value.to_bits().to_le_bytes())
+        })
 }
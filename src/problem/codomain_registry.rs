@@ -0,0 +1,54 @@
+/*!
+Module for the registry of custom codomain generators, so that codomain functions can be added without editing the crate.
+*/
+
+use rand_chacha::ChaChaRng;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use super::clique_tree::InputParameters;
+
+///Trait for codomain generators that can be registered and used with `CodomainFunction::Custom`
+pub trait CodomainGenerator: Send + Sync {
+    ///Generate the codomain values for all cliques/subfunctions, structured as [M][2^k]
+    fn generate(&self, input_parameters: &InputParameters, rng: &mut ChaChaRng) -> Vec<Vec<f64>>;
+    ///Get the name of the generator, used to register and look up the generator
+    fn io_name(&self) -> String;
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn CodomainGenerator>>>;
+
+///Get the global registry, initializing it on first use
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+///Register a custom codomain generator under its io_name, replacing any generator previously registered under that name
+pub fn register_codomain_generator<G: CodomainGenerator + 'static>(generator: G) {
+    registry()
+        .write()
+        .expect("codomain generator registry lock was poisoned")
+        .insert(generator.io_name(), Arc::new(generator));
+}
+
+///Remove the custom codomain generator with the given name from the registry, returning whether it was registered
+pub fn unregister_codomain_generator(name: &str) -> bool {
+    registry()
+        .write()
+        .expect("codomain generator registry lock was poisoned")
+        .remove(name)
+        .is_some()
+}
+
+///Get the custom codomain generator registered under the given name, if any
+pub fn get_codomain_generator(name: &str) -> Option<Arc<dyn CodomainGenerator>> {
+    registry()
+        .read()
+        .expect("codomain generator registry lock was poisoned")
+        .get(name)
+        .cloned()
+}
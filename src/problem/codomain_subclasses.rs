@@ -5,12 +5,17 @@ Module with all implemented codomain subclasses that can be generated with the c
 use structopt::StructOpt;
 
 use super::clique_tree::{get_possible_substrings, InputParameters};
+use super::codomain_registry::get_codomain_generator;
+use super::configuration::shuffle;
+use super::error::ProblemGenError;
 
 use std::fmt;
+use std::path::PathBuf;
 
 use rand::distributions::Uniform;
 use rand::prelude::*;
 use rand_chacha::ChaChaRng;
+use rand_distr::Normal;
 
 ///Enum to represent various codomain classes
 #[derive(Debug, StructOpt, PartialOrd, PartialEq, Clone)]
@@ -23,6 +28,11 @@ pub enum CodomainFunction {
     NKq {
         q: u32,
     },
+    ///Signed NKq, where every codomain value is a random integer in -q..=q, without normalization to [0, 1]
+    #[structopt(name = "nk-q-signed")]
+    NKqSigned {
+        q: u32,
+    },
     #[structopt(name = "nk-p")]
     NKp {
         p: f64,
@@ -31,10 +41,101 @@ pub enum CodomainFunction {
     RandomDeceptiveTrap {
         p_deceptive: f64,
     },
+    ///Deceptive trap with configurable values, where the deceptive attractor has value deceptor_value and the local optimum has value optimum_value
+    DeceptiveTrapScaled {
+        deceptor_value: f64,
+        optimum_value: f64,
+    },
+    ///Deceptive trap where the local optimum is at hamming distance opt_distance (0 < opt_distance <= k) from the deceptive attractor,
+    /// instead of being its inverse
+    DeceptiveTrapDist {
+        opt_distance: u32,
+    },
+    ///Trap with a plateau, where all bit strings within hamming distance w (exclusive) of the deceptive attractor have the same value
+    PlateauTrap {
+        w: u32,
+    },
+    ///Trap with steps, where the value increases in steps of step_size hamming distance units away from the deceptive attractor
+    StepTrap {
+        step_size: u32,
+    },
+    ///Royal road, where every clique/subfunction awards block_reward if all its bits are 1, and 0 otherwise
+    RoyalRoad {
+        block_reward: f64,
+    },
+    ///Max-cut, where every clique/subfunction is a graph whose edges are sampled with probability edge_probability,
+    /// and the value of a substring is the number of edges cut by the partition it represents
+    MaxCut {
+        edge_probability: f64,
+    },
+    ///Gaussian random, where every codomain value is sampled from the normal distribution with the given mean and standard deviation
+    GaussianRandom {
+        mean: f64,
+        stddev: f64,
+    },
+    ///Mixture of codomain functions, where every clique/subfunction independently picks one of the component codomain functions,
+    /// with probability proportional to the component's weight
+    Mixture {
+        ///Components as weight:function, with the parameters of the function separated by ':' as well, e.g. 0.5:nk-q:4
+        #[structopt(parse(try_from_str = parse_mixture_component))]
+        components: Vec<(f64, CodomainFunction)>,
+    },
+    ///Codomain read from a file that contains only the whitespace-separated codomain values, e.g. of a published benchmark instance,
+    /// attached to a freshly generated structure. See read_codomain_values_only for the file format.
+    #[structopt(name = "from-file")]
+    FromFile {
+        #[structopt(parse(from_os_str))]
+        codomain_file_path: PathBuf,
+    },
+    ///Custom codomain function, generated by the codomain generator registered under the given name
+    Custom {
+        name: String,
+    },
     Unknown,
 }
 
 impl CodomainFunction {
+    ///Parse the codomain function from a line like "nk-q 4", as used in configuration and codomain files
+    pub fn from_line(line: &str) -> Result<CodomainFunction, ProblemGenError> {
+        let mut iter_list = vec![" "];
+        iter_list.extend(line.trim().split(' '));
+        let codomain_function = CodomainFunction::from_iter_safe(iter_list)
+            .map_err(|_| ProblemGenError::UnknownCodomainFunction(line.to_owned()))?;
+        codomain_function.validate()?;
+        Ok(codomain_function)
+    }
+
+    ///Check that the codomain function can generate codomain values: the standard deviation of gaussian random should be
+    /// finite and non-negative, the step size of step trap should be at least 1, and the weights of a mixture should sum to a positive value
+    pub fn validate(&self) -> Result<(), ProblemGenError> {
+        if let CodomainFunction::StepTrap { step_size: 0 } = self {
+            return Err(ProblemGenError::InvalidParameters(
+                "the step size of step trap should be at least 1, found 0".to_owned(),
+            ));
+        }
+        if let CodomainFunction::GaussianRandom { stddev, .. } = self {
+            if !stddev.is_finite() || *stddev < 0.0 {
+                return Err(ProblemGenError::InvalidParameters(format!(
+                    "the standard deviation of gaussian random should be finite and non-negative, found {}",
+                    stddev
+                )));
+            }
+        }
+        if let CodomainFunction::Mixture { components } = self {
+            let total_weight: f64 = components.iter().map(|(weight, _)| weight).sum();
+            if total_weight.is_nan() || total_weight <= 0.0 {
+                return Err(ProblemGenError::InvalidParameters(format!(
+                    "the weights of the mixture components should sum to a positive value, found {}",
+                    total_weight
+                )));
+            }
+            for (_, codomain_function) in components {
+                codomain_function.validate()?;
+            }
+        }
+        Ok(())
+    }
+
     //Get string representation of CodomainFunction, for use with filenames
     pub fn to_io_string(&self) -> String {
         match &self {
@@ -42,14 +143,201 @@ impl CodomainFunction {
             CodomainFunction::Trap => "trap".to_owned(),
             CodomainFunction::DeceptiveTrap => "deceptive-trap".to_owned(),
             CodomainFunction::NKq { q } => format!("nk-q-{}", q),
+            CodomainFunction::NKqSigned { q } => format!("nk-q-signed-{}", q),
             CodomainFunction::NKp { p } => format!("nk-p-{}", p),
             CodomainFunction::RandomDeceptiveTrap { p_deceptive } => {
                 format!("random-deceptive-trap-{}", p_deceptive)
             }
+            CodomainFunction::DeceptiveTrapScaled {
+                deceptor_value,
+                optimum_value,
+            } => format!("deceptive-trap-scaled-{}-{}", deceptor_value, optimum_value),
+            CodomainFunction::DeceptiveTrapDist { opt_distance } => {
+                format!("deceptive-trap-dist-{}", opt_distance)
+            }
+            CodomainFunction::PlateauTrap { w } => format!("plateau-trap-{}", w),
+            CodomainFunction::StepTrap { step_size } => format!("step-trap-{}", step_size),
+            CodomainFunction::RoyalRoad { block_reward } => format!("royal-road-{}", block_reward),
+            CodomainFunction::MaxCut { edge_probability } => {
+                format!("max-cut-{}", edge_probability)
+            }
+            CodomainFunction::GaussianRandom { mean, stddev } => {
+                format!("gaussian-{}-{}", mean, stddev)
+            }
+            CodomainFunction::Mixture { components } => {
+                let mut io_string = "mixture".to_owned();
+                for (weight, component) in components {
+                    io_string.push_str(&format!("-{}-{}", weight, component.to_io_string()));
+                }
+                io_string
+            }
+            CodomainFunction::FromFile { codomain_file_path } => format!(
+                "from-file-{}",
+                codomain_file_path
+                    .file_stem()
+                    .map_or_else(String::new, |file_stem| file_stem.to_string_lossy().into_owned())
+            ),
+            CodomainFunction::Custom { name } => format!("custom-{}", name),
             CodomainFunction::Unknown => "unknown".to_owned(),
         }
     }
 }
+
+///Parse a component of a mixture codomain function, like "0.5:nk-q:4", into its weight and codomain function
+fn parse_mixture_component(component: &str) -> Result<(f64, CodomainFunction), ProblemGenError> {
+    let (weight, codomain_function) = component.split_once(':').ok_or_else(|| {
+        ProblemGenError::InvalidParameters(format!(
+            "mixture component {} is not of the form weight:function",
+            component
+        ))
+    })?;
+    let weight: f64 = weight.parse()?;
+    if weight.is_nan() || weight < 0.0 {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "mixture component {} does not have a non-negative weight",
+            component
+        )));
+    }
+    let codomain_function = CodomainFunction::from_line(&codomain_function.replace(':', " "))?;
+    if let CodomainFunction::FromFile { .. } = codomain_function {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "mixture component {} can't read its codomain from a file, as the components generate single cliques",
+            component
+        )));
+    }
+    Ok((weight, codomain_function))
+}
+
+///Information on a codomain function, to list the available codomain functions
+pub struct CodomainFunctionInfo {
+    ///Name of the codomain function, as used in configuration files and on the command line
+    pub name: &'static str,
+    ///Pattern of the string representation used in filenames, as returned by to_io_string
+    pub io_pattern: &'static str,
+    ///The parameters of the codomain function
+    pub parameters: &'static str,
+    ///One-line description of the codomain function
+    pub description: &'static str,
+}
+
+///All codomain functions that can be generated, together with their information
+pub const CODOMAIN_FUNCTIONS: &[CodomainFunctionInfo] = &[
+    CodomainFunctionInfo {
+        name: "random",
+        io_pattern: "random",
+        parameters: "",
+        description: "Uniformly random codomain values in [0, 1)",
+    },
+    CodomainFunctionInfo {
+        name: "trap",
+        io_pattern: "trap",
+        parameters: "",
+        description: "Trap function with the all-ones string as optimum",
+    },
+    CodomainFunctionInfo {
+        name: "deceptive-trap",
+        io_pattern: "deceptive-trap",
+        parameters: "",
+        description: "Trap function with a random deceptive attractor and its inverse as optimum",
+    },
+    CodomainFunctionInfo {
+        name: "nk-q",
+        io_pattern: "nk-q-{q}",
+        parameters: "q: number of distinct values (u32)",
+        description: "Random codomain values from q equally spaced values in [0, 1]",
+    },
+    CodomainFunctionInfo {
+        name: "nk-q-signed",
+        io_pattern: "nk-q-signed-{q}",
+        parameters: "q: highest absolute value (u32)",
+        description: "Random integer codomain values in [-q, q], which can be negative",
+    },
+    CodomainFunctionInfo {
+        name: "nk-p",
+        io_pattern: "nk-p-{p}",
+        parameters: "p: fraction of zero values (f64)",
+        description: "Random codomain values in [0, 1), with a fraction p of the values set to 0",
+    },
+    CodomainFunctionInfo {
+        name: "random-deceptive-trap",
+        io_pattern: "random-deceptive-trap-{p_deceptive}",
+        parameters: "p_deceptive: probability of a deceptive subfunction (f64)",
+        description: "Every subfunction is a deceptive trap with probability p_deceptive, and random otherwise",
+    },
+    CodomainFunctionInfo {
+        name: "deceptive-trap-scaled",
+        io_pattern: "deceptive-trap-scaled-{deceptor_value}-{optimum_value}",
+        parameters: "deceptor_value: value of the attractor (f64), optimum_value: value of the optimum (f64)",
+        description: "Deceptive trap with the given values for the deceptive attractor and the optimum",
+    },
+    CodomainFunctionInfo {
+        name: "deceptive-trap-dist",
+        io_pattern: "deceptive-trap-dist-{opt_distance}",
+        parameters: "opt_distance: distance of the optimum to the attractor (u32)",
+        description: "Deceptive trap with the optimum at distance opt_distance from the deceptive attractor",
+    },
+    CodomainFunctionInfo {
+        name: "plateau-trap",
+        io_pattern: "plateau-trap-{w}",
+        parameters: "w: width of the plateau (u32)",
+        description: "Trap function with a plateau within distance w of the deceptive attractor",
+    },
+    CodomainFunctionInfo {
+        name: "step-trap",
+        io_pattern: "step-trap-{step_size}",
+        parameters: "step_size: distance units per step (u32)",
+        description: "Trap function whose values increase in steps of step_size away from the deceptive attractor",
+    },
+    CodomainFunctionInfo {
+        name: "royal-road",
+        io_pattern: "royal-road-{block_reward}",
+        parameters: "block_reward: value of a block of all ones (f64)",
+        description: "Every subfunction awards block_reward if all its bits are 1, and 0 otherwise",
+    },
+    CodomainFunctionInfo {
+        name: "max-cut",
+        io_pattern: "max-cut-{edge_probability}",
+        parameters: "edge_probability: probability of an edge (f64)",
+        description: "Every subfunction counts the edges of a random graph cut by the substring's partition",
+    },
+    CodomainFunctionInfo {
+        name: "gaussian-random",
+        io_pattern: "gaussian-{mean}-{stddev}",
+        parameters: "mean: mean (f64), stddev: standard deviation (f64)",
+        description: "Random codomain values from the normal distribution with the given mean and standard deviation",
+    },
+    CodomainFunctionInfo {
+        name: "mixture",
+        io_pattern: "mixture-{weight}-{function}-...",
+        parameters: "components: weight:function pairs (f64:String)",
+        description: "Every subfunction picks one of the component functions, with probability proportional to its weight",
+    },
+    CodomainFunctionInfo {
+        name: "from-file",
+        io_pattern: "from-file-{file_stem}",
+        parameters: "codomain_file_path: file with only the values (path)",
+        description: "Codomain values read from a file, attached to a generated structure",
+    },
+    CodomainFunctionInfo {
+        name: "custom",
+        io_pattern: "custom-{name}",
+        parameters: "name: name of the registered generator (String)",
+        description: "Codomain generated by the codomain generator registered under the given name",
+    },
+];
+
+///Get the list of all codomain functions with their information, one codomain function per line
+pub fn list_codomain_functions() -> String {
+    let mut list = String::new();
+    for info in CODOMAIN_FUNCTIONS {
+        list.push_str(&format!(
+            "{:<24}{:<38}{:<60}{}\n",
+            info.name, info.io_pattern, info.parameters, info.description
+        ));
+    }
+    list
+}
+
 impl fmt::Display for CodomainFunction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
@@ -57,12 +345,42 @@ impl fmt::Display for CodomainFunction {
             CodomainFunction::Trap => write!(f, "trap"),
             CodomainFunction::DeceptiveTrap => write!(f, "deceptive-trap"),
             CodomainFunction::NKq { q } => write!(f, "nk-q {}", q),
+            CodomainFunction::NKqSigned { q } => write!(f, "nk-q-signed {}", q),
             CodomainFunction::NKp { p } => write!(f, "nk-p {}", p),
             CodomainFunction::RandomDeceptiveTrap {
                 p_deceptive: p_random,
             } => {
                 write!(f, "random-deceptive-trap {}", p_random)
             }
+            CodomainFunction::DeceptiveTrapScaled {
+                deceptor_value,
+                optimum_value,
+            } => write!(f, "deceptive-trap-scaled {} {}", deceptor_value, optimum_value),
+            CodomainFunction::DeceptiveTrapDist { opt_distance } => {
+                write!(f, "deceptive-trap-dist {}", opt_distance)
+            }
+            CodomainFunction::PlateauTrap { w } => write!(f, "plateau-trap {}", w),
+            CodomainFunction::StepTrap { step_size } => write!(f, "step-trap {}", step_size),
+            CodomainFunction::RoyalRoad { block_reward } => {
+                write!(f, "royal-road {}", block_reward)
+            }
+            CodomainFunction::MaxCut { edge_probability } => {
+                write!(f, "max-cut {}", edge_probability)
+            }
+            CodomainFunction::GaussianRandom { mean, stddev } => {
+                write!(f, "gaussian-random {} {}", mean, stddev)
+            }
+            CodomainFunction::Mixture { components } => {
+                write!(f, "mixture")?;
+                for (weight, component) in components {
+                    write!(f, " {}:{}", weight, component.to_string().replace(' ', ":"))?;
+                }
+                Ok(())
+            }
+            CodomainFunction::FromFile { codomain_file_path } => {
+                write!(f, "from-file {}", codomain_file_path.display())
+            }
+            CodomainFunction::Custom { name } => write!(f, "custom {}", name),
             CodomainFunction::Unknown => write!(f, "unknown"),
         }
     }
@@ -70,25 +388,50 @@ impl fmt::Display for CodomainFunction {
 
 ///Generate random codomain values
 pub fn generate_random(input_parameters: &InputParameters, rng: &mut ChaChaRng) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    (0..m).map(|_| generate_random_clique(k, rng)).collect()
+}
+
+///Generate random codomain values for a single clique/subfunction of size k
+pub fn generate_random_clique(k: u32, rng: &mut ChaChaRng) -> Vec<f64> {
     let die = Uniform::from(0.0..1.0);
+    (0..(1 << k)).map(|_| die.sample(rng)).collect()
+}
 
+///Generate gaussian random codomain values, sampled from the normal distribution with the given mean and standard deviation
+pub fn generate_gaussian_random(
+    input_parameters: &InputParameters,
+    mean: f64,
+    stddev: f64,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
     let m = input_parameters.m;
     let k = input_parameters.k;
 
     //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
     assert!(k < 32);
 
-    let mut codomain_tree = Vec::with_capacity(m as usize);
-
-    for _ in 0..m {
-        let mut codomain_clique = Vec::with_capacity((1 << k) as usize);
-        for _ in 0..(1 << k) {
-            codomain_clique.push(die.sample(rng));
-        }
-        codomain_tree.push(codomain_clique);
-    }
+    (0..m)
+        .map(|_| generate_gaussian_random_clique(k, mean, stddev, rng))
+        .collect()
+}
 
-    codomain_tree
+///Generate gaussian random codomain values for a single clique/subfunction of size k.
+/// The standard deviation should be finite and non-negative, see CodomainFunction::validate.
+pub fn generate_gaussian_random_clique(
+    k: u32,
+    mean: f64,
+    stddev: f64,
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let normal = Normal::new(mean, stddev)
+        .expect("the standard deviation should be finite and non-negative");
+    (0..(1 << k)).map(|_| normal.sample(rng)).collect()
 }
 
 ///Generate general deceptive trap values:
@@ -106,79 +449,432 @@ pub fn generate_trap_general(input_parameters: &InputParameters, rng: &mut ChaCh
 
     let possible_clique_substrings = get_possible_substrings(k);
 
-    let mut codomain = Vec::with_capacity(m as usize);
-    for _i in 0..m {
-        let local_deceptor = get_random_solution(k, rng);
+    (0..m)
+        .map(|_| generate_trap_general_clique(k, &possible_clique_substrings, rng))
+        .collect()
+}
+
+///Generate general deceptive trap values for a single clique/subfunction of size k,
+/// given all possible substrings of the clique
+pub fn generate_trap_general_clique(
+    k: u32,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let local_deceptor = get_random_solution(k, rng);
+    generate_trap_clique_from_deceptor(
+        &local_deceptor,
+        possible_clique_substrings,
+        DECEPTIVE_TRAP_DECEPTOR_VALUE,
+        DECEPTIVE_TRAP_OPTIMUM_VALUE,
+    )
+}
+
+///Value of the local deceptive attractor of the general deceptive trap
+pub const DECEPTIVE_TRAP_DECEPTOR_VALUE: f64 = 0.9;
+///Value of the local optimum of the general deceptive trap
+pub const DECEPTIVE_TRAP_OPTIMUM_VALUE: f64 = 1.0;
+
+///Generate scaled deceptive trap values:
+/// Every subfunction is a general deceptive trap (see generate_trap_general),
+///  but the local deceptive attractor has value deceptor_value instead of 0.9 and the local optimum has value optimum_value instead of 1.0.
+/// The codomain values for the other bit strings are linearly scaled down from deceptor_value by their hamming distance d to the local deceptive attractor:
+///  deceptor_value - d * deceptor_value/k
+pub fn generate_trap_scaled(
+    input_parameters: &InputParameters,
+    deceptor_value: f64,
+    optimum_value: f64,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    let possible_clique_substrings = get_possible_substrings(k);
+
+    (0..m)
+        .map(|_| {
+            generate_trap_scaled_clique(
+                k,
+                deceptor_value,
+                optimum_value,
+                &possible_clique_substrings,
+                rng,
+            )
+        })
+        .collect()
+}
+
+///Generate scaled deceptive trap values for a single clique/subfunction of size k, given all possible substrings of the clique
+pub fn generate_trap_scaled_clique(
+    k: u32,
+    deceptor_value: f64,
+    optimum_value: f64,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let local_deceptor = get_random_solution(k, rng);
+    generate_trap_clique_from_deceptor(
+        &local_deceptor,
+        possible_clique_substrings,
+        deceptor_value,
+        optimum_value,
+    )
+}
+
+///Generate general deceptive trap values for a single clique/subfunction, given its local deceptor, all possible substrings of the clique,
+/// and the values of the local deceptor and the local optimum
+fn generate_trap_clique_from_deceptor(
+    local_deceptor: &[u32],
+    possible_clique_substrings: &[Vec<u32>],
+    deceptor_value: f64,
+    optimum_value: f64,
+) -> Vec<f64> {
+    let k = local_deceptor.len() as u32;
+    let mut codomain_clique = Vec::with_capacity(1 << k);
+    for clique_substring in possible_clique_substrings {
+        // d
+        let distance_to_deceptor =
+            get_hamming_distance_to_solution(local_deceptor, clique_substring);
+        let value = if distance_to_deceptor == k {
+            //if local optimum
+            optimum_value
+        } else {
+            //otherwise it's the local deceptive attractor or any other bit string
+            deceptor_value - distance_to_deceptor as f64 * (deceptor_value / k as f64)
+        };
+        codomain_clique.push(value);
+    }
+    codomain_clique
+}
+
+///Generate deceptive trap values with the local optimum at a configurable distance from the deceptive attractor:
+/// For each subfunction, the local deceptive attractor is a random bit string of length k,
+///  and the local optimum is the bit string that differs from it in opt_distance random positions.
+/// The codomain values for each bit string other than the local optimum are defined by their hamming distance d to the local deceptive attractor:
+///  max(0.9 - d * 0.9/opt_distance, 0), so that the values decrease from the attractor to 0 at the distance of the local optimum.
+/// The codomain value for the local optimum is 1.0. With opt_distance = k, this is the general deceptive trap (see generate_trap_general).
+pub fn generate_trap_dist(
+    input_parameters: &InputParameters,
+    opt_distance: u32,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    let possible_clique_substrings = get_possible_substrings(k);
+
+    (0..m)
+        .map(|_| generate_trap_dist_clique(k, opt_distance, &possible_clique_substrings, rng))
+        .collect()
+}
+
+///Generate deceptive trap values with the local optimum at distance opt_distance from the deceptive attractor
+/// for a single clique/subfunction of size k, given all possible substrings of the clique
+pub fn generate_trap_dist_clique(
+    k: u32,
+    opt_distance: u32,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    //Ensure the local optimum differs from the deceptive attractor and fits in the clique
+    assert!(
+        opt_distance > 0 && opt_distance <= k,
+        "opt_distance should be in 1..=k, found opt_distance = {} for k = {}",
+        opt_distance,
+        k
+    );
 
-        let mut codomain_clique = Vec::with_capacity(1 << k);
-        for j in 0..(1 << k) {
-            // d
+    let local_deceptor = get_random_solution(k, rng);
+    //The local optimum flips the bits of the deceptive attractor at opt_distance random positions
+    let mut positions: Vec<usize> = (0..k as usize).collect();
+    shuffle(&mut positions, rng);
+    let mut local_optimum = local_deceptor.clone();
+    for &position in &positions[..opt_distance as usize] {
+        local_optimum[position] ^= 1;
+    }
+
+    let mut codomain_clique = Vec::with_capacity(1 << k);
+    for clique_substring in possible_clique_substrings {
+        let value = if *clique_substring == local_optimum {
+            DECEPTIVE_TRAP_OPTIMUM_VALUE
+        } else {
             let distance_to_deceptor =
-                get_hamming_distance_to_solution(&local_deceptor, &possible_clique_substrings[j]);
-            let value = if distance_to_deceptor == k {
-                //if local optimum
-                1.0
-            } else {
-                //otherwise it's the local deceptive attractor or any other bit string
-                0.9 - distance_to_deceptor as f64 * (0.9 / k as f64)
-            };
-            codomain_clique.push(value);
-        }
-        codomain.push(codomain_clique);
+                get_hamming_distance_to_solution(&local_deceptor, clique_substring);
+            (DECEPTIVE_TRAP_DECEPTOR_VALUE
+                - distance_to_deceptor as f64
+                    * (DECEPTIVE_TRAP_DECEPTOR_VALUE / opt_distance as f64))
+                .max(0.0)
+        };
+        codomain_clique.push(value);
     }
+    codomain_clique
+}
+
+///Generate correlated deceptive trap values for the given clique tree structure (cliques and separators, as returned by `CliqueTree::construct`):
+/// Every clique is a general deceptive trap (see generate_trap_general), but the local deceptor of a child clique
+///  agrees with the local deceptor of its parent on the separator variables, so that the deception spans multiple cliques.
+/// The bits of the local deceptors that are not in a separator are random.
+/// As the codomain depends on the structure, it can not be generated before the structure is constructed,
+///  and the global optima should be calculated with `CliqueTree::calculate_global_optima` using the same structure.
+pub fn generate_correlated_trap(
+    cliques: &[Vec<u32>],
+    separators: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let k = cliques.first().map_or(0, |clique| clique.len() as u32);
 
-    codomain
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    let possible_clique_substrings = get_possible_substrings(k);
+    let die = Uniform::from(0..2);
+
+    //The deceptor bit of every variable, set by the first clique that contains it.
+    // A parent always comes before its children, so the separator variables of a clique are already set by its parent.
+    let number_of_variables = cliques
+        .iter()
+        .flatten()
+        .max()
+        .map_or(0, |&max_variable_index| max_variable_index as usize + 1);
+    let mut deceptor_bits: Vec<Option<u32>> = vec![None; number_of_variables];
+
+    cliques
+        .iter()
+        .zip(separators)
+        .map(|(clique, separator)| {
+            let local_deceptor: Vec<u32> = clique
+                .iter()
+                .map(|&variable_index| match deceptor_bits[variable_index as usize] {
+                    Some(bit) if separator.contains(&variable_index) => bit,
+                    _ => {
+                        let bit = die.sample(rng);
+                        deceptor_bits[variable_index as usize] = Some(bit);
+                        bit
+                    }
+                })
+                .collect();
+            generate_trap_clique_from_deceptor(
+                &local_deceptor,
+                &possible_clique_substrings,
+                DECEPTIVE_TRAP_DECEPTOR_VALUE,
+                DECEPTIVE_TRAP_OPTIMUM_VALUE,
+            )
+        })
+        .collect()
 }
 
 ///Generate the codomain for the combination of random and deceptive trap codomain functions:
 /// With probability p_deceptive, each clique/subfunction is a deceptive trap function,
 ///  and with probability (1 - p_deceptive) each clique/subfunction is a random function.
 pub fn generate_random_trap(input_parameters: &InputParameters, p_deceptive: f64, rng: &mut ChaChaRng) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    let possible_clique_substrings = get_possible_substrings(k);
+
+    (0..m)
+        .map(|_| generate_random_trap_clique(k, p_deceptive, &possible_clique_substrings, rng))
+        .collect()
+}
+
+///Generate the codomain values of a single clique/subfunction of size k for the combination of random and deceptive trap,
+/// given all possible substrings of the clique
+pub fn generate_random_trap_clique(
+    k: u32,
+    p_deceptive: f64,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
     let die = Uniform::from(0.0..1.0);
 
+    if die.sample(rng) > p_deceptive {
+        //Random
+        generate_random_clique(k, rng)
+    } else {
+        //Deceptive trap
+        generate_trap_general_clique(k, possible_clique_substrings, rng)
+    }
+}
+
+///Generate plateau trap values:
+/// For each subfunction, the local deceptive attractor is a random bit string of length k
+///  and the local optimum is the inverse of that random bit string.
+/// The codomain values for each bit string other than the local optimum are defined by their hamming distance d to the local deceptive attractor:
+///  all bit strings with d < w form a plateau with value 0.9 * (w - 1)/k, and from there the values increase as 0.9 * d/k.
+/// The codomain value for the local optimum is 1.0
+pub fn generate_plateau_trap(
+    input_parameters: &InputParameters,
+    w: u32,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
     let m = input_parameters.m;
     let k = input_parameters.k;
 
     //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
     assert!(k < 32);
+    //Ensure the plateau does not contain the local optimum, which check_codomain_function reports as an error beforehand
+    assert!(w <= k);
 
     let possible_clique_substrings = get_possible_substrings(k);
-    let mut codomain_tree = Vec::with_capacity(m as usize);
 
-    for _ in 0..m {
-        let mut codomain_clique = Vec::with_capacity(1 << k);
+    (0..m)
+        .map(|_| generate_plateau_trap_clique(k, w, &possible_clique_substrings, rng))
+        .collect()
+}
 
-        if die.sample(rng) > p_deceptive {
-            //Random
-            for _ in 0..(1 << k) {
-                codomain_clique.push(die.sample(rng));
-            }
+///Generate plateau trap values for a single clique/subfunction of size k, given all possible substrings of the clique
+pub fn generate_plateau_trap_clique(
+    k: u32,
+    w: u32,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let local_deceptor = get_random_solution(k, rng);
+
+    let mut codomain_clique = Vec::with_capacity(1 << k);
+    for clique_substring in possible_clique_substrings {
+        let distance_to_deceptor =
+            get_hamming_distance_to_solution(&local_deceptor, clique_substring);
+        let value = if distance_to_deceptor == k {
+            //if local optimum
+            1.0
         } else {
-            //Deceptive trap
-            let local_deceptor = get_random_solution(k, rng);
-
-            for j in 0..(1 << k) {
-                let distance_to_deceptor = get_hamming_distance_to_solution(
-                    &local_deceptor,
-                    &possible_clique_substrings[j],
-                );
-                let value = if distance_to_deceptor == k {
-                    1.0
-                } else {
-                    0.9 - distance_to_deceptor as f64 * (0.9 / k as f64)
-                };
-                codomain_clique.push(value);
+            //otherwise it's on the plateau or on the slope towards the local optimum
+            distance_to_deceptor.max(w.saturating_sub(1)) as f64 * (0.9 / k as f64)
+        };
+        codomain_clique.push(value);
+    }
+    codomain_clique
+}
+
+///Generate step trap values:
+/// For each subfunction, the local deceptive attractor is a random bit string of length k
+///  and the local optimum is the inverse of that random bit string.
+/// The codomain values for each bit string other than the local optimum are defined by their hamming distance d to the local deceptive attractor,
+///  floored to a multiple of step_size: 0.9 * (d - d % step_size)/k, so that the values increase in steps of step_size distance units.
+/// The codomain value for the local optimum is 1.0
+pub fn generate_step_trap(
+    input_parameters: &InputParameters,
+    step_size: u32,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+    //Ensure every step spans at least one distance unit
+    assert!(step_size >= 1);
+
+    let possible_clique_substrings = get_possible_substrings(k);
+
+    (0..m)
+        .map(|_| generate_step_trap_clique(k, step_size, &possible_clique_substrings, rng))
+        .collect()
+}
+
+///Generate step trap values for a single clique/subfunction of size k, given all possible substrings of the clique
+pub fn generate_step_trap_clique(
+    k: u32,
+    step_size: u32,
+    possible_clique_substrings: &[Vec<u32>],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let local_deceptor = get_random_solution(k, rng);
+
+    let mut codomain_clique = Vec::with_capacity(1 << k);
+    for clique_substring in possible_clique_substrings {
+        let distance_to_deceptor =
+            get_hamming_distance_to_solution(&local_deceptor, clique_substring);
+        let value = if distance_to_deceptor == k {
+            //if local optimum
+            1.0
+        } else {
+            //otherwise it's on the step of its distance, floored to a multiple of the step size
+            let step_distance = distance_to_deceptor - distance_to_deceptor % step_size;
+            step_distance as f64 * (0.9 / k as f64)
+        };
+        codomain_clique.push(value);
+    }
+    codomain_clique
+}
+
+///Generate royal road values: for each subfunction, the all-ones bit string has value block_reward, and all others have value 0
+pub fn generate_royal_road(input_parameters: &InputParameters, block_reward: f64) -> Vec<Vec<f64>> {
+    let codomain_clique = generate_royal_road_clique(input_parameters.k, block_reward);
+
+    (0..input_parameters.m)
+        .map(|_| codomain_clique.clone())
+        .collect()
+}
+
+///Generate royal road values for a single clique/subfunction of size k
+pub fn generate_royal_road_clique(k: u32, block_reward: f64) -> Vec<f64> {
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds
+    assert!(k < 32);
+
+    let mut codomain_clique = vec![0.0; 1 << k];
+    //The all-ones substring is the last substring
+    codomain_clique[(1 << k) - 1] = block_reward;
+    codomain_clique
+}
+
+///Generate max-cut values: every subfunction is a graph over its k variables, where every pair of variables is connected with probability edge_probability.
+/// The value of a substring is the number of edges cut by the partition of the variables into the variables with value 0 and the variables with value 1,
+///  so a substring and its complement (the same cut) have the same value.
+pub fn generate_max_cut(
+    input_parameters: &InputParameters,
+    edge_probability: f64,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds on 32-bit machines
+    assert!(k < 32);
+
+    (0..m)
+        .map(|_| generate_max_cut_clique(k, edge_probability, rng))
+        .collect()
+}
+
+///Generate max-cut values for a single clique/subfunction of size k, sampling its edges with probability edge_probability
+pub fn generate_max_cut_clique(k: u32, edge_probability: f64, rng: &mut ChaChaRng) -> Vec<f64> {
+    //Sample the edges, as pairs of positions in the clique
+    let die = Uniform::from(0.0..1.0);
+    let mut edges = Vec::new();
+    for i in 0..k {
+        for j in (i + 1)..k {
+            if die.sample(rng) < edge_probability {
+                edges.push((i, j));
             }
         }
-
-        codomain_tree.push(codomain_clique);
     }
 
-    codomain_tree
+    //An edge is cut if the bits at its positions differ, where position j is bit (k - j - 1) of the substring index
+    (0..(1u32 << k))
+        .map(|substring_index| {
+            edges
+                .iter()
+                .filter(|&&(i, j)| {
+                    (substring_index >> (k - i - 1)) & 1 != (substring_index >> (k - j - 1)) & 1
+                })
+                .count() as f64
+        })
+        .collect()
 }
 
 ///Get the hamming distance to a solution, by counting the number of unequal bits in the bit strings
-fn get_hamming_distance_to_solution(target_solution: &[u32], solution: &[u32]) -> u32 {
+pub(crate) fn get_hamming_distance_to_solution(target_solution: &[u32], solution: &[u32]) -> u32 {
     assert_eq!(target_solution.len(), solution.len());
 
     let mut distance = 0;
@@ -198,6 +894,13 @@ pub fn generate_trap(input_parameters: &InputParameters, d: f64) -> Vec<Vec<f64>
     //Ensure k is smaller than 32, as otherwise the bit shift goes out of bounds
     assert!(k < 32);
 
+    let codomain_clique = generate_trap_clique(k, d);
+
+    (0..m).map(|_| codomain_clique.clone()).collect()
+}
+
+///Construct the trap codomain values for a single clique/subfunction of size k
+pub fn generate_trap_clique(k: u32, d: f64) -> Vec<f64> {
     let multiplication_factor = ((k as f64) - d) / ((k - 1) as f64);
 
     let mut codomain_clique = Vec::with_capacity(1 << k);
@@ -206,11 +909,10 @@ pub fn generate_trap(input_parameters: &InputParameters, d: f64) -> Vec<Vec<f64>
             codomain_clique.push(k as f64);
         } else {
             codomain_clique
-                .push(((k as f64) - d - multiplication_factor * (count_ones(k, i) as f64)) as f64);
+                .push((k as f64) - d - multiplication_factor * (count_ones(k, i) as f64));
         }
     }
-
-    (0..m).map(|_| codomain_clique.clone()).collect()
+    codomain_clique
 }
 
 ///Generate NKq codomain values
@@ -219,16 +921,34 @@ pub fn generate_nk_q(input_parameters: &InputParameters, q: u32, rng: &mut ChaCh
     let m = input_parameters.m;
     let k = input_parameters.k;
 
+    (0..m).map(|_| generate_nk_q_clique(k, q, rng)).collect()
+}
+
+///Generate NKq codomain values for a single clique/subfunction of size k
+pub fn generate_nk_q_clique(k: u32, q: u32, rng: &mut ChaChaRng) -> Vec<f64> {
     let die = Uniform::from(0..q);
+    (0..(1 << k))
+        .map(|_| die.sample(rng) as f64 / (q - 1) as f64)
+        .collect()
+}
 
-    let mut codomain = Vec::with_capacity(m as usize);
-    for _ in 0..m {
-        let codomain_clique: Vec<f64> = (0..(1 << k))
-            .map(|_| die.sample(rng) as f64 / (q - 1) as f64)
-            .collect();
-        codomain.push(codomain_clique);
-    }
-    codomain
+///Generate signed NKq codomain values
+///Every codomain value is a random integer between -q and q (both inclusive), which is not normalized, so it can be negative
+pub fn generate_nk_q_signed(
+    input_parameters: &InputParameters,
+    q: u32,
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    let m = input_parameters.m;
+    let k = input_parameters.k;
+
+    (0..m).map(|_| generate_nk_q_signed_clique(k, q, rng)).collect()
+}
+
+///Generate signed NKq codomain values for a single clique/subfunction of size k
+pub fn generate_nk_q_signed_clique(k: u32, q: u32, rng: &mut ChaChaRng) -> Vec<f64> {
+    let die = Uniform::new_inclusive(-(q as i64), q as i64);
+    (0..(1 << k)).map(|_| die.sample(rng) as f64).collect()
 }
 
 ///Generate NKp codomain values
@@ -237,33 +957,160 @@ pub fn generate_nk_p(input_parameters: &InputParameters, p: f64, rng: &mut ChaCh
     let m = input_parameters.m;
     let k = input_parameters.k;
 
+    //The indices are shuffled in place for every clique, so every clique starts from the permutation of the previous clique
+    let mut codomain_clique_indices: Vec<u32> = (0..(1 << k)).collect();
+
+    (0..m)
+        .map(|_| generate_nk_p_clique(k, p, &mut codomain_clique_indices, rng))
+        .collect()
+}
+
+///Generate NKp codomain values for a single clique/subfunction of size k,
+/// using (and shuffling) the passed permutation of the clique's substring indices to pick the zero values
+pub fn generate_nk_p_clique(
+    k: u32,
+    p: f64,
+    codomain_clique_indices: &mut [u32],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
     let num_zeroes = (p * (1 << k) as f64).round() as u32;
 
     let die = Uniform::from(0.0..1.0);
 
-    let mut codomain_clique_indices: Vec<u32> = (0..(1 << k)).collect();
-    let mut codomain = Vec::with_capacity(m as usize);
-
-    for _ in 0..m {
-        let mut codomain_clique = Vec::with_capacity(k as usize);
-        codomain_clique_indices.shuffle(rng);
-
-        let no_contribution_indices: Vec<&u32> = codomain_clique_indices
-            .iter()
-            .take(num_zeroes as usize)
-            .collect();
-
-        for i in 0..(1 << k) {
-            if no_contribution_indices.contains(&&i) {
-                codomain_clique.push(0.0);
-            } else {
-                codomain_clique.push(die.sample(rng));
+    let mut codomain_clique = Vec::with_capacity(k as usize);
+    shuffle(codomain_clique_indices, rng);
+
+    let no_contribution_indices: Vec<&u32> = codomain_clique_indices
+        .iter()
+        .take(num_zeroes as usize)
+        .collect();
+
+    for i in 0..(1 << k) {
+        if no_contribution_indices.contains(&&i) {
+            codomain_clique.push(0.0);
+        } else {
+            codomain_clique.push(die.sample(rng));
+        }
+    }
+    codomain_clique
+}
+
+///Generate the codomain for a mixture of codomain functions:
+/// every clique/subfunction independently picks one of the components with probability proportional to its weight,
+///  and is generated with the codomain function of that component.
+pub fn generate_mixture(
+    input_parameters: &InputParameters,
+    components: &[(f64, CodomainFunction)],
+    rng: &mut ChaChaRng,
+) -> Vec<Vec<f64>> {
+    (0..input_parameters.m)
+        .map(|_| generate_mixture_clique(input_parameters, components, rng))
+        .collect()
+}
+
+///Generate the codomain values of a single clique/subfunction for a mixture of codomain functions
+fn generate_mixture_clique(
+    input_parameters: &InputParameters,
+    components: &[(f64, CodomainFunction)],
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let total_weight: f64 = components.iter().map(|(weight, _)| weight).sum();
+    assert!(
+        total_weight > 0.0,
+        "the weights of a mixture should sum to a positive value"
+    );
+
+    //Pick the first component whose cumulative weight exceeds the sampled weight
+    let die = Uniform::from(0.0..total_weight);
+    let mut sampled_weight = die.sample(rng);
+    let (_, codomain_function) = components
+        .iter()
+        .find(|(weight, _)| {
+            sampled_weight -= weight;
+            sampled_weight < 0.0
+        })
+        .unwrap_or_else(|| components.last().unwrap());
+
+    generate_single_clique(input_parameters, codomain_function, rng)
+}
+
+///Generate the codomain values of a single clique/subfunction with the given codomain function,
+/// for cliques with the size given by the input parameters
+pub fn generate_single_clique(
+    input_parameters: &InputParameters,
+    codomain_function: &CodomainFunction,
+    rng: &mut ChaChaRng,
+) -> Vec<f64> {
+    let k = input_parameters.k;
+    match codomain_function {
+        CodomainFunction::Random => generate_random_clique(k, rng),
+        CodomainFunction::Trap => generate_trap_clique(k, 2.5),
+        CodomainFunction::DeceptiveTrap => {
+            generate_trap_general_clique(k, &get_possible_substrings(k), rng)
+        }
+        CodomainFunction::NKq { q } => generate_nk_q_clique(k, *q, rng),
+        CodomainFunction::NKqSigned { q } => generate_nk_q_signed_clique(k, *q, rng),
+        CodomainFunction::NKp { p } => {
+            let mut codomain_clique_indices: Vec<u32> = (0..(1 << k)).collect();
+            generate_nk_p_clique(k, *p, &mut codomain_clique_indices, rng)
+        }
+        CodomainFunction::RandomDeceptiveTrap { p_deceptive } => {
+            generate_random_trap_clique(k, *p_deceptive, &get_possible_substrings(k), rng)
+        }
+        CodomainFunction::DeceptiveTrapScaled {
+            deceptor_value,
+            optimum_value,
+        } => generate_trap_scaled_clique(
+            k,
+            *deceptor_value,
+            *optimum_value,
+            &get_possible_substrings(k),
+            rng,
+        ),
+        CodomainFunction::DeceptiveTrapDist { opt_distance } => {
+            generate_trap_dist_clique(k, *opt_distance, &get_possible_substrings(k), rng)
+        }
+        CodomainFunction::PlateauTrap { w } => {
+            generate_plateau_trap_clique(k, *w, &get_possible_substrings(k), rng)
+        }
+        CodomainFunction::StepTrap { step_size } => {
+            generate_step_trap_clique(k, *step_size, &get_possible_substrings(k), rng)
+        }
+        CodomainFunction::RoyalRoad { block_reward } => {
+            generate_royal_road_clique(k, *block_reward)
+        }
+        CodomainFunction::MaxCut { edge_probability } => {
+            generate_max_cut_clique(k, *edge_probability, rng)
+        }
+        CodomainFunction::GaussianRandom { mean, stddev } => {
+            generate_gaussian_random_clique(k, *mean, *stddev, rng)
+        }
+        CodomainFunction::Mixture { components } => {
+            generate_mixture_clique(input_parameters, components, rng)
+        }
+        CodomainFunction::FromFile { .. } => {
+            panic!("The codomain of a single clique can't be read from a codomain file")
+        }
+        CodomainFunction::Custom { name } => {
+            let clique_parameters =
+                InputParameters::new_from_primitives(1, k, input_parameters.o, input_parameters.b);
+            match get_codomain_generator(name) {
+                Some(generator) => generator.generate(&clique_parameters, rng).pop().unwrap(),
+                None => panic!("No codomain generator registered with name {}", name),
             }
         }
-        codomain.push(codomain_clique);
+        CodomainFunction::Unknown => panic!("We can't generate codomain for unknown codomain"),
     }
+}
 
-    codomain
+///Check whether the codomain function generates the same codomain values for every clique/subfunction.
+/// This holds for the deterministic functions trap and royal road, whose single clique codomain is shared by all cliques,
+///  so it only needs to be generated once.
+pub fn codomain_is_clique_invariant(codomain_function: &CodomainFunction) -> bool {
+    matches!(
+        codomain_function,
+        CodomainFunction::Trap | CodomainFunction::RoyalRoad { .. }
+    )
 }
 
 ///Count the number of ones in the bit string represented by and as the index
@@ -278,7 +1125,7 @@ fn count_ones(k: u32, index: u32) -> u32 {
 }
 
 ///Get a random solution, given the problem size
-fn get_random_solution(problem_size: u32, rng: &mut ChaChaRng) -> Vec<u32> {
+pub(crate) fn get_random_solution(problem_size: u32, rng: &mut ChaChaRng) -> Vec<u32> {
     let die = Uniform::from(0..2);
     (0..problem_size).map(|_| die.sample(rng)).collect()
 }
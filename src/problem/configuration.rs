@@ -2,16 +2,20 @@
 Module for reading the configuration ranges (and iterating over it)
 */
 
-use structopt::StructOpt;
 use rand_chacha::ChaChaRng;
-use rand::SeedableRng;
+use rand::{RngCore, SeedableRng};
+use serde::Deserialize;
 
-use std::{error::Error, path::Path};
+use std::{path::Path, str::Lines};
 
-use super::{clique_tree::InputParameters, codomain_subclasses::CodomainFunction};
+use super::{
+    clique_tree::{InputParameters, Topology},
+    codomain_subclasses::CodomainFunction,
+    error::ProblemGenError,
+};
 
 ///Struct to contain the configuration parameters, to conveniently iterate over
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConfigurationParameters {
     pub m_begin: u32,
     pub m_end: u32,
@@ -22,9 +26,93 @@ pub struct ConfigurationParameters {
     pub b_begin: u32,
     pub b_end: u32,
     pub codomain_function: CodomainFunction,
+    ///Maximum number of input parameter configurations to iterate over, or None to iterate over all of them
+    pub max_configurations: Option<u32>,
+    ///Steps between the consecutive values of M, k, o, and b, which are 1 unless set in a TOML configuration file
+    pub steps: ParameterSteps,
+    ///Range of o as a fraction of k, which replaces o_begin and o_end if set
+    pub o_fraction: Option<OverlapFraction>,
+}
+
+///Range of the overlap o as a fraction of k, from begin (inclusive) to end (exclusive)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapFraction {
+    pub begin: f64,
+    pub end: f64,
+}
+
+impl OverlapFraction {
+    ///Margin for floating point errors when multiplying the fractions with k, e.g. 0.3 * 10 = 3.0000000000000004
+    const MARGIN: f64 = 1e-9;
+
+    ///Get the range of absolute values of o for the given k, from begin (inclusive) to end (exclusive):
+    /// the values of o for which o / k lies in the fractional range, where o is always smaller than k.
+    pub fn get_o_range(&self, k: u32) -> (u32, u32) {
+        let o_begin = (self.begin * k as f64 - OverlapFraction::MARGIN)
+            .ceil()
+            .max(0.0) as u32;
+        let o_end = (self.end * k as f64 - OverlapFraction::MARGIN)
+            .ceil()
+            .max(0.0) as u32;
+        (o_begin, o_end.min(k))
+    }
+}
+
+///The steps between the consecutive values of the input parameters in a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterSteps {
+    pub m: u32,
+    pub k: u32,
+    pub o: u32,
+    pub b: u32,
+}
+
+impl Default for ParameterSteps {
+    fn default() -> Self {
+        ParameterSteps {
+            m: 1,
+            k: 1,
+            o: 1,
+            b: 1,
+        }
+    }
+}
+
+///Configuration file in the TOML format
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlConfiguration {
+    ranges: TomlRanges,
+    codomain_functions: Vec<String>,
+    max_configurations: Option<u32>,
+}
+
+///The ranges table of a TOML configuration file, with the range for every input parameter
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlRanges {
+    m: TomlRange,
+    k: TomlRange,
+    o: TomlRange,
+    b: TomlRange,
+}
+
+///The range of an input parameter in a TOML configuration file, from begin (inclusive) to end (exclusive)
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlRange {
+    begin: u32,
+    end: u32,
+    #[serde(default = "default_step")]
+    step: u32,
+}
+
+fn default_step() -> u32 {
+    1
 }
 
 impl ConfigurationParameters {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         m_begin: u32,
         m_end: u32,
@@ -46,58 +134,127 @@ impl ConfigurationParameters {
             b_begin,
             b_end,
             codomain_function,
+            max_configurations: None,
+            steps: ParameterSteps::default(),
+            o_fraction: None,
+        }
+    }
+
+    ///Check that the configuration can be generated with the topology. The path and star topologies replace b,
+    /// so a range of more than one value of b would generate the same input parameters (and file names) multiple times.
+    pub fn check_topology(&self, topology: Topology) -> Result<(), ProblemGenError> {
+        let has_b_range = self.b_end > self.b_begin.saturating_add(self.steps.b);
+        if topology != Topology::Balanced && has_b_range {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "the {:?} topology replaces b, so the configuration should have a single value of b, found the range {} to {}",
+                topology, self.b_begin, self.b_end
+            )));
         }
+        Ok(())
     }
 
-    ///Read configuration parameters from a file
-    pub fn from_file(input_file_path: &Path) -> Result<ConfigurationParameters, Box<dyn Error>> {
-        let contents = std::fs::read_to_string(&input_file_path)?;
+    ///Read configuration parameters from a file, in the TOML format if the file has the .toml extension,
+    /// and in the line-based configuration format otherwise
+    pub fn from_file(input_file_path: &Path) -> Result<ConfigurationParameters, ProblemGenError> {
+        if input_file_path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            return ConfigurationParameters::from_toml(input_file_path);
+        }
+
+        let contents = std::fs::read_to_string(input_file_path)?;
         let mut content_iterator = contents.lines();
 
-        let mut split_line = content_iterator.next().unwrap().split(' ');
-        let m_or_n = split_line.next().unwrap();
+        let mut split_line = get_next_line(&mut content_iterator, "M/N")?.split(' ');
+        let m_or_n = get_next_token(&mut split_line, "M/N")?;
         // .skip(1);
-        let m_or_n_begin: u32 = split_line.next().unwrap().parse()?;
-        let m_or_n_end: u32 = split_line.next().unwrap().parse()?;
+        let m_or_n_begin: u32 = get_next_token(&mut split_line, "M/N")?.parse()?;
+        let m_or_n_end: u32 = get_next_token(&mut split_line, "M/N")?.parse()?;
 
-        let mut split_line = content_iterator.next().unwrap().split(' ').skip(1);
-        let k_begin: u32 = split_line.next().unwrap().parse()?;
-        let k_end: u32 = split_line.next().unwrap().parse()?;
+        let mut split_line = get_next_line(&mut content_iterator, "k")?
+            .split(' ')
+            .skip(1);
+        let k_begin: u32 = get_next_token(&mut split_line, "k")?.parse()?;
+        let k_end: u32 = get_next_token(&mut split_line, "k")?.parse()?;
 
-        let mut split_line = content_iterator.next().unwrap().split(' ').skip(1);
-        let o_begin: u32 = split_line.next().unwrap().parse()?;
-        let o_end: i32 = split_line.next().unwrap().trim().parse()?;
-        let o_end: u32 = o_end as u32;
+        let mut split_line = get_next_line(&mut content_iterator, "o")?
+            .split(' ')
+            .skip(1);
+        let o_begin_token = get_next_token(&mut split_line, "o")?;
+        let o_end_token = get_next_token(&mut split_line, "o")?.trim();
+        //The o range is a fraction of k if it contains a decimal point
+        let o_is_fraction = o_begin_token.contains('.') || o_end_token.contains('.');
+        let (o_begin, o_end, o_fraction) = if o_is_fraction {
+            (
+                0,
+                0,
+                Some(parse_overlap_fraction(o_begin_token, o_end_token)?),
+            )
+        } else {
+            let o_begin: u32 = o_begin_token.parse()?;
+            let o_end: i32 = o_end_token.parse()?;
+            (o_begin, o_end as u32, None)
+        };
 
-        let mut split_line = content_iterator.next().unwrap().split(' ').skip(1);
-        let b_begin: u32 = split_line.next().unwrap().parse()?;
-        let b_end: u32 = split_line.next().unwrap().parse()?;
+        let mut split_line = get_next_line(&mut content_iterator, "b")?
+            .split(' ')
+            .skip(1);
+        let b_begin: u32 = get_next_token(&mut split_line, "b")?.parse()?;
+        let b_end: u32 = get_next_token(&mut split_line, "b")?.parse()?;
 
         let (m_begin, m_end) = if m_or_n == "M" {
             (m_or_n_begin, m_or_n_end)
         } else if m_or_n == "N" {
-            if k_end - k_begin > 1 || o_end - o_begin > 1 {
-                return Err("Can not use problem size in configuration when k and o are not one fixed value".into());
+            if k_end - k_begin > 1 || o_fraction.is_some() || o_end - o_begin > 1 {
+                return Err(ProblemGenError::InvalidParameters(
+                    "Can not use problem size in configuration when k and o are not one fixed value"
+                        .to_owned(),
+                ));
             }
             (
                 get_m_for_min_problem_size(m_or_n_begin, k_begin, o_begin),
                 get_m_for_max_problem_size(m_or_n_end, k_begin, o_begin),
             )
         } else {
-            return Err("First letter in configuration not recognized; not M or N".into());
+            return Err(ProblemGenError::InvalidParameters(
+                "First letter in configuration not recognized; not M or N".to_owned(),
+            ));
         };
 
         let codomain_functions_split_line: Vec<&str> =
-            content_iterator.next().unwrap().split(',').collect();
+            get_next_line(&mut content_iterator, "codomain function")?
+                .split(',')
+                .collect();
+
+        if codomain_functions_split_line.len() != 1 {
+            return Err(ProblemGenError::InvalidParameters(
+                "configuration file should contain exactly one codomain function".to_owned(),
+            ));
+        }
 
-        assert_eq!(codomain_functions_split_line.len(), 1);
+        let codomain_function = CodomainFunction::from_line(codomain_functions_split_line[0])?;
 
-        let codomain_function_string = String::from(codomain_functions_split_line[0]);
-        let mut iter_list = vec![" "];
-        iter_list.extend(codomain_function_string.split(' '));
-        let codomain_function = CodomainFunction::from_iter(iter_list);
+        //Optionally, the number of configurations is capped by a max_configurations line
+        let max_configurations = match content_iterator.next() {
+            Some(line) if !line.trim().is_empty() => {
+                let mut split_line = line.split(' ');
+                if get_next_token(&mut split_line, "max_configurations")? != "max_configurations" {
+                    return Err(ProblemGenError::InvalidParameters(format!(
+                        "unrecognized line in configuration file: {}",
+                        line
+                    )));
+                }
+                Some(
+                    get_next_token(&mut split_line, "max_configurations")?
+                        .trim()
+                        .parse()?,
+                )
+            }
+            _ => None,
+        };
 
-        Ok(ConfigurationParameters::new(
+        let mut configuration_parameters = ConfigurationParameters::new(
             m_begin,
             m_end,
             k_begin,
@@ -107,7 +264,63 @@ impl ConfigurationParameters {
             b_begin,
             b_end,
             codomain_function,
-        ))
+        );
+        configuration_parameters.max_configurations = max_configurations;
+        configuration_parameters.o_fraction = o_fraction;
+        Ok(configuration_parameters)
+    }
+
+    ///Read configuration parameters from a TOML file, with a range (begin, end, and optional step) for M, k, o, and b
+    /// in the ranges table, a codomain_functions array with exactly one codomain function, and an optional max_configurations
+    pub fn from_toml(input_file_path: &Path) -> Result<ConfigurationParameters, ProblemGenError> {
+        let contents = std::fs::read_to_string(input_file_path)?;
+        let toml_configuration: TomlConfiguration =
+            toml::from_str(&contents).map_err(|error| ProblemGenError::Parse(error.to_string()))?;
+
+        if toml_configuration.codomain_functions.len() != 1 {
+            return Err(ProblemGenError::InvalidParameters(
+                "configuration file should contain exactly one codomain function".to_owned(),
+            ));
+        }
+        let codomain_function =
+            CodomainFunction::from_line(&toml_configuration.codomain_functions[0])?;
+
+        let ranges = toml_configuration.ranges;
+        for (name, range) in [
+            ("m", &ranges.m),
+            ("k", &ranges.k),
+            ("o", &ranges.o),
+            ("b", &ranges.b),
+        ]
+        .iter()
+        {
+            if range.step == 0 {
+                return Err(ProblemGenError::InvalidParameters(format!(
+                    "the step of the {} range should be at least 1",
+                    name
+                )));
+            }
+        }
+
+        let mut configuration_parameters = ConfigurationParameters::new(
+            ranges.m.begin,
+            ranges.m.end,
+            ranges.k.begin,
+            ranges.k.end,
+            ranges.o.begin,
+            ranges.o.end,
+            ranges.b.begin,
+            ranges.b.end,
+            codomain_function,
+        );
+        configuration_parameters.max_configurations = toml_configuration.max_configurations;
+        configuration_parameters.steps = ParameterSteps {
+            m: ranges.m.step,
+            k: ranges.k.step,
+            o: ranges.o.step,
+            b: ranges.b.step,
+        };
+        Ok(configuration_parameters)
     }
 }
 
@@ -132,11 +345,17 @@ pub struct ConfigurationParametersIterator {
     pub b_begin: u32,
     pub b_end: u32,
     pub codomain_function: CodomainFunction,
+    ///Number of input parameter configurations that may still be returned, or None if there is no cap
+    pub remaining_configurations: Option<u32>,
+    pub steps: ParameterSteps,
+    ///Range of o as a fraction of k, which replaces o_begin and o_end if set
+    pub o_fraction: Option<OverlapFraction>,
 
     pub current_parameters: InputParameters,
 }
 
 impl ConfigurationParametersIterator {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         m_begin: u32,
         m_end: u32,
@@ -158,6 +377,9 @@ impl ConfigurationParametersIterator {
             b_begin,
             b_end,
             codomain_function,
+            remaining_configurations: None,
+            steps: ParameterSteps::default(),
+            o_fraction: None,
             current_parameters: InputParameters::new_from_primitives(0, 0, 0, 0),
         }
     }
@@ -165,7 +387,7 @@ impl ConfigurationParametersIterator {
     pub fn from_configuration_parameters(
         configuration_parameters: &ConfigurationParameters,
     ) -> ConfigurationParametersIterator {
-        ConfigurationParametersIterator::new(
+        let mut iterator = ConfigurationParametersIterator::new(
             configuration_parameters.m_begin,
             configuration_parameters.m_end,
             configuration_parameters.k_begin,
@@ -175,7 +397,36 @@ impl ConfigurationParametersIterator {
             configuration_parameters.b_begin,
             configuration_parameters.b_end,
             configuration_parameters.codomain_function.clone(),
-        )
+        );
+        iterator.remaining_configurations = configuration_parameters.max_configurations;
+        iterator.steps = configuration_parameters.steps.clone();
+        iterator.o_fraction = configuration_parameters.o_fraction.clone();
+        iterator
+    }
+
+    ///Get the range of o for the given k, from begin (inclusive) to end (exclusive),
+    /// which is derived from k if the o range is a fraction of k
+    fn get_o_range(&self, k: u32) -> (u32, u32) {
+        match &self.o_fraction {
+            Some(o_fraction) => o_fraction.get_o_range(k),
+            None => (self.o_begin, self.o_end),
+        }
+    }
+
+    ///Advance to the next value of k, or to the next value of M (with the first value of k) if all values of k are handled,
+    /// and reset o and b to their first values. Returns false if all values of M are handled as well.
+    fn advance_k_or_m(&mut self) -> bool {
+        if self.current_parameters.k + self.steps.k < self.k_end {
+            self.current_parameters.k += self.steps.k;
+        } else if self.current_parameters.m + self.steps.m < self.m_end {
+            self.current_parameters.m += self.steps.m;
+            self.current_parameters.k = self.k_begin;
+        } else {
+            return false;
+        }
+        self.current_parameters.o = self.get_o_range(self.current_parameters.k).0;
+        self.current_parameters.b = self.b_begin;
+        true
     }
 }
 
@@ -184,34 +435,87 @@ impl Iterator for ConfigurationParametersIterator {
     type Item = InputParameters;
 
     fn next(&mut self) -> Option<Self::Item> {
+        //Stop when the cap on the number of configurations is reached
+        match self.remaining_configurations {
+            Some(0) => return None,
+            Some(remaining) => self.remaining_configurations = Some(remaining - 1),
+            None => {}
+        }
+
         if self.current_parameters.m == 0 {
             self.current_parameters = InputParameters::new_from_primitives(
                 self.m_begin,
                 self.k_begin,
-                self.o_begin,
+                self.get_o_range(self.k_begin).0,
                 self.b_begin,
             );
-        } else if self.current_parameters.b < self.b_end - 1 {
-            self.current_parameters.b += 1;
-        } else if self.current_parameters.o < self.o_end - 1 {
-            self.current_parameters.o += 1;
-            self.current_parameters.b = self.b_begin;
-        } else if self.current_parameters.k < self.k_end - 1 {
-            self.current_parameters.k += 1;
-            self.current_parameters.o = self.o_begin;
+        } else if self.current_parameters.b + self.steps.b < self.b_end {
+            self.current_parameters.b += self.steps.b;
+        } else if self.current_parameters.o + self.steps.o
+            < self.get_o_range(self.current_parameters.k).1
+        {
+            self.current_parameters.o += self.steps.o;
             self.current_parameters.b = self.b_begin;
-        } else if self.current_parameters.m < self.m_end - 1 {
-            self.current_parameters.m += 1;
-            self.current_parameters.k = self.k_begin;
-            self.current_parameters.o = self.o_begin;
-            self.current_parameters.b = self.b_begin;
-        } else {
+        } else if !self.advance_k_or_m() {
             return None;
         }
+
+        //With a fractional o range, skip the values of k for which the range contains no absolute values of o
+        while self.o_fraction.is_some() {
+            let (o_begin, o_end) = self.get_o_range(self.current_parameters.k);
+            if o_begin < o_end {
+                break;
+            }
+            if !self.advance_k_or_m() {
+                return None;
+            }
+        }
         Some(self.current_parameters.clone())
     }
 }
 
+///Get the next line of the configuration file, returning an error naming the missing line if there is none
+fn get_next_line<'a>(
+    content_iterator: &mut Lines<'a>,
+    line_name: &str,
+) -> Result<&'a str, ProblemGenError> {
+    content_iterator.next().ok_or_else(|| {
+        ProblemGenError::InvalidParameters(format!(
+            "configuration file does not contain the {} line",
+            line_name
+        ))
+    })
+}
+
+///Get the next token of a configuration line, returning an error naming the line if there is none
+fn get_next_token<'a>(
+    split_line: &mut impl Iterator<Item = &'a str>,
+    line_name: &str,
+) -> Result<&'a str, ProblemGenError> {
+    split_line.next().ok_or_else(|| {
+        ProblemGenError::InvalidParameters(format!(
+            "not enough values on the {} line of the configuration file",
+            line_name
+        ))
+    })
+}
+
+///Parse the o range as a fraction of k, checking that the fractions lie in [0, 1]
+fn parse_overlap_fraction(
+    o_begin_token: &str,
+    o_end_token: &str,
+) -> Result<OverlapFraction, ProblemGenError> {
+    let begin: f64 = o_begin_token.parse()?;
+    let end: f64 = o_end_token.parse()?;
+    if !(0.0..=1.0).contains(&begin) || !(0.0..=1.0).contains(&end) {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "the fractional o range {} {} should lie within 0.0 and 1.0",
+            o_begin_token, o_end_token
+        )));
+    }
+    Ok(OverlapFraction { begin, end })
+}
+
 //min problem size (incl. )
 fn get_m_for_min_problem_size(min_problem_size: u32, k: u32, o: u32) -> u32 {
     let a = (min_problem_size as i32 + (k - o) as i32 - k as i32) as f32 / (k - o) as f32;
@@ -229,4 +533,65 @@ pub fn get_rng(seed: Option<u64>) -> ChaChaRng {
         Some(seed) => ChaChaRng::seed_from_u64(seed),
         None => ChaChaRng::from_entropy(),
     }
-}
\ No newline at end of file
+}
+
+///Get the passed seed, or a random seed (from entropy) if no seed is passed
+pub fn get_seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| ChaChaRng::from_entropy().next_u64())
+}
+
+///Get the random number generator together with the seed it is seeded with, which is random if no seed is passed.
+/// The seed is logged, so that a run without a seed can be reproduced by passing the logged seed to `get_rng`.
+pub fn get_rng_logged(seed: Option<u64>) -> (ChaChaRng, u64) {
+    let seed = get_seed_or_random(seed);
+    info!("Using seed: {}", seed);
+    (ChaChaRng::seed_from_u64(seed), seed)
+}
+
+///Get the random number generator for a single instance, seeded deterministically from the seed of the passed rng,
+/// the input parameters, and the instance number.
+/// As the seed of a ChaChaRng does not change when it is used, the instance rng does not depend on any previously generated instances.
+pub fn get_instance_rng(
+    rng: &ChaChaRng,
+    input_parameters: &InputParameters,
+    num: u32,
+) -> ChaChaRng {
+    let mut seed = rng.get_seed();
+    let instance_values = [
+        input_parameters.m,
+        input_parameters.k,
+        input_parameters.o,
+        input_parameters.b,
+        num,
+    ];
+    for (seed_chunk, value) in seed.chunks_mut(4).zip(instance_values.iter()) {
+        for (seed_byte, value_byte) in seed_chunk.iter_mut().zip(value.to_le_bytes().iter()) {
+            *seed_byte ^= value_byte;
+        }
+    }
+    ChaChaRng::from_seed(seed)
+}
+
+///Shuffle the slice with a Fisher-Yates shuffle that only uses raw `next_u32` draws,
+/// so that the permutation does not depend on the version of the rand crate.
+/// It gives the same permutation as `SliceRandom::shuffle` of rand 0.8.
+pub fn shuffle<T>(slice: &mut [T], rng: &mut ChaChaRng) {
+    for i in (1..slice.len()).rev() {
+        slice.swap(i, get_random_index(i + 1, rng));
+    }
+}
+
+///Get a uniformly random index in 0..upper_bound, using rejection sampling on the widening multiplication of a random u32
+fn get_random_index(upper_bound: usize, rng: &mut ChaChaRng) -> usize {
+    assert!(upper_bound <= u32::MAX as usize);
+    let range = upper_bound as u32;
+    //Reject the values in the incomplete last 'zone', so that every index is equally likely
+    let zone = (range << range.leading_zeros()).wrapping_sub(1);
+    loop {
+        let multiplied = rng.next_u32() as u64 * range as u64;
+        let (high, low) = ((multiplied >> 32) as u32, multiplied as u32);
+        if low <= zone {
+            return high as usize;
+        }
+    }
+}
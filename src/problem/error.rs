@@ -0,0 +1,45 @@
+/*!
+Module for the error type returned by the functions that read and parse configurations, codomains, and problems.
+*/
+
+use thiserror::Error;
+
+use std::num::{ParseFloatError, ParseIntError};
+
+///Error type for the problem generator, to be able to distinguish between the kinds of failure
+#[derive(Error, Debug)]
+pub enum ProblemGenError {
+    ///Reading or writing a file failed
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    ///A value or line in a file could not be parsed
+    #[error("parse error: {0}")]
+    Parse(String),
+    ///The (input or configuration) parameters are missing or invalid
+    #[error("invalid parameters: {0}")]
+    InvalidParameters(String),
+    ///The codomain does not contain the number of values the input parameters specify
+    #[error("codomain dimension mismatch: expected {expected} values, but found {found}")]
+    CodomainDimensionMismatch { expected: usize, found: usize },
+    ///The codomain file has a header, but does not contain all codomain values
+    #[error("truncated codomain file: expected {expected} values, but found {found}")]
+    TruncatedFile { expected: usize, found: usize },
+    ///The checksum of the codomain values does not match the checksum in the codomain file header
+    #[error("codomain checksum mismatch: expected {expected:016x}, but calculated {found:016x}")]
+    ChecksumMismatch { expected: u64, found: u64 },
+    ///The codomain function could not be recognized
+    #[error("unknown codomain function: {0}")]
+    UnknownCodomainFunction(String),
+}
+
+impl From<ParseIntError> for ProblemGenError {
+    fn from(error: ParseIntError) -> Self {
+        ProblemGenError::Parse(error.to_string())
+    }
+}
+
+impl From<ParseFloatError> for ProblemGenError {
+    fn from(error: ParseFloatError) -> Self {
+        ProblemGenError::Parse(error.to_string())
+    }
+}
@@ -0,0 +1,272 @@
+/*!
+Module with the core of the TD Mk Landscape evaluation: the input parameters, the struct to contain solutions, and the fitness evaluation.
+This module only needs `alloc`, so that it is also available without the `std` feature, e.g. to evaluate problems in a WASM EA.
+*/
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+///Struct to contain the input parameters of the TD Mk Landscape:
+/// Number of cliques/subfunctions M,
+/// size k of each clique/subfunction,
+/// number of overlapping variables between cliques/subfunctions o,
+/// number of branches in the clique tree / tree decomposition b
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct InputParameters {
+    pub m: u32,
+    pub k: u32,
+    pub o: u32,
+    pub b: u32,
+    ///Number of overlapping variables per level of the clique tree, where the i-th value is used for the cliques at depth i + 1
+    /// (the root has no separator) and o is used for the deeper levels. Empty by default, so that o is used for every level.
+    /// The problem and codomain files only contain M, k, o, and b, so they can't be written if this is set;
+    ///  the binary format of `CliqueTree::to_bytes` does contain it.
+    #[cfg_attr(feature = "std", serde(default))]
+    pub o_per_level: Vec<u32>,
+}
+
+impl InputParameters {
+    pub fn new_from_primitives(m: u32, k: u32, o: u32, b: u32) -> InputParameters {
+        InputParameters {
+            m,
+            k,
+            o,
+            b,
+            o_per_level: Vec::new(),
+        }
+    }
+
+    ///Check whether the cliques do not overlap, i.e. whether o is 0 for every level
+    pub fn is_separable(&self) -> bool {
+        self.o == 0 && self.o_per_level.iter().all(|&o| o == 0)
+    }
+
+    ///Get the branching factor used to construct the clique tree; separable problems are constructed as a chain (b = 1)
+    pub fn branching_factor(&self) -> u32 {
+        if self.is_separable() {
+            1
+        } else {
+            self.b
+        }
+    }
+
+    ///Get the index of the parent of the clique at the given index, derived from the branching factor, or None for the root
+    pub fn parent_index(&self, clique_index: u32) -> Option<u32> {
+        if clique_index == 0 {
+            None
+        } else {
+            Some((clique_index - 1) / self.branching_factor())
+        }
+    }
+
+    ///Get the number of variables the clique at the given index shares with its parent, which depends on the clique's level
+    /// if o_per_level is set. The root has no parent, so its separator size is 0.
+    pub fn separator_size(&self, clique_index: u32) -> u32 {
+        if clique_index == 0 {
+            return 0;
+        }
+        if self.o_per_level.is_empty() {
+            return self.o;
+        }
+
+        let mut level = 0;
+        let mut current_index = clique_index;
+        while let Some(parent_index) = self.parent_index(current_index) {
+            level += 1;
+            current_index = parent_index;
+        }
+        self.o_per_level.get(level - 1).copied().unwrap_or(self.o)
+    }
+
+    ///Get the problem size, the number of variables of a clique tree with these input parameters
+    pub fn problem_size(&self) -> u32 {
+        if self.o_per_level.is_empty() {
+            return (self.m - 1) * (self.k - self.o) + self.k;
+        }
+        self.k
+            + (1..self.m)
+                .map(|clique_index| self.k - self.separator_size(clique_index))
+                .sum::<u32>()
+    }
+}
+
+///Struct to contain the solution and its fitness, with the solution stored as a vector of u32 values (0 or 1) and the fitness as a f64 value
+#[derive(Debug, Clone)]
+pub struct SolutionFit {
+    pub solution: Vec<u32>,
+    pub fitness: f64,
+}
+
+impl SolutionFit {
+    ///Create a solution with the given fitness. The caller is responsible for passing the fitness of the solution,
+    /// use evaluate to calculate it instead. In debug builds, the solution is checked to only contain 0 and 1 values,
+    ///  and the fitness is checked to be a number.
+    pub fn new(solution: Vec<u32>, fitness: f64) -> SolutionFit {
+        debug_assert!(
+            solution.iter().all(|&bit| bit <= 1),
+            "solution should only contain 0 and 1 values"
+        );
+        debug_assert!(!fitness.is_nan(), "fitness should not be NaN");
+        SolutionFit { solution, fitness }
+    }
+}
+
+///The part of a clique tree needed to evaluate solutions: its cliques and codomain values.
+/// A CliqueTree can be converted into it, to evaluate its solutions where only `alloc` is available.
+#[derive(Debug, Clone)]
+pub struct CliqueTreeEvaluator {
+    pub cliques: Vec<Vec<u32>>,
+    pub codomain_values: Vec<Vec<f64>>,
+    ///Indices of the cliques that contain each variable, used by the delta evaluation.
+    /// Calculated from the cliques on construction, see variable_cliques()
+    pub(crate) variable_cliques: Vec<Vec<u32>>,
+}
+
+impl CliqueTreeEvaluator {
+    pub fn new(cliques: Vec<Vec<u32>>, codomain_values: Vec<Vec<f64>>) -> CliqueTreeEvaluator {
+        CliqueTreeEvaluator {
+            variable_cliques: get_variable_cliques(&cliques),
+            cliques,
+            codomain_values,
+        }
+    }
+
+    ///Get, for every variable index, the indices of the cliques that contain the variable, as calculated on construction
+    pub fn variable_cliques(&self) -> &[Vec<u32>] {
+        &self.variable_cliques
+    }
+
+    ///Calculate the fitnesss of a passed solution
+    pub fn calculate_fitness(&self, solution: &[u32], number_evaluations: &mut u32) -> f64 {
+        *number_evaluations += 1;
+        calculate_fitness(&self.cliques, &self.codomain_values, solution)
+    }
+
+    ///Calculate the fitness of a passed solution using the knowledge that only one bit will be flipped,
+    /// and given that the solution has **not** been mutated at the given index yet
+    pub fn calculate_fitness_delta(
+        &self,
+        current_solutionfit: &SolutionFit,
+        number_evaluations: &mut u32,
+        index_mutation: u32,
+    ) -> f64 {
+        *number_evaluations += 1;
+        calculate_fitness_delta(
+            &self.cliques,
+            &self.codomain_values,
+            get_cliques_of_variable(&self.variable_cliques, index_mutation),
+            current_solutionfit,
+            index_mutation,
+        )
+    }
+
+    ///Create a solution together with its fitness, so that the solution and its fitness are consistent
+    pub fn evaluate(&self, solution: Vec<u32>, number_evaluations: &mut u32) -> SolutionFit {
+        let fitness = self.calculate_fitness(&solution, number_evaluations);
+        SolutionFit::new(solution, fitness)
+    }
+}
+
+///Calculate the fitness of a passed solution, as the sum over the cliques of the codomain value of the solution's substring in the clique
+pub fn calculate_fitness(cliques: &[Vec<u32>], codomain_values: &[Vec<f64>], solution: &[u32]) -> f64 {
+    //First set the fitness to 0.0
+    let mut fitness = 0.0;
+
+    //Then loop over all the cliques
+    for (clique_index, clique) in cliques.iter().enumerate() {
+        //And for each clique calculate the solution substring for this clique, as an index into an array of these substrings.
+        let mut clique_substring_as_index = 0;
+        //Go over each variable index in the clique and for each one, take the bit value from the solution string and add it to the clique substring.
+        for j in (0..clique.len()).rev() {
+            clique_substring_as_index += solution[clique[j] as usize] << (clique.len() - j - 1);
+        }
+
+        //Add the fitness contribution of this clique
+        fitness += codomain_values[clique_index][clique_substring_as_index as usize];
+    }
+
+    fitness
+}
+
+///Calculate the fitness of a passed solution using the knowledge that only one bit will be flipped,
+/// given the indices of the cliques that contain the mutated variable,
+/// and given that the solution has **not** been mutated at the given index yet
+pub fn calculate_fitness_delta(
+    cliques: &[Vec<u32>],
+    codomain_values: &[Vec<f64>],
+    cliques_of_variable: &[u32],
+    current_solutionfit: &SolutionFit,
+    index_mutation: u32,
+) -> f64 {
+    //First set the fitness to the current fitness
+    let mut fitness = current_solutionfit.fitness;
+
+    //Then loop over all the cliques that contain the mutated variable
+    for &clique_index in cliques_of_variable {
+        let clique_index = clique_index as usize;
+        //And for each clique calculate the solution substring for this clique, as an index into an array of these substrings.
+        let mut clique_substring_as_index = 0;
+        //Create variable to conveniently store reference to the current clique in.
+        let clique = &cliques[clique_index];
+
+        //We will store the index in the clique of the bit that will be flipped
+        let mut clique_mutation_index = 0;
+
+        //Go over each variable index in the clique and for each one, take the bit value from the solution string and add it to the clique substring.
+        for j in (0..clique.len()).rev() {
+            //If the solution index of the considered index is equal to the index of the mutated bit, we store the index (in this clique) for future use.
+            if clique[j] == index_mutation {
+                clique_mutation_index = j;
+            }
+
+            //As we would otherwise do, add all the bits from the solution to the clique's subsolution, to be evaluated hereafter
+            clique_substring_as_index +=
+                current_solutionfit.solution[clique[j] as usize] << (clique.len() - j - 1);
+        }
+
+        //Substract the fitness contribution of this clique, as this has been previously added to get to the current fitness.
+        fitness -= codomain_values[clique_index][clique_substring_as_index as usize];
+
+        //Now set the bit in the clique's subsolution to the value it would be after mutation.
+        // It looks a bit involved, as we use u32 values.
+        if current_solutionfit.solution[clique[clique_mutation_index] as usize] == 0 {
+            clique_substring_as_index += 1 << (clique.len() - clique_mutation_index - 1);
+        } else {
+            clique_substring_as_index -= 1 << (clique.len() - clique_mutation_index - 1);
+        }
+
+        //Add the fitness contribution of this clique, taking into account the mutation.
+        fitness += codomain_values[clique_index][clique_substring_as_index as usize];
+
+        //Now we subtracted the old codomain value of this clique and have added the new value.
+    }
+
+    fitness
+}
+
+///Get, for every variable index up to the highest variable index in the cliques, the indices of the cliques that contain it
+pub(crate) fn get_variable_cliques(cliques: &[Vec<u32>]) -> Vec<Vec<u32>> {
+    let number_of_variables = cliques
+        .iter()
+        .flatten()
+        .max()
+        .map_or(0, |&max_variable_index| max_variable_index as usize + 1);
+    let mut variable_cliques = vec![Vec::new(); number_of_variables];
+    for (clique_index, clique) in cliques.iter().enumerate() {
+        for &variable_index in clique {
+            variable_cliques[variable_index as usize].push(clique_index as u32);
+        }
+    }
+    variable_cliques
+}
+
+///Get the indices of the cliques that contain the variable, or no cliques if the variable index is out of range
+pub(crate) fn get_cliques_of_variable(variable_cliques: &[Vec<u32>], variable_index: u32) -> &[u32] {
+    variable_cliques
+        .get(variable_index as usize)
+        .map_or(&[], |clique_indices| clique_indices.as_slice())
+}
@@ -0,0 +1,165 @@
+/*!
+Module for exporting clique trees to file formats of other solvers, such as weighted MaxSAT (WCNF),
+ and of their variable interaction graph to graph file formats.
+*/
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use super::clique_tree::CliqueTree;
+use super::codomain_subclasses::CodomainFunction;
+use super::error::ProblemGenError;
+
+///Maximum difference between a scaled codomain value and the nearest integer, for it to be considered integer-valued
+const INTEGER_EPSILON: f64 = 0.000001;
+
+///File format of an exported variable interaction graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    ///One edge per line as two 0-indexed variable indices, separated by a space
+    EdgeList,
+    ///DIMACS graph format, with a `p edge N E` header and an `e U V` line per edge, with 1-indexed variables
+    Dimacs,
+}
+
+impl GraphFormat {
+    ///Get the file extension of graph files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::EdgeList => "edgelist",
+            GraphFormat::Dimacs => "dimacs",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "edgelist" => Ok(GraphFormat::EdgeList),
+            "dimacs" => Ok(GraphFormat::Dimacs),
+            _ => Err(format!(
+                "unknown graph format '{}', expected 'edgelist' or 'dimacs'",
+                s
+            )),
+        }
+    }
+}
+
+impl CliqueTree {
+    ///Write the problem of finding the global optimum as a weighted MaxSAT problem, in the (DIMACS-style) WCNF format.
+    /// For every clique substring with a value below the clique's highest value, a soft clause is added that is only falsified by that substring,
+    ///  with as weight the difference with the highest value. Minimizing the weight of the falsified clauses then maximizes the fitness.
+    /// The codomain values need to be integer-valued, where NKq values are first scaled by (q - 1).
+    pub fn to_wcnf(&self, path: &Path) -> Result<(), ProblemGenError> {
+        let scale = match self.codomain_function {
+            CodomainFunction::NKq { q } => (q - 1) as f64,
+            _ => 1.0,
+        };
+
+        //Calculate all clauses as the weight and the literals (1-indexed, negative for negated variables)
+        let mut clauses: Vec<(u64, Vec<i64>)> = Vec::new();
+        for (clique, clique_codomain) in self.cliques.iter().zip(self.codomain_values.iter()) {
+            let scaled_values = clique_codomain
+                .iter()
+                .map(|value| get_integer_value(value * scale))
+                .collect::<Result<Vec<i64>, ProblemGenError>>()?;
+            let highest_value = *scaled_values
+                .iter()
+                .max()
+                .expect("clique codomain should not be empty");
+
+            for (substring_index, value) in scaled_values.iter().enumerate() {
+                let weight = (highest_value - value) as u64;
+                if weight == 0 {
+                    continue;
+                }
+                //The clause is falsified only when every variable has the value it has in the substring
+                let literals = clique
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &variable_index)| {
+                        let bit = (substring_index >> (clique.len() - j - 1)) & 1;
+                        let variable = variable_index as i64 + 1;
+                        if bit == 1 {
+                            -variable
+                        } else {
+                            variable
+                        }
+                    })
+                    .collect();
+                clauses.push((weight, literals));
+            }
+        }
+
+        let file = File::create(path)?;
+        let mut buf_writer = BufWriter::new(file);
+
+        //The weight of hard clauses (top) is higher than the sum of all soft clause weights, there are no hard clauses.
+        let top = clauses.iter().map(|(weight, _)| weight).sum::<u64>() + 1;
+        writeln!(
+            buf_writer,
+            "p wcnf {} {} {}",
+            self.get_problem_size(),
+            clauses.len(),
+            top
+        )?;
+        for (weight, literals) in &clauses {
+            write!(buf_writer, "{}", weight)?;
+            for literal in literals {
+                write!(buf_writer, " {}", literal)?;
+            }
+            writeln!(buf_writer, " 0")?;
+        }
+
+        buf_writer.flush()?;
+        Ok(())
+    }
+
+    ///Write the variable interaction graph (see `interaction_graph`) to the given path in the given graph format,
+    /// for use in graph-analysis and treewidth tools.
+    pub fn write_interaction_graph(
+        &self,
+        path: &Path,
+        format: GraphFormat,
+    ) -> Result<(), ProblemGenError> {
+        let (number_of_variables, edges) = self.interaction_graph();
+
+        let file = File::create(path)?;
+        let mut buf_writer = BufWriter::new(file);
+
+        match format {
+            GraphFormat::EdgeList => {
+                for (u, v) in &edges {
+                    writeln!(buf_writer, "{} {}", u, v)?;
+                }
+            }
+            GraphFormat::Dimacs => {
+                writeln!(buf_writer, "p edge {} {}", number_of_variables, edges.len())?;
+                for (u, v) in &edges {
+                    writeln!(buf_writer, "e {} {}", u + 1, v + 1)?;
+                }
+            }
+        }
+
+        buf_writer.flush()?;
+        Ok(())
+    }
+}
+
+///Get the integer value of a (scaled) codomain value, returning an error if it is not integer-valued
+fn get_integer_value(value: f64) -> Result<i64, ProblemGenError> {
+    let rounded_value = value.round();
+    if (value - rounded_value).abs() > INTEGER_EPSILON {
+        return Err(ProblemGenError::InvalidParameters(format!(
+            "codomain value {} is not integer-valued, which is required for the WCNF export",
+            value
+        )));
+    }
+    Ok(rounded_value as i64)
+}
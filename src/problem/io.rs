@@ -2,40 +2,40 @@
 Module for functions related to reading and writing to files, mainly for reading stored clique trees
 */
 
-use itertools::Itertools;
 use rand_chacha::ChaChaRng;
-use structopt::StructOpt;
-// use itertools::Itertools;
-use itertools::izip;
 
 use std::{
+    collections::BTreeMap,
     error::Error,
     fs::{self, remove_dir_all},
     path::{Path, PathBuf},
 };
 
-use super::clique_tree::{CliqueTree, InputParameters};
-use super::codomain::read_codomain;
+use super::clique_tree::{get_variable_cliques, CliqueTree, InputParameters};
+use super::codomain::{
+    parse_codomain_with_known_parameters, read_codomain, split_codomain_file_header,
+};
 use super::codomain_subclasses::CodomainFunction;
+use super::error::ProblemGenError;
 
 /// Construct and return clique tree using input codomain file; use codomain and input parameters.
 pub fn get_clique_tree_from_codomain_file(
     codomain_file_path: &Path,
     file_has_codomain_function: bool,
     rng: &mut ChaChaRng
-) -> Result<CliqueTree, Box<dyn Error>> {
-    let contents = fs::read_to_string(&codomain_file_path)?;
-    let mut content_iterator = contents.lines();
+) -> Result<CliqueTree, ProblemGenError> {
+    let contents = fs::read_to_string(codomain_file_path)?;
+    let (_header, mut content_iterator) = split_codomain_file_header(&contents)?;
 
     let codomain_function = if file_has_codomain_function {
         //Read codomain function
-        let first_line = content_iterator
-            .next()
-            .ok_or("Input file does not contain enough entries")?;
+        let first_line = content_iterator.next().ok_or_else(|| {
+            ProblemGenError::InvalidParameters(
+                "Input file does not contain enough entries".to_owned(),
+            )
+        })?;
 
-        let mut iter_list = vec![" "];
-        iter_list.extend(first_line.split(' '));
-        CodomainFunction::from_iter(iter_list)
+        CodomainFunction::from_line(first_line)?
     } else {
         CodomainFunction::Unknown
     };
@@ -56,32 +56,102 @@ pub fn get_clique_tree_from_codomain_file(
     Ok(clique_tree)
 }
 
+/// Construct and return clique tree using input codomain file and the given input parameters,
+///  for (legacy) codomain files that may or may not contain the input parameters line; see read_codomain_with_known_parameters.
+///   The given input parameters are validated before the file is read.
+pub fn get_clique_tree_from_codomain_file_with_parameters(
+    codomain_file_path: &Path,
+    input_parameters: InputParameters,
+    rng: &mut ChaChaRng,
+) -> Result<CliqueTree, ProblemGenError> {
+    input_parameters.validate()?;
+
+    //Read the codomain function, if the file contains it, and the codomain
+    let contents = fs::read_to_string(codomain_file_path)?;
+    let (codomain_function, codomain) =
+        parse_codomain_with_known_parameters(&input_parameters, &contents)?;
+
+    //Generate a clique tree that adheres to the given input parameters. The clique tree also calculates the global optimum.
+    Ok(CliqueTree::new(input_parameters, codomain_function, codomain, rng))
+}
+
+///Detect whether the codomain file contains the codomain function on the first line,
+/// by checking whether the first line is a line of input parameters instead
+pub fn codomain_file_has_codomain_function(
+    codomain_file_path: &Path,
+) -> Result<bool, ProblemGenError> {
+    let contents = fs::read_to_string(codomain_file_path)?;
+    let (_header, mut content_iterator) = split_codomain_file_header(&contents)?;
+    let first_line = content_iterator.next().ok_or_else(|| {
+        ProblemGenError::InvalidParameters("Input file does not contain enough entries".to_owned())
+    })?;
+
+    Ok(!is_input_parameters_line(first_line))
+}
+
+///Check whether the line is a line of input parameters, i.e. whether it consists of four integers
+pub(crate) fn is_input_parameters_line(line: &str) -> bool {
+    let parameters: Vec<&str> = line.split(' ').collect();
+    parameters.len() == 4
+        && parameters
+            .iter()
+            .all(|parameter| parameter.parse::<u32>().is_ok())
+}
+
 ///Get the clique tree and path for each file in the passed codomain folder path
 pub fn get_clique_trees_paths_from_codomain_folder(
     folder_path: &Path,
     files_have_codomain_function: bool,
     rng: &mut ChaChaRng
-) -> Result<Vec<(CliqueTree, PathBuf)>, Box<dyn Error>> {
-    Ok(folder_path
+) -> Result<Vec<(CliqueTree, PathBuf)>, ProblemGenError> {
+    let mut paths = folder_path
         .read_dir()?
-        .map(|file| file.unwrap().path())
-        .sorted()
+        .map(|file| file.map(|file| file.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.sort_by_cached_key(|path| get_instance_file_sort_key(path));
+
+    paths
+        .into_iter()
         .map(|path| {
-            (
-                get_clique_tree_from_codomain_file(&path, files_have_codomain_function, rng).unwrap(),
-                path,
-            )
+            get_clique_tree_from_codomain_file(&path, files_have_codomain_function, rng)
+                .map(|clique_tree| (clique_tree, path))
         })
-        .collect())
+        .collect()
 }
 
+///Get the key to sort instance files named `{codomain function}_{M}_{k}_{o}_{b}_{instance number}` by:
+/// the codomain function name, followed by the numeric parameters and instance number,
+/// so that e.g. instance 2 comes before instance 10.
+/// Files not following this naming scheme get an empty list of numbers, and are thus sorted by name only.
+fn get_instance_file_sort_key(path: &Path) -> (String, Vec<u64>) {
+    let file_stem = path
+        .file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let fields: Vec<&str> = file_stem.rsplitn(6, '_').collect();
+    if fields.len() == 6 {
+        let numbers: Result<Vec<u64>, _> = fields[..5].iter().rev().map(|field| field.parse()).collect();
+        if let Ok(numbers) = numbers {
+            return (fields[5].to_owned(), numbers);
+        }
+    }
+    (file_stem, Vec::new())
+}
+
+///Triple of paths to a configuration file, its codomain folder, and its problem folder
+pub type FolderFileTriple = (PathBuf, PathBuf, PathBuf);
+
 ///Get from a folder the triples configuration_parameters - problem_folder - codomain_folder
 /// from the problem_generation, problems, and codomain_files folders.
-/// Each file in problem_generation is coupled with the corresponding folder in 'problems' and 'codomain_files'
+/// Each file in problem_generation is coupled with the corresponding folder in 'problems' and 'codomain_files'.
+/// If a codomain or problem directory is passed, it is used instead of the 'codomain_files' or 'problems' folder.
 pub fn get_folders_file_triples(
     input_folder_path: &Path,
+    codomain_dir: Option<&Path>,
+    problem_dir: Option<&Path>,
     remove_results_folder: bool,
-) -> Result<Vec<(PathBuf, PathBuf, PathBuf)>, Box<dyn Error>> {
+) -> Result<Vec<FolderFileTriple>, Box<dyn Error>> {
     //If we want to remove (previous) results, remove the results folder
     if remove_results_folder {
         let mut results_folder_path = PathBuf::from(input_folder_path);
@@ -98,62 +168,324 @@ pub fn get_folders_file_triples(
     //Get the problem_generation, codomain_files, and problems folders.
     let mut problem_generation_folder = PathBuf::from(input_folder_path);
     problem_generation_folder.push("problem_generation");
-    let mut codomain_files_folder = PathBuf::from(input_folder_path);
-    codomain_files_folder.push("codomain_files");
-    let mut problem_files_folder = PathBuf::from(input_folder_path);
-    problem_files_folder.push("problems");
-
-    //And read all files/folders inside these folders
-    //Sort these, so that we can pass the files together without searching for the accompanying folder or file
-    let file_entries: Vec<PathBuf> = problem_generation_folder
-        .read_dir()?
-        .map(|file| file.unwrap().path())
-        .sorted()
-        .collect();
-    let codomain_folder_entries: Vec<PathBuf> = codomain_files_folder
-        .read_dir()?
-        .map(|file| file.unwrap().path())
-        .sorted()
-        .collect();
-    let problem_folder_entries: Vec<PathBuf> = problem_files_folder
-        .read_dir()?
-        .map(|file| file.unwrap().path())
-        .sorted()
-        .collect();
-
-    assert_eq!(file_entries.len(), codomain_folder_entries.len());
-    assert_eq!(file_entries.len(), problem_folder_entries.len());
-
-    //And couple the matching entries (matching is ensured by the sorting, as the same name is used for all three)
-    Ok(izip!(
-        file_entries,
-        codomain_folder_entries,
-        problem_folder_entries
-    )
-    .collect())
+    let codomain_files_folder = codomain_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| input_folder_path.join("codomain_files"));
+    let problem_files_folder = problem_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| input_folder_path.join("problems"));
+
+    //And read all files/folders inside these folders, keyed on their file stem
+    let mut codomain_folder_entries = get_folder_entries_by_stem(&codomain_files_folder)?;
+    let mut problem_folder_entries = get_folder_entries_by_stem(&problem_files_folder)?;
+
+    //Couple each configuration file with the codomain and problem folder with the same name
+    let configuration_entries = get_folder_entries_by_stem(&problem_generation_folder)?;
+    if configuration_entries.is_empty() {
+        return Err(format!("no configuration files found in {:?}", problem_generation_folder).into());
+    }
+    let mut folder_file_triples = Vec::new();
+    for (stem, file_entry) in configuration_entries {
+        let codomain_folder_entry = codomain_folder_entries.remove(&stem).ok_or_else(|| {
+            format!("no codomain folder {:?} found for configuration file {:?}", stem, file_entry)
+        })?;
+        let problem_folder_entry = problem_folder_entries.remove(&stem).ok_or_else(|| {
+            format!("no problem folder {:?} found for configuration file {:?}", stem, file_entry)
+        })?;
+        folder_file_triples.push((file_entry, codomain_folder_entry, problem_folder_entry));
+    }
+
+    //Every codomain and problem folder should belong to a configuration file
+    if let Some(codomain_folder_entry) = codomain_folder_entries.values().next() {
+        return Err(format!("no configuration file found for codomain folder {:?}", codomain_folder_entry).into());
+    }
+    if let Some(problem_folder_entry) = problem_folder_entries.values().next() {
+        return Err(format!("no configuration file found for problem folder {:?}", problem_folder_entry).into());
+    }
+
+    Ok(folder_file_triples)
+}
+
+///Get the entries of a folder keyed on (and sorted by) their file stem, skipping hidden entries such as `.DS_Store`
+fn get_folder_entries_by_stem(folder_path: &Path) -> Result<BTreeMap<String, PathBuf>, Box<dyn Error>> {
+    let mut entries = BTreeMap::new();
+    let read_dir = folder_path
+        .read_dir()
+        .map_err(|error| format!("could not read folder {:?}: {}", folder_path, error))?;
+    for entry in read_dir {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|file_name| file_name.to_string_lossy().starts_with('.'));
+        if is_hidden {
+            continue;
+        }
+        let stem = path
+            .file_stem()
+            .ok_or_else(|| format!("could not get the file stem of {:?}", path))?
+            .to_string_lossy()
+            .into_owned();
+        if let Some(previous_path) = entries.insert(stem, path.clone()) {
+            return Err(format!("{:?} and {:?} have the same file stem", previous_path, path).into());
+        }
+    }
+    Ok(entries)
+}
+
+///Get the configuration files in the given folder, sorted by path and skipping folders and hidden files such as `.DS_Store`.
+/// Returns a descriptive error if the folder does not exist or contains no configuration files,
+///  which usually means that the wrong folder was passed.
+pub fn get_configuration_files(folder_path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if !folder_path.is_dir() {
+        return Err(format!("configuration folder {:?} does not exist", folder_path).into());
+    }
+
+    let mut configuration_files = Vec::new();
+    for entry in folder_path.read_dir()? {
+        let entry = entry?;
+        let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !is_hidden && !entry.file_type()?.is_dir() {
+            configuration_files.push(entry.path());
+        }
+    }
+    if configuration_files.is_empty() {
+        return Err(format!("no configuration files found in {:?}", folder_path).into());
+    }
+
+    configuration_files.sort();
+    Ok(configuration_files)
 }
 
 /// Get the output folder path for a given input configuration file
 /// For example, passing "problem_generation/deceptive_trap_separated.txt"
 ///  and "results" , will create and return the folder "results/deceptive_trap_separated"
+/// If an output root directory is passed, the output folder is created under it instead,
+///  for example "OUTPUT_ROOT/results/deceptive_trap_separated"
 pub fn get_output_folder_path_from_configuration_file(
     input_configuration_file_path: &Path,
     output_directory_name: &str,
+    output_root_directory: Option<&Path>,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let mut output_folder_path = match output_root_directory {
+        Some(output_root_directory) => PathBuf::from(output_root_directory),
+        None => PathBuf::from(
+            input_configuration_file_path
+                .parent()
+                .ok_or("could not get parent")?
+                .parent()
+                .ok_or("could not get parent's parent")?,
+        ),
+    };
+    output_folder_path.push(output_directory_name);
+
+    get_configuration_folder_path(input_configuration_file_path, &output_folder_path)
+}
+
+/// Get the folder of a given input configuration file in a directory with a folder per configuration
+/// For example, passing "problem_generation/deceptive_trap_separated.txt"
+///  and "/data/codomain_files", will create and return the folder "/data/codomain_files/deceptive_trap_separated"
+pub fn get_configuration_folder_path(
+    input_configuration_file_path: &Path,
+    directory_path: &Path,
 ) -> Result<PathBuf, Box<dyn Error>> {
     let file_name = input_configuration_file_path.file_stem().ok_or(
         "could not get file stem of input configuration file while calculating output path",
     )?;
 
-    let mut output_folder_path = PathBuf::from(
-        input_configuration_file_path
-            .parent()
-            .ok_or("could not get parent")?
-            .parent()
-            .ok_or("could not get parent's parent")?,
-    );
-    output_folder_path.push(output_directory_name);
-    output_folder_path.push(file_name);
-
+    let output_folder_path = directory_path.join(file_name);
     std::fs::create_dir_all(&output_folder_path)?;
     Ok(output_folder_path)
 }
+
+///Magic bytes at the start of a binary clique tree
+const CLIQUE_TREE_BYTES_MAGIC: &[u8; 4] = b"TDMK";
+
+///Current version of the binary clique tree format. Version 2 added the number of overlapping variables per level,
+/// version 1 is still read.
+pub const CLIQUE_TREE_BYTES_VERSION: u32 = 2;
+
+impl CliqueTree {
+    ///Serialize the clique tree to a compact binary format, to cache clique trees that are expensive to construct.
+    /// All numbers are stored little-endian, and the global optima are stored as well, so that `from_bytes` does not need to recalculate them.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(CLIQUE_TREE_BYTES_MAGIC);
+        push_u32(&mut bytes, CLIQUE_TREE_BYTES_VERSION);
+
+        let input_parameters = &self.input_parameters;
+        for parameter in &[
+            input_parameters.m,
+            input_parameters.k,
+            input_parameters.o,
+            input_parameters.b,
+        ] {
+            push_u32(&mut bytes, *parameter);
+        }
+        push_u32(&mut bytes, input_parameters.o_per_level.len() as u32);
+        for o in &input_parameters.o_per_level {
+            push_u32(&mut bytes, *o);
+        }
+
+        let codomain_function = self.codomain_function.to_string();
+        push_u32(&mut bytes, codomain_function.len() as u32);
+        bytes.extend_from_slice(codomain_function.as_bytes());
+
+        for cliques_or_separators in &[&self.cliques, &self.separators] {
+            push_u32(&mut bytes, cliques_or_separators.len() as u32);
+            for variable_indices in cliques_or_separators.iter() {
+                push_u32(&mut bytes, variable_indices.len() as u32);
+                for variable_index in variable_indices {
+                    push_u32(&mut bytes, *variable_index);
+                }
+            }
+        }
+
+        push_u32(&mut bytes, self.codomain_values.len() as u32);
+        for clique_codomain in &self.codomain_values {
+            push_u32(&mut bytes, clique_codomain.len() as u32);
+            for value in clique_codomain {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        //The global optima are stored with one byte per bit
+        bytes.extend_from_slice(&self.glob_optima_score.to_le_bytes());
+        bytes.extend_from_slice(&self.fitness_epsilon.to_le_bytes());
+        push_u32(&mut bytes, self.glob_optima_strings.len() as u32);
+        for global_optimum in &self.glob_optima_strings {
+            push_u32(&mut bytes, global_optimum.len() as u32);
+            bytes.extend(global_optimum.iter().map(|&bit| bit as u8));
+        }
+
+        bytes
+    }
+
+    ///Deserialize a clique tree from the binary format written by `to_bytes`, without recalculating the global optima
+    pub fn from_bytes(bytes: &[u8]) -> Result<CliqueTree, ProblemGenError> {
+        let mut reader = ByteReader { bytes, position: 0 };
+
+        if reader.read_bytes(CLIQUE_TREE_BYTES_MAGIC.len())? != CLIQUE_TREE_BYTES_MAGIC {
+            return Err(ProblemGenError::Parse(
+                "bytes do not start with the clique tree magic bytes".to_owned(),
+            ));
+        }
+        let version = reader.read_u32()?;
+        if version == 0 || version > CLIQUE_TREE_BYTES_VERSION {
+            return Err(ProblemGenError::Parse(format!(
+                "unsupported clique tree bytes version {}",
+                version
+            )));
+        }
+
+        let mut input_parameters = InputParameters::new_from_primitives(
+            reader.read_u32()?,
+            reader.read_u32()?,
+            reader.read_u32()?,
+            reader.read_u32()?,
+        );
+        if version >= 2 {
+            let number_of_levels = reader.read_u32()?;
+            let o_per_level = (0..number_of_levels)
+                .map(|_| reader.read_u32())
+                .collect::<Result<Vec<u32>, _>>()?;
+            input_parameters = input_parameters.with_o_per_level(o_per_level)?;
+        }
+
+        let codomain_function_length = reader.read_u32()? as usize;
+        let codomain_function = std::str::from_utf8(reader.read_bytes(codomain_function_length)?)
+            .map_err(|error| ProblemGenError::Parse(error.to_string()))?;
+        let codomain_function = CodomainFunction::from_line(codomain_function)?;
+
+        let cliques = reader.read_nested(|reader| reader.read_u32())?;
+        let separators = reader.read_nested(|reader| reader.read_u32())?;
+        let codomain_values = reader.read_nested(|reader| reader.read_f64())?;
+        if cliques.len() != input_parameters.m as usize
+            || separators.len() != cliques.len()
+            || codomain_values.len() != cliques.len()
+        {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "expected {} cliques, separators, and clique codomains, but found {}, {}, and {}",
+                input_parameters.m,
+                cliques.len(),
+                separators.len(),
+                codomain_values.len()
+            )));
+        }
+
+        let glob_optima_score = reader.read_f64()?;
+        let fitness_epsilon = reader.read_f64()?;
+        let glob_optima_strings =
+            reader.read_nested(|reader| Ok(reader.read_bytes(1)?[0] as u32))?;
+
+        if reader.position != bytes.len() {
+            return Err(ProblemGenError::Parse(format!(
+                "{} trailing bytes after the clique tree",
+                bytes.len() - reader.position
+            )));
+        }
+
+        Ok(CliqueTree {
+            input_parameters,
+            codomain_function,
+            variable_cliques: get_variable_cliques(&cliques),
+            cliques,
+            separators,
+            codomain_values,
+            glob_optima_strings,
+            glob_optima_score,
+            fitness_epsilon,
+        })
+    }
+}
+
+///Append the u32 value to the bytes, little-endian
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+///Reader over the bytes of a binary clique tree, that keeps track of the current position
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    ///Read the next length bytes, returning an error if there are not enough bytes left
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], ProblemGenError> {
+        let end = self.position + length;
+        let read_bytes = self.bytes.get(self.position..end).ok_or_else(|| {
+            ProblemGenError::Parse(format!(
+                "unexpected end of the clique tree bytes at position {}",
+                self.position
+            ))
+        })?;
+        self.position = end;
+        Ok(read_bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProblemGenError> {
+        let mut value_bytes = [0; 4];
+        value_bytes.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(value_bytes))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, ProblemGenError> {
+        let mut value_bytes = [0; 8];
+        value_bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(f64::from_le_bytes(value_bytes))
+    }
+
+    ///Read a length-prefixed list of length-prefixed lists, reading every element with read_element
+    fn read_nested<T>(
+        &mut self,
+        read_element: impl Fn(&mut Self) -> Result<T, ProblemGenError>,
+    ) -> Result<Vec<Vec<T>>, ProblemGenError> {
+        let outer_length = self.read_u32()?;
+        (0..outer_length)
+            .map(|_| {
+                let inner_length = self.read_u32()?;
+                (0..inner_length).map(|_| read_element(self)).collect()
+            })
+            .collect()
+    }
+}
@@ -12,19 +12,27 @@ use std::{
     fmt::Write as fmt_write,
     fs::File,
     io::{BufRead, BufReader, BufWriter, Write},
+    ops::AddAssign,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
 
 use super::{
-    clique_tree::{CliqueTree, InputParameters},
-    codomain::{read_codomain, generate_write_return},
+    clique_tree::{is_equal_fitness_with_epsilon, CliqueTree, CodomainStats, InputParameters},
+    codomain::{
+        check_codomain_function, check_codomain_size, generate_codomain, generate_codomain_with_options, read_codomain,
+        generate_write_return, write_codomain, GenerationOptions, DEFAULT_MAX_CODOMAIN_ENTRIES,
+    },
+    codomain_subclasses::{list_codomain_functions, CodomainFunction},
     io::{get_clique_tree_from_codomain_file, get_clique_trees_paths_from_codomain_folder, 
+            get_configuration_files, get_configuration_folder_path, get_folders_file_triples,
             get_output_folder_path_from_configuration_file},
-    configuration::{get_rng}
+    configuration::{get_instance_rng, get_rng, get_rng_logged}
 };
 
 use super::configuration::ConfigurationParameters;
+use super::error::ProblemGenError;
 
 #[derive(StructOpt, Debug)]
 #[structopt(
@@ -36,6 +44,20 @@ pub struct ProblemOpt {
     pub problem_command: ProblemCommand,
     #[structopt(short = "s", long = "seed")]
     pub seed: Option<u64>,
+    ///Do not print the parsed options and turn off all log messages
+    #[structopt(short = "q", long = "quiet")]
+    pub quiet: bool,
+    ///Directory to write the output folders to, instead of the parent of the configuration file's folder
+    #[structopt(long = "output-dir", parse(from_os_str))]
+    pub output_dir: Option<PathBuf>,
+    ///Directory with a folder per configuration to read and write the codomain files from and to, instead of the 'codomain_files' folder
+    #[structopt(long = "codomain-dir", parse(from_os_str))]
+    pub codomain_dir: Option<PathBuf>,
+    ///Directory with a folder per configuration to read and write the problem files from and to, instead of the 'problems' folder
+    #[structopt(long = "problem-dir", parse(from_os_str))]
+    pub problem_dir: Option<PathBuf>,
+    #[structopt(flatten)]
+    pub generation_options: GenerationOptions,
 }
 
 #[derive(StructOpt, Debug)]
@@ -91,43 +113,261 @@ pub enum ProblemCommand {
         #[structopt(default_value = "1", short = "n")]
         number_of_problems_to_generate: u32,
     },
+    /// Verify existing problem files, by recalculating their global optima from their cliques and codomain files
+    #[structopt(name = "verify")]
+    Verify {
+        ///Problem file to verify, or folder that contains the 'codomain_files' and 'problems' folders with the problems to verify
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+        ///Codomain file of the problem file, required when verifying a single problem file
+        #[structopt(parse(from_os_str))]
+        codomain_file_path: Option<PathBuf>,
+        ///Whether the codomain was generated by the problem generator / whether the codomain contains the codomain function on the first line
+        #[structopt(short = "g")]
+        generated: bool,
+    },
+    /// Analyze the difficulty of all problems in a folder, and write a CSV summary with a row per problem
+    #[structopt(name = "analyze")]
+    Analyze {
+        ///Folder that contains the 'problem_generation', 'codomain_files' and 'problems' folders with the problems to analyze
+        #[structopt(parse(from_os_str))]
+        folder_path: PathBuf,
+        ///CSV file to write the summary to, defaults to 'analysis.csv' in the folder
+        #[structopt(parse(from_os_str))]
+        output_file_path: Option<PathBuf>,
+        ///Whether the codomain was generated by the problem generator / whether the codomain contains the codomain function on the first line
+        #[structopt(short = "g")]
+        generated: bool,
+        ///Number of random solutions to sample for the fitness-distance correlation
+        #[structopt(long = "samples", default_value = "1000")]
+        samples: usize,
+    },
+    /// List all available codomain functions, with their parameters and a description
+    #[structopt(name = "list-functions")]
+    ListFunctions,
 }
 
-///Run codomain generator from command line options (structopt)
+impl ProblemOpt {
+    ///Create the options for the given command and seed, with all other options set to their defaults.
+    /// The other options can be set on the returned options, before passing them to `run_opt`.
+    pub fn new(problem_command: ProblemCommand, seed: Option<u64>) -> ProblemOpt {
+        ProblemOpt {
+            problem_command,
+            seed,
+            quiet: false,
+            output_dir: None,
+            codomain_dir: None,
+            problem_dir: None,
+            generation_options: GenerationOptions::default(),
+        }
+    }
+
+    ///Create the options to generate problems for the codomain files in the 'codomain_files' folder of every passed folder
+    pub fn codomain_folder(
+        folder_paths: Vec<PathBuf>,
+        generated: bool,
+        seed: Option<u64>,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::CodomainFolder {
+                folder_paths,
+                generated,
+            },
+            seed,
+        )
+    }
+
+    ///Create the options to generate codomains and problems for the configuration files in the 'problem_generation' folder of every passed folder
+    pub fn configuration_folder(
+        folder_paths: Vec<PathBuf>,
+        number_of_problems_to_generate: u32,
+        seed: Option<u64>,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::ConfigurationFolder {
+                folder_paths,
+                number_of_problems_to_generate,
+            },
+            seed,
+        )
+    }
+
+    ///Create the options to generate a problem for the passed codomain file, and write it to the output problem file
+    pub fn codomain_file(
+        input_codomain_file_path: PathBuf,
+        output_problem_file_path: PathBuf,
+        generated: bool,
+        seed: Option<u64>,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::CodomainFile {
+                input_codomain_file_path,
+                output_problem_file_path,
+                generated,
+            },
+            seed,
+        )
+    }
+
+    ///Create the options to generate codomains and problems for the configurations in the passed configuration file,
+    /// and write them to the output codomain and problem folders
+    pub fn configuration_file(
+        input_configuration_file_path: PathBuf,
+        output_codomain_folder_path: PathBuf,
+        output_problem_folder_path: PathBuf,
+        number_of_problems_to_generate: u32,
+        seed: Option<u64>,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::ConfigurationFile {
+                input_configuration_file_path,
+                output_codomain_folder_path,
+                output_problem_folder_path,
+                number_of_problems_to_generate,
+            },
+            seed,
+        )
+    }
+
+    ///Create the options to verify the problem file against the codomain file,
+    /// or, if no codomain file is passed, all problems in the 'problems' folder of the passed folder
+    pub fn verify(
+        path: PathBuf,
+        codomain_file_path: Option<PathBuf>,
+        generated: bool,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::Verify {
+                path,
+                codomain_file_path,
+                generated,
+            },
+            None,
+        )
+    }
+
+    ///Create the options to analyze all problems in the folder with the given number of samples,
+    /// writing the summary to the output file or to 'analysis.csv' in the folder
+    pub fn analyze(
+        folder_path: PathBuf,
+        output_file_path: Option<PathBuf>,
+        generated: bool,
+        samples: usize,
+        seed: Option<u64>,
+    ) -> ProblemOpt {
+        ProblemOpt::new(
+            ProblemCommand::Analyze {
+                folder_path,
+                output_file_path,
+                generated,
+                samples,
+            },
+            seed,
+        )
+    }
+}
+
+///Summary of what a run of the problem generator produced, as returned by run_opt_with_summary
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunSummary {
+    ///Number of problems (or structures) generated and written
+    pub problems_generated: usize,
+    ///Number of files written, including codomain files, graph files and CSV files
+    pub files_written: usize,
+    ///Time taken by the run; the generation functions leave this at zero, it is measured by run_opt_with_summary
+    pub elapsed: Duration,
+}
+
+impl RunSummary {
+    ///Summary of a single generated problem, for which the given number of files were written
+    fn problem(files_written: usize) -> RunSummary {
+        RunSummary {
+            problems_generated: 1,
+            files_written,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl AddAssign for RunSummary {
+    fn add_assign(&mut self, other: RunSummary) {
+        self.problems_generated += other.problems_generated;
+        self.files_written += other.files_written;
+        self.elapsed += other.elapsed;
+    }
+}
+
+///Run problem generator from command line options (structopt)
 pub fn run_opt(problem_opt: ProblemOpt) -> Result<(), Box<dyn Error>> {
-    let mut rng = get_rng(problem_opt.seed);
+    run_opt_with_summary(problem_opt).map(|_summary| ())
+}
+
+///Run problem generator from command line options (structopt),
+/// and return a summary of the number of generated problems and written files, and the time it took
+pub fn run_opt_with_summary(problem_opt: ProblemOpt) -> Result<RunSummary, Box<dyn Error>> {
+    let start = Instant::now();
+    let mut summary = run_problem_command(problem_opt)?;
+    summary.elapsed = start.elapsed();
+    Ok(summary)
+}
+
+///Run the problem command of the options, and return the summary of what it produced
+fn run_problem_command(problem_opt: ProblemOpt) -> Result<RunSummary, Box<dyn Error>> {
+    let (mut rng, _seed) = get_rng_logged(problem_opt.seed);
+    let output_dir = problem_opt.output_dir.as_deref();
+    let codomain_dir = problem_opt.codomain_dir.as_deref();
+    let problem_dir = problem_opt.problem_dir.as_deref();
+    let generation_options = &problem_opt.generation_options;
     match problem_opt.problem_command {
         ProblemCommand::CodomainFolder {
             folder_paths,
             generated,
         } => {
+            generation_options
+                .check_supported_options(&["--write-separators"], "codomain_folder")?;
+            let mut summary = RunSummary::default();
             for folder_path in folder_paths {
-                generate_problems_from_codomain_folder(&folder_path, generated, &mut rng)?;
+                summary += generate_problems_from_codomain_folder(
+                    &folder_path,
+                    generated,
+                    output_dir,
+                    codomain_dir,
+                    problem_dir,
+                    generation_options.write_separators,
+                    &mut rng,
+                )?;
             }
-            Ok(())
+            Ok(summary)
         }
         ProblemCommand::ConfigurationFolder {
             folder_paths,
             number_of_problems_to_generate,
         } => {
+            let mut summary = RunSummary::default();
             for folder_path in folder_paths {
-                generate_codomain_and_problem_from_folder(
+                summary += generate_codomain_and_problem_from_folder(
                     &folder_path,
                     number_of_problems_to_generate,
+                    output_dir,
+                    codomain_dir,
+                    problem_dir,
+                    generation_options,
                     &mut rng,
                 )?;
             }
-            Ok(())
+            Ok(summary)
         }
         ProblemCommand::CodomainFile {
             input_codomain_file_path,
             output_problem_file_path,
             generated,
         } => {
+            generation_options.check_supported_options(&["--write-separators"], "codomain_file")?;
             generate_problem_from_codomain_file(
                 &input_codomain_file_path,
                 &output_problem_file_path,
                 generated,
+                generation_options.write_separators,
                 &mut rng
             )
         },
@@ -141,10 +381,55 @@ pub fn run_opt(problem_opt: ProblemOpt) -> Result<(), Box<dyn Error>> {
                 &input_configuration_file_path,
                 Some(&output_codomain_folder_path),
                 Some(&output_problem_folder_path),
+                None,
                 number_of_problems_to_generate,
+                generation_options,
                 &mut rng
             )
         }
+        ProblemCommand::Verify {
+            path,
+            codomain_file_path,
+            generated,
+        } => {
+            generation_options.check_supported_options(&[], "verify")?;
+            let verification_results = match codomain_file_path {
+                Some(codomain_file_path) => vec![(
+                    path.clone(),
+                    verify_problem_file(&path, &codomain_file_path, generated)?,
+                )],
+                None => verify_problems_from_folder(&path, codomain_dir, problem_dir, generated)?,
+            };
+            report_verification_results(&verification_results)?;
+            Ok(RunSummary::default())
+        }
+        ProblemCommand::Analyze {
+            folder_path,
+            output_file_path,
+            generated,
+            samples,
+        } => {
+            generation_options.check_supported_options(&[], "analyze")?;
+            let analyses = analyze_problems_from_folder(
+                &folder_path,
+                codomain_dir,
+                problem_dir,
+                generated,
+                samples,
+                &mut rng,
+            )?;
+            let output_file_path =
+                output_file_path.unwrap_or_else(|| folder_path.join("analysis.csv"));
+            write_analyses_to_csv(&analyses, &output_file_path)?;
+            Ok(RunSummary {
+                files_written: 1,
+                ..Default::default()
+            })
+        }
+        ProblemCommand::ListFunctions => {
+            print!("{}", list_codomain_functions());
+            Ok(RunSummary::default())
+        }
     }
 }
 
@@ -170,16 +455,25 @@ impl Problem {
 }
 
 ///Generate problems from the codomain and input parameters,
-/// which are both given by the files in the parent's codomain folder and write them to the parent's problems folder
+/// which are both given by the files in the parent's codomain folder and write them to the parent's problems folder.
+/// If an output directory is passed, the problems folder is created in it instead.
+/// If a codomain or problem directory is passed, it is used instead of the codomain_files or problems folder.
+/// Returns the summary of the generated problems.
 pub fn generate_problems_from_codomain_folder(
     parent_folder_path: &Path,
     generated: bool,
+    output_dir: Option<&Path>,
+    codomain_dir: Option<&Path>,
+    problem_dir: Option<&Path>,
+    write_separators: bool,
     rng: &mut ChaChaRng
-) -> Result<(), Box<dyn Error>> {
-    let mut codomain_folder_path = PathBuf::from(parent_folder_path);
-    codomain_folder_path.push("codomain_files");
-    let mut problems_folder_path = PathBuf::from(parent_folder_path);
-    problems_folder_path.push("problems");
+) -> Result<RunSummary, Box<dyn Error>> {
+    let codomain_folder_path = codomain_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| parent_folder_path.join("codomain_files"));
+    let problems_folder_path = problem_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| output_dir.unwrap_or(parent_folder_path).join("problems"));
 
     //get all folder entries in the codomain_files folder
     let folder_entries: Vec<PathBuf> = codomain_folder_path
@@ -189,6 +483,7 @@ pub fn generate_problems_from_codomain_folder(
         .collect();
 
     //For each folder f,
+    let mut summary = RunSummary::default();
     for folder in folder_entries {
         // Create a directory in the problems folder with the same name (f)
         let mut output_folder_path = problems_folder_path.clone();
@@ -209,49 +504,73 @@ pub fn generate_problems_from_codomain_folder(
                     .ok_or("could not get filename of codomain file")?,
             );
             //write the output problems to disk
-            write_problem_to_file(&clique_tree, &output_path)?;
+            write_problem(&clique_tree, &output_path, write_separators)?;
+            summary += RunSummary::problem(1);
         }
     }
-    Ok(())
+    Ok(summary)
 }
 
-///Generate a problem from the codomain and input parameters given by codomain_file_path and write it to output_path
+///Generate a problem from the codomain and input parameters given by codomain_file_path and write it to output_path.
+/// If an output directory is passed, the codomain_files and problems folders are created in it.
+/// If a codomain or problem directory is passed, it is used instead of the codomain_files or problems folder.
+/// Returns the summary of the generated problems.
 pub fn generate_codomain_and_problem_from_folder(
     input_folder_path: &Path,
     number_of_problems_to_generate: u32,
+    output_dir: Option<&Path>,
+    codomain_dir: Option<&Path>,
+    problem_dir: Option<&Path>,
+    generation_options: &GenerationOptions,
     rng: &mut ChaChaRng
-) -> Result<(), Box<dyn Error>> {
+) -> Result<RunSummary, Box<dyn Error>> {
     //Use the input_folder_path to get the problem_generation folder and problems folder paths
     let mut problem_generation_path = PathBuf::from(input_folder_path);
     problem_generation_path.push("problem_generation");
 
     //For each file in the problem_generation folder,
-    let file_entries: Vec<PathBuf> = problem_generation_path
-        .read_dir()?
-        .map(|file| file.unwrap().path())
-        .sorted()
-        .collect();
+    let file_entries = get_configuration_files(&problem_generation_path)?;
 
     // generate all codomain and problem files and write them to the codomain_files and problems folders
+    let mut summary = RunSummary::default();
     for file in file_entries {
-        generate_codomain_and_problem(&file, None, None, number_of_problems_to_generate, rng)?;
+        let output_codomain_folder_path = codomain_dir
+            .map(|codomain_dir| get_configuration_folder_path(&file, codomain_dir))
+            .transpose()?;
+        let output_problem_folder_path = problem_dir
+            .map(|problem_dir| get_configuration_folder_path(&file, problem_dir))
+            .transpose()?;
+        summary += generate_codomain_and_problem(
+            &file,
+            output_codomain_folder_path.as_deref(),
+            output_problem_folder_path.as_deref(),
+            output_dir,
+            number_of_problems_to_generate,
+            generation_options,
+            rng,
+        )?;
     }
-    Ok(())
+    Ok(summary)
 }
 
 ///Generate codomain and problem files for the input configuration as read from the input_configuration_file.
 /// If the output_(codomain/problem)_folder_path is None, we default to folder paths used in other parts of the program (codomain_files & problems).
 /// If they are Some(path), we use the path as the destination folder.
+/// When defaulting, the folders are created in output_dir if it is passed.
+/// Returns the summary of the generated problems.
 pub fn generate_codomain_and_problem(
     input_configuration_file_path: &Path,
     output_codomain_folder_path: Option<&Path>,
     output_problem_folder_path: Option<&Path>,
+    output_dir: Option<&Path>,
     number_of_problems_to_generate: u32,
+    generation_options: &GenerationOptions,
     rng: &mut ChaChaRng
-) -> Result<(), Box<dyn Error>> {
+) -> Result<RunSummary, Box<dyn Error>> {
     //Get the configuration parameters from the input configuration file
     let configuration_parameters =
         ConfigurationParameters::from_file(input_configuration_file_path)?;
+    configuration_parameters.check_topology(generation_options.topology)?;
 
     let codomain_function = configuration_parameters.codomain_function.clone();
 
@@ -261,55 +580,241 @@ pub fn generate_codomain_and_problem(
         None => get_output_folder_path_from_configuration_file(
             input_configuration_file_path,
             "problems",
+            output_dir,
         )?,
     };
 
+    //In structure-only mode, only the structure files are written to the problem folder, so no codomain folder is needed
+    if generation_options.structure_only {
+        generation_options
+            .check_supported_options(&["--structure-only", "--topology"], "--structure-only")?;
+        return generate_structures(
+            configuration_parameters,
+            &output_problem_folder_path_buf,
+            number_of_problems_to_generate,
+            generation_options,
+            rng,
+        );
+    }
+
     //if a output_codomain_folder_path is passed, we use it, otherwise we default to our way of calculating where the file should go (into codomain_files)
     let output_codomain_folder_path_buf = match output_codomain_folder_path {
         Some(folder) => PathBuf::from(folder),
         None => get_output_folder_path_from_configuration_file(
             input_configuration_file_path,
             "codomain_files",
+            output_dir,
         )?,
     };
 
+    //The interaction graphs are written to the graphs folder, next to the problems folder
+    let output_graph_folder_path_buf = match generation_options.emit_graph {
+        Some(_) => Some(get_output_folder_path_from_configuration_file(
+            input_configuration_file_path,
+            "graphs",
+            output_dir,
+        )?),
+        None => None,
+    };
+
     //Loop over all input parameters (using custom iterator)
+    let mut summary = RunSummary::default();
     for input_parameters in configuration_parameters {
+        let input_parameters = input_parameters.with_topology(generation_options.topology);
+        input_parameters.validate()?;
+        check_codomain_size(&input_parameters, generation_options.max_codomain_entries())?;
+        check_codomain_function(&codomain_function, &input_parameters)?;
         //Generate number_problems different problem instances for each input parameter configuration
         for num in 0..number_of_problems_to_generate {
             let mut output_problem_file_path = output_problem_folder_path_buf.clone();
             let mut output_codomain_file_path = output_codomain_folder_path_buf.clone();
 
-            let output_file_name = format!(
-                "{}_{}_{}_{}_{}_{}.txt",
-                codomain_function.to_io_string(),
-                input_parameters.m,
-                input_parameters.k,
-                input_parameters.o,
-                input_parameters.b,
-                num
-            );
+            let output_file_name = get_output_file_name(&codomain_function, &input_parameters, num);
 
             output_problem_file_path.push(output_file_name.clone());
             output_codomain_file_path.push(output_file_name);
             //println!("constructed output file path: {:?}", output_file_path);
 
-            let codomain =
-                generate_write_return(&input_parameters, &codomain_function, &output_codomain_file_path, rng)?;
+            let mut seeded_rng = generation_options
+                .seed_per_instance
+                .then(|| get_instance_rng(rng, &input_parameters, num));
+            let instance_rng = seeded_rng.as_mut().unwrap_or(&mut *rng);
+
+            //Instances that are not selected are not written, but still advance the rng the same way as when they are
+            if !generation_options.is_instance_selected(num) {
+                if !generation_options.seed_per_instance {
+                    generate_codomain_with_options(
+                        &input_parameters,
+                        &codomain_function,
+                        generation_options,
+                        instance_rng,
+                    );
+                    CliqueTree::construct(&input_parameters, instance_rng);
+                }
+                continue;
+            }
+
+            //With a unique optimum, the codomain is only written after it has been perturbed
+            let codomain = if generation_options.unique_optimum {
+                generate_codomain_with_options(
+                    &input_parameters,
+                    &codomain_function,
+                    generation_options,
+                    instance_rng,
+                )
+            } else {
+                generate_write_return(
+                    &input_parameters,
+                    &codomain_function,
+                    generation_options,
+                    &output_codomain_file_path,
+                    instance_rng,
+                )?
+            };
 
             //Generate a clique tree using the input parameter, the codomain function, and the codomain values
-            let clique_tree = CliqueTree::new(
+            let mut clique_tree = CliqueTree::new_with_fitness_epsilon(
                 input_parameters.clone(),
                 codomain_function.clone(),
                 codomain,
-                rng
+                generation_options.fitness_epsilon(),
+                instance_rng
             );
 
+            if generation_options.unique_optimum {
+                if !clique_tree.make_global_optimum_unique() {
+                    return Err(format!(
+                        "could not make the global optimum of {:?} unique",
+                        output_problem_file_path
+                    )
+                    .into());
+                }
+                write_codomain(
+                    &input_parameters,
+                    &codomain_function,
+                    &output_codomain_file_path,
+                    &clique_tree.codomain_values,
+                    generation_options.codomain_checksum,
+                    generation_options.flush_every(),
+                )?;
+            }
+
             //Write the problem to disk
-            write_problem_to_file(&clique_tree, &output_problem_file_path)?;
+            write_problem(
+                &clique_tree,
+                &output_problem_file_path,
+                generation_options.write_separators,
+            )?;
+
+            if let (Some(graph_format), Some(output_graph_folder_path)) =
+                (generation_options.emit_graph, &output_graph_folder_path_buf)
+            {
+                let mut output_graph_file_path = output_graph_folder_path.join(
+                    output_problem_file_path
+                        .file_name()
+                        .ok_or("could not get filename of problem file")?,
+                );
+                output_graph_file_path.set_extension(graph_format.extension());
+                clique_tree.write_interaction_graph(&output_graph_file_path, graph_format)?;
+            }
+            //The codomain and problem files, and the graph file if emitted
+            let files_written = if output_graph_folder_path_buf.is_some() { 3 } else { 2 };
+            summary += RunSummary::problem(files_written);
         }
     }
-    Ok(())
+    Ok(summary)
+}
+
+///Generate only the structure (cliques) of the problems for all input parameters of the configuration, and write them to the problem folder.
+/// This skips writing the codomain and the calculation of the global optima. The codomain is still generated in memory,
+///  so that the structure is drawn from the same random number stream as the structure of the full problem.
+fn generate_structures(
+    configuration_parameters: ConfigurationParameters,
+    output_problem_folder_path: &Path,
+    number_of_problems_to_generate: u32,
+    generation_options: &GenerationOptions,
+    rng: &mut ChaChaRng,
+) -> Result<RunSummary, Box<dyn Error>> {
+    let codomain_function = configuration_parameters.codomain_function.clone();
+
+    let mut summary = RunSummary::default();
+    for input_parameters in configuration_parameters {
+        let input_parameters = input_parameters.with_topology(generation_options.topology);
+        input_parameters.validate()?;
+        check_codomain_size(&input_parameters, generation_options.max_codomain_entries())?;
+        check_codomain_function(&codomain_function, &input_parameters)?;
+        for num in 0..number_of_problems_to_generate {
+            let output_file_name = get_output_file_name(&codomain_function, &input_parameters, num);
+            let output_structure_file_path = output_problem_folder_path.join(output_file_name);
+
+            let mut seeded_rng = generation_options
+                .seed_per_instance
+                .then(|| get_instance_rng(rng, &input_parameters, num));
+            let instance_rng = seeded_rng.as_mut().unwrap_or(&mut *rng);
+
+            //The full problem generates the codomain before the structure, so do the same to get the same structure
+            generate_codomain_with_options(
+                &input_parameters,
+                &codomain_function,
+                generation_options,
+                instance_rng,
+            );
+            let (cliques, _separators) = CliqueTree::construct(&input_parameters, instance_rng);
+            if !generation_options.is_instance_selected(num) {
+                continue;
+            }
+            write_structure_to_file(&input_parameters, &cliques, &output_structure_file_path)?;
+            summary += RunSummary::problem(1);
+        }
+    }
+    Ok(summary)
+}
+
+///Get the name of the codomain and problem files for a problem instance, from the codomain function, the input parameters and the instance number
+fn get_output_file_name(
+    codomain_function: &CodomainFunction,
+    input_parameters: &InputParameters,
+    num: u32,
+) -> String {
+    format!(
+        "{}_{}_{}_{}_{}_{}.txt",
+        codomain_function.to_io_string(),
+        input_parameters.m,
+        input_parameters.k,
+        input_parameters.o,
+        input_parameters.b,
+        num
+    )
+}
+
+///Generate the clique trees for all input parameters of the configuration in memory, without writing any files.
+/// For each input parameter configuration, the given number of instances is generated, in the same order as the folder workflow.
+/// Returns an error if any input parameter configuration is invalid, see InputParameters::validate, check_codomain_size
+///  (with DEFAULT_MAX_CODOMAIN_ENTRIES), and check_codomain_function.
+pub fn generate_configuration(
+    configuration_parameters: &ConfigurationParameters,
+    instances: u32,
+    seed: Option<u64>,
+) -> Result<Vec<CliqueTree>, ProblemGenError> {
+    let mut rng = get_rng(seed);
+    let codomain_function = &configuration_parameters.codomain_function;
+
+    let mut clique_trees = Vec::new();
+    for input_parameters in configuration_parameters.clone() {
+        input_parameters.validate()?;
+        check_codomain_size(&input_parameters, DEFAULT_MAX_CODOMAIN_ENTRIES)?;
+        check_codomain_function(codomain_function, &input_parameters)?;
+        for _num in 0..instances {
+            let codomain = generate_codomain(&input_parameters, codomain_function, &mut rng);
+            clique_trees.push(CliqueTree::new(
+                input_parameters.clone(),
+                codomain_function.clone(),
+                codomain,
+                &mut rng,
+            ));
+        }
+    }
+    Ok(clique_trees)
 }
 
 ///Generate a problem from the codomain and input parameters given by codomain_file_path and write it to the problem at the output_problem path
@@ -317,12 +822,14 @@ pub fn generate_problem_from_codomain_file(
     codomain_file_path: &Path,
     output_problem_file_path: &Path,
     generated: bool,
+    write_separators: bool,
     rng: &mut ChaChaRng
-) -> Result<(), Box<dyn Error>> {
+) -> Result<RunSummary, Box<dyn Error>> {
     //Get the clique tree from the codomain file
     let clique_tree = get_clique_tree_from_codomain_file(codomain_file_path, generated, rng)?;
     //Write the problem to file
-    write_problem_to_file(&clique_tree, output_problem_file_path)
+    write_problem(&clique_tree, output_problem_file_path, write_separators)?;
+    Ok(RunSummary::problem(1))
 }
 
 ///Read the clique tree from the problem and codomain values, from the problem file and codomain file
@@ -330,7 +837,7 @@ pub fn read_clique_tree_from_files(
     problem_path: &Path,
     codomain_path: &Path,
     generated: bool,
-) -> Result<CliqueTree, Box<dyn Error>> {
+) -> Result<CliqueTree, ProblemGenError> {
     let problem = read_problem_from_file(problem_path)?;
     let skip_lines = if generated { 2 } else { 1 };
     let codomain = read_codomain(&problem.input_parameters, codomain_path, skip_lines)?;
@@ -339,6 +846,235 @@ pub fn read_clique_tree_from_files(
     ))
 }
 
+///Verify the problem file against the codomain file: the clique tree is reconstructed from the cliques in the problem file and the codomain values,
+/// after which its global optima are recalculated and compared to the global optimum score and strings stored in the problem file.
+/// Returns a description of every mismatch, so the problem file is correct if the returned list is empty.
+pub fn verify_problem_file(
+    problem_path: &Path,
+    codomain_path: &Path,
+    generated: bool,
+) -> Result<Vec<String>, ProblemGenError> {
+    let mut problem = read_problem_from_file(problem_path)?;
+    let stored_score = problem.glob_optima_score;
+    //Without stored global optima, the clique tree recalculates them
+    let stored_strings = std::mem::take(&mut problem.glob_optima_strings);
+
+    let skip_lines = if generated { 2 } else { 1 };
+    let codomain = read_codomain(&problem.input_parameters, codomain_path, skip_lines)?;
+    let clique_tree = CliqueTree::construct_from_problem_codomain(problem, codomain);
+
+    let mut mismatches = Vec::new();
+    if !is_equal_fitness_with_epsilon(
+        stored_score,
+        clique_tree.glob_optima_score,
+        clique_tree.fitness_epsilon,
+    ) {
+        mismatches.push(format!(
+            "global optimum score is {}, but recalculated {}",
+            stored_score, clique_tree.glob_optima_score
+        ));
+    }
+    if stored_strings.len() != clique_tree.glob_optima_strings.len() {
+        mismatches.push(format!(
+            "number of global optima is {}, but recalculated {}",
+            stored_strings.len(),
+            clique_tree.glob_optima_strings.len()
+        ));
+    }
+    for stored_string in &stored_strings {
+        if !clique_tree.glob_optima_strings.contains(stored_string) {
+            mismatches.push(format!(
+                "{} is not a global optimum",
+                stored_string.iter().join("")
+            ));
+        }
+    }
+    Ok(mismatches)
+}
+
+///Path of a verified problem file, together with the mismatches found when verifying it
+pub type VerificationResult = (PathBuf, Vec<String>);
+
+///Verify all problems in the problems folder of the parent folder against the codomain files in its codomain_files folder,
+/// which both contain a folder per configuration with files of the same name.
+/// If a codomain or problem directory is passed, it is used instead of the codomain_files or problems folder.
+/// Returns the path of every problem file together with its mismatches, as returned by verify_problem_file.
+pub fn verify_problems_from_folder(
+    parent_folder_path: &Path,
+    codomain_dir: Option<&Path>,
+    problem_dir: Option<&Path>,
+    generated: bool,
+) -> Result<Vec<VerificationResult>, Box<dyn Error>> {
+    let codomain_folder_path = codomain_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| parent_folder_path.join("codomain_files"));
+    let problems_folder_path = problem_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| parent_folder_path.join("problems"));
+
+    let configuration_folders: Vec<PathBuf> = problems_folder_path
+        .read_dir()?
+        .map(|folder| folder.map(|folder| folder.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?
+        .into_iter()
+        .sorted()
+        .collect();
+
+    let mut verification_results = Vec::new();
+    for configuration_folder in configuration_folders {
+        let configuration_name = configuration_folder
+            .file_name()
+            .ok_or("could not get file name of folder")?;
+        let problem_files: Vec<PathBuf> = configuration_folder
+            .read_dir()?
+            .map(|file| file.map(|file| file.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()?
+            .into_iter()
+            .sorted()
+            .collect();
+
+        for problem_file in problem_files {
+            let mut codomain_file = codomain_folder_path.join(configuration_name);
+            codomain_file.push(
+                problem_file
+                    .file_name()
+                    .ok_or("could not get filename of problem file")?,
+            );
+            let mismatches = verify_problem_file(&problem_file, &codomain_file, generated)?;
+            verification_results.push((problem_file, mismatches));
+        }
+    }
+    Ok(verification_results)
+}
+
+///Print whether every verified problem file passed, with the mismatches of the files that failed.
+/// Returns an error if any problem file failed the verification.
+fn report_verification_results(
+    verification_results: &[VerificationResult],
+) -> Result<(), Box<dyn Error>> {
+    let mut number_failed = 0;
+    for (problem_path, mismatches) in verification_results {
+        if mismatches.is_empty() {
+            println!("PASS {}", problem_path.display());
+        } else {
+            number_failed += 1;
+            println!("FAIL {}: {}", problem_path.display(), mismatches.join("; "));
+        }
+    }
+
+    if number_failed > 0 {
+        return Err(format!(
+            "{} of {} problem files failed the verification",
+            number_failed,
+            verification_results.len()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+///Difficulty metrics of a single problem, as calculated by analyze_problems_from_folder
+#[derive(Debug, Clone)]
+pub struct ProblemAnalysis {
+    pub problem_path: PathBuf,
+    pub input_parameters: InputParameters,
+    pub glob_optima_score: f64,
+    pub number_of_global_optima: usize,
+    pub expected_random_fitness: f64,
+    ///Sampled fitness-distance correlation, NaN if all samples have the same fitness or distance
+    pub fitness_distance_correlation: f64,
+    pub codomain_statistics: CodomainStats,
+}
+
+impl ProblemAnalysis {
+    ///Calculate the difficulty metrics of the clique tree, sampling the given number of random solutions for the fitness-distance correlation
+    pub fn new(
+        clique_tree: &CliqueTree,
+        problem_path: PathBuf,
+        samples: usize,
+        rng: &mut ChaChaRng,
+    ) -> ProblemAnalysis {
+        ProblemAnalysis {
+            problem_path,
+            input_parameters: clique_tree.input_parameters.clone(),
+            glob_optima_score: clique_tree.glob_optima_score,
+            number_of_global_optima: clique_tree.glob_optima_strings.len(),
+            expected_random_fitness: clique_tree.expected_random_fitness(),
+            fitness_distance_correlation: clique_tree.fitness_distance_correlation(samples, rng),
+            codomain_statistics: clique_tree.codomain_statistics(),
+        }
+    }
+}
+
+///Analyze all problems in the folder, which contains the 'problem_generation', 'codomain_files' and 'problems' folders.
+/// Every problem is read from its problem file and the codomain file with the same name, sorted by configuration and file name.
+/// If a codomain or problem directory is passed, it is used instead of the 'codomain_files' or 'problems' folder.
+pub fn analyze_problems_from_folder(
+    folder_path: &Path,
+    codomain_dir: Option<&Path>,
+    problem_dir: Option<&Path>,
+    generated: bool,
+    samples: usize,
+    rng: &mut ChaChaRng,
+) -> Result<Vec<ProblemAnalysis>, Box<dyn Error>> {
+    let mut analyses = Vec::new();
+    for (_configuration_file, codomain_folder, problem_folder) in
+        get_folders_file_triples(folder_path, codomain_dir, problem_dir, false)?
+    {
+        for (clique_tree, codomain_file_path) in
+            read_clique_trees_paths_from_folders(&codomain_folder, &problem_folder, generated)?
+        {
+            let problem_path = problem_folder.join(
+                codomain_file_path
+                    .file_name()
+                    .ok_or("could not get filename of codomain file")?,
+            );
+            analyses.push(ProblemAnalysis::new(&clique_tree, problem_path, samples, rng));
+        }
+    }
+    Ok(analyses)
+}
+
+///Write the problem analyses to a CSV file, with a header line and a row per problem
+pub fn write_analyses_to_csv(
+    analyses: &[ProblemAnalysis],
+    output_file_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let file = File::create(output_file_path)?;
+    let mut buf_writer = BufWriter::new(file);
+
+    writeln!(
+        buf_writer,
+        "problem,m,k,o,b,glob_optima_score,number_of_global_optima,expected_random_fitness,\
+         fitness_distance_correlation,codomain_min,codomain_max,codomain_mean,codomain_std_dev,codomain_distinct_values"
+    )?;
+    for analysis in analyses {
+        let input_parameters = &analysis.input_parameters;
+        let codomain_statistics = &analysis.codomain_statistics;
+        writeln!(
+            buf_writer,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            analysis.problem_path.display(),
+            input_parameters.m,
+            input_parameters.k,
+            input_parameters.o,
+            input_parameters.b,
+            analysis.glob_optima_score,
+            analysis.number_of_global_optima,
+            analysis.expected_random_fitness,
+            analysis.fitness_distance_correlation,
+            codomain_statistics.min,
+            codomain_statistics.max,
+            codomain_statistics.mean,
+            codomain_statistics.std_dev,
+            codomain_statistics.distinct_values
+        )?;
+    }
+
+    buf_writer.flush()?;
+    Ok(())
+}
+
 ///Read the TD Mk Landscapes / clique trees from the codomain and problem folders.
 /// We return a Vector of tuples that contain both the clique tree and the path to the codomain file.
 ///  The path is required to construct the output file path.
@@ -367,7 +1103,7 @@ pub fn read_clique_trees_paths_from_folders(
     //zip the (sorted) codomains and problems, and read the clique tree from the codomain and problem files.
     for (codomain_file_entry, problem_file_entry) in codomain_file_entries
         .into_iter()
-        .zip(problem_file_entries.into_iter())
+        .zip(problem_file_entries)
     {
         //Construct tuple from the read clique tree and the codomain file path
         result_vec.push((
@@ -384,19 +1120,30 @@ pub fn write_problem_to_file(
     clique_tree: &CliqueTree,
     output_problem_file_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
+    write_problem(clique_tree, output_problem_file_path, false)
+}
+
+/// Write problem to file like write_problem_to_file, followed by the separator variable indices of every clique
+pub fn write_problem_to_file_with_separators(
+    clique_tree: &CliqueTree,
+    output_problem_file_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    write_problem(clique_tree, output_problem_file_path, true)
+}
+
+/// Write problem to file, and if write_separators is set, the separator variable indices of every clique after the cliques
+fn write_problem(
+    clique_tree: &CliqueTree,
+    output_problem_file_path: &Path,
+    write_separators: bool,
+) -> Result<(), Box<dyn Error>> {
+    clique_tree.input_parameters.check_parameters_line()?;
     let file = File::create(output_problem_file_path)?;
     let mut buf_writer = BufWriter::new(file);
     let mut write_buffer = String::new();
 
     //Write the input parameters on the first line
-    writeln!(
-        write_buffer,
-        "{} {} {} {}",
-        clique_tree.input_parameters.m,
-        clique_tree.input_parameters.k,
-        clique_tree.input_parameters.o,
-        clique_tree.input_parameters.b
-    )?;
+    write_input_parameters(&mut write_buffer, &clique_tree.input_parameters)?;
     buf_writer.write_all(write_buffer.as_bytes())?;
     write_buffer.clear();
 
@@ -427,7 +1174,62 @@ pub fn write_problem_to_file(
 
     //Cliques/Subfunctions
     //      Per clique; variable indices
-    for clique in &clique_tree.cliques {
+    write_cliques(&mut write_buffer, &clique_tree.cliques)?;
+    buf_writer.write_all(write_buffer.as_bytes())?;
+    write_buffer.clear();
+
+    //Separators (optional)
+    //      Per clique; separator variable indices, an empty line for the root
+    if write_separators {
+        for separator in &clique_tree.separators {
+            writeln!(write_buffer, "{}", separator.iter().join(" "))?;
+        }
+        buf_writer.write_all(write_buffer.as_bytes())?;
+        write_buffer.clear();
+    }
+
+    buf_writer.flush()?;
+
+    Ok(())
+}
+
+///Write only the structure of a problem to file: the input parameters on the first line, followed by the variable indices of every clique.
+/// These are the same lines as in the problem file, without the global optima.
+pub fn write_structure_to_file(
+    input_parameters: &InputParameters,
+    cliques: &[Vec<u32>],
+    output_structure_file_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    input_parameters.check_parameters_line()?;
+    let file = File::create(output_structure_file_path)?;
+    let mut buf_writer = BufWriter::new(file);
+    let mut write_buffer = String::new();
+
+    write_input_parameters(&mut write_buffer, input_parameters)?;
+    write_cliques(&mut write_buffer, cliques)?;
+    buf_writer.write_all(write_buffer.as_bytes())?;
+
+    buf_writer.flush()?;
+
+    Ok(())
+}
+
+///Write the input parameters on a line to the write buffer
+fn write_input_parameters(
+    write_buffer: &mut String,
+    input_parameters: &InputParameters,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        write_buffer,
+        "{} {} {} {}",
+        input_parameters.m, input_parameters.k, input_parameters.o, input_parameters.b
+    )?;
+    Ok(())
+}
+
+///Write the variable indices of every clique on a line to the write buffer
+fn write_cliques(write_buffer: &mut String, cliques: &[Vec<u32>]) -> Result<(), Box<dyn Error>> {
+    for clique in cliques {
         for variable_index in clique {
             write!(write_buffer, "{} ", variable_index)?;
         }
@@ -436,11 +1238,6 @@ pub fn write_problem_to_file(
         )?;
         writeln!(write_buffer)?;
     }
-    buf_writer.write_all(write_buffer.as_bytes())?;
-    write_buffer.clear();
-
-    buf_writer.flush()?;
-
     Ok(())
 }
 
@@ -470,16 +1267,20 @@ pub fn write_problem_to_file_ser(
 }
 
 ///Read problem from file
-pub fn read_problem_from_file(file_path: &Path) -> Result<Problem, Box<dyn Error>> {
+pub fn read_problem_from_file(file_path: &Path) -> Result<Problem, ProblemGenError> {
     let file = File::open(file_path)?;
     let reader = BufReader::new(file);
     let mut content_iter = reader.lines();
 
     //Read input parameters
-    let mut line = content_iter.next().ok_or("Empty problem file")??;
+    let mut line = content_iter
+        .next()
+        .ok_or_else(|| ProblemGenError::Parse("Empty problem file".to_owned()))??;
     let parameters: Vec<&str> = line.split(' ').collect();
     if parameters.len() != 4 {
-        return Err("not enough input parameters on first line of input file".into());
+        return Err(ProblemGenError::InvalidParameters(
+            "not enough input parameters on first line of input file".to_owned(),
+        ));
     }
     //And set the parameters
     let m: u32 = parameters[0].parse()?;
@@ -492,33 +1293,36 @@ pub fn read_problem_from_file(file_path: &Path) -> Result<Problem, Box<dyn Error
     let problem_size = (m - 1) * (k - o) + k;
 
     //Read global optmium score
-    line = content_iter
-        .next()
-        .ok_or("No global optimum score in problem file")??;
+    line = content_iter.next().ok_or_else(|| {
+        ProblemGenError::Parse("No global optimum score in problem file".to_owned())
+    })??;
     let glob_optima_score: f64 = line.parse()?;
 
     //Read number_of_global_optima
-    line = content_iter
-        .next()
-        .ok_or("No number_of_global_optima line in problem file")??;
+    line = content_iter.next().ok_or_else(|| {
+        ProblemGenError::Parse("No number_of_global_optima line in problem file".to_owned())
+    })??;
     let number_of_global_optima: usize = line.parse()?;
 
     //Read global optima
     let mut glob_optima_strings = Vec::with_capacity(number_of_global_optima);
     for _i in 0..number_of_global_optima {
-        line = content_iter
-            .next()
-            .ok_or("Not enough global optima strings in problem file")??;
+        line = content_iter.next().ok_or_else(|| {
+            ProblemGenError::Parse("Not enough global optima strings in problem file".to_owned())
+        })??;
         let mut chars = line.chars();
         let mut global_optimum: Vec<u32> = Vec::with_capacity(problem_size as usize);
         for _j in 0..problem_size as usize {
-            let bit = chars
-                .next()
-                .ok_or("global optimum in problem file does not contain enough bits")?;
-            global_optimum.push(
-                bit.to_digit(10)
-                    .ok_or("Could not convert global optimum bit from char to u32")?,
-            );
+            let bit = chars.next().ok_or_else(|| {
+                ProblemGenError::Parse(
+                    "global optimum in problem file does not contain enough bits".to_owned(),
+                )
+            })?;
+            global_optimum.push(bit.to_digit(10).ok_or_else(|| {
+                ProblemGenError::Parse(
+                    "Could not convert global optimum bit from char to u32".to_owned(),
+                )
+            })?);
         }
         glob_optima_strings.push(global_optimum);
     }
@@ -526,16 +1330,18 @@ pub fn read_problem_from_file(file_path: &Path) -> Result<Problem, Box<dyn Error
     //Read clique_tree cliques
     let mut cliques = Vec::with_capacity(m as usize);
     for _i in 0..m as usize {
-        line = content_iter
-            .next()
-            .ok_or("Not enough cliques in problem file")??;
+        line = content_iter.next().ok_or_else(|| {
+            ProblemGenError::Parse("Not enough cliques in problem file".to_owned())
+        })??;
         let variable_indices: Vec<&str> = line.split(' ').collect();
         if variable_indices.len() != k as usize {
-            return Err("not enough variable indices in clique indices".into());
+            return Err(ProblemGenError::Parse(
+                "not enough variable indices in clique indices".to_owned(),
+            ));
         }
         let mut clique_indices: Vec<u32> = Vec::with_capacity(k as usize);
-        for j in 0..k as usize {
-            clique_indices.push(variable_indices[j].parse()?);
+        for variable_index in variable_indices {
+            clique_indices.push(variable_index.parse()?);
         }
         cliques.push(clique_indices);
     }
@@ -551,9 +1357,10 @@ pub fn read_problem_from_file(file_path: &Path) -> Result<Problem, Box<dyn Error
 }
 
 ///Read problem from file using deserialization
-pub fn read_problem_from_file_de(file_path: &Path) -> Result<Problem, Box<dyn Error>> {
+pub fn read_problem_from_file_de(file_path: &Path) -> Result<Problem, ProblemGenError> {
     let f = File::open(file_path)?;
     let mut reader = BufReader::new(f);
-    let problem = ron::de::from_reader(&mut reader)?;
+    let problem = ron::de::from_reader(&mut reader)
+        .map_err(|error| ProblemGenError::Parse(error.to_string()))?;
     Ok(problem)
 }
@@ -0,0 +1,151 @@
+/*!
+Module for the WASM interface, to construct and evaluate clique trees from JavaScript, e.g. for EA demos in the browser.
+As wasm_bindgen can't pass pointers to clique trees, the constructed clique trees are stored in a thread-local registry
+ and referred to by their handle, the index into that registry. Free a clique tree that is no longer needed with its handle,
+ after which the handle may be reused for a new clique tree.
+*/
+
+use wasm_bindgen::prelude::*;
+
+use std::cell::RefCell;
+
+use super::clique_tree::{CliqueTree, InputParameters};
+use super::codomain::{check_codomain_function, check_codomain_size, DEFAULT_MAX_CODOMAIN_ENTRIES};
+use super::codomain_subclasses::CodomainFunction;
+use super::error::ProblemGenError;
+
+thread_local! {
+    ///The clique trees constructed in this thread, indexed by their handle, None if the clique tree was freed
+    static CLIQUE_TREES: RefCell<Vec<Option<CliqueTree>>> = const { RefCell::new(Vec::new()) };
+}
+
+///Construct a clique tree with the given input parameters and codomain function (e.g. "nk-q 4"), seeded with the seed,
+/// and store it in the registry. Returns the handle of the clique tree, or an error if the input parameters or codomain function
+///  are invalid, or the codomain would have more than DEFAULT_MAX_CODOMAIN_ENTRIES values.
+pub fn construct_clique_tree(
+    m: u32,
+    k: u32,
+    o: u32,
+    b: u32,
+    codomain_function_name: &str,
+    seed: u64,
+) -> Result<u32, ProblemGenError> {
+    let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
+    input_parameters.validate()?;
+    check_codomain_size(&input_parameters, DEFAULT_MAX_CODOMAIN_ENTRIES)?;
+    let codomain_function = CodomainFunction::from_line(codomain_function_name)?;
+    check_codomain_function(&codomain_function, &input_parameters)?;
+    let clique_tree = CliqueTree::new_seeded(input_parameters, codomain_function, seed);
+
+    CLIQUE_TREES.with(|clique_trees| {
+        let mut clique_trees = clique_trees.borrow_mut();
+        //Reuse the slot of a freed clique tree if there is one
+        match clique_trees.iter().position(Option::is_none) {
+            Some(handle) => {
+                clique_trees[handle] = Some(clique_tree);
+                Ok(handle as u32)
+            }
+            None => {
+                clique_trees.push(Some(clique_tree));
+                Ok(clique_trees.len() as u32 - 1)
+            }
+        }
+    })
+}
+
+///Free the clique tree with the given handle, or return an error if there is no clique tree with that handle
+pub fn free_clique_tree(handle: u32) -> Result<(), ProblemGenError> {
+    CLIQUE_TREES.with(|clique_trees| {
+        let mut clique_trees = clique_trees.borrow_mut();
+        clique_trees
+            .get_mut(handle as usize)
+            .and_then(Option::take)
+            .map(|_| ())
+            .ok_or_else(|| {
+                ProblemGenError::InvalidParameters(format!("no clique tree with handle {}", handle))
+            })
+    })
+}
+
+///Calculate the fitness of the solution for the clique tree with the given handle.
+/// The solution should contain a 0 or 1 value for every variable of the problem.
+pub fn evaluate_clique_tree(handle: u32, solution: &[i32]) -> Result<f64, ProblemGenError> {
+    with_clique_tree(handle, |clique_tree| {
+        let problem_size = clique_tree.get_problem_size() as usize;
+        if solution.len() != problem_size {
+            return Err(ProblemGenError::InvalidParameters(format!(
+                "solution has {} values, but the problem size is {}",
+                solution.len(),
+                problem_size
+            )));
+        }
+        if solution.iter().any(|&bit| bit != 0 && bit != 1) {
+            return Err(ProblemGenError::InvalidParameters(
+                "solution should only contain 0 and 1 values".to_owned(),
+            ));
+        }
+
+        let solution: Vec<u32> = solution.iter().map(|&bit| bit as u32).collect();
+        let mut number_evaluations = 0;
+        Ok(clique_tree.calculate_fitness(&solution, &mut number_evaluations))
+    })
+}
+
+///Get the score of the global optimum of the clique tree with the given handle
+pub fn get_clique_tree_global_optimum_score(handle: u32) -> Result<f64, ProblemGenError> {
+    with_clique_tree(handle, |clique_tree| Ok(clique_tree.glob_optima_score))
+}
+
+///Call the function with the clique tree with the given handle, or return an error if there is no clique tree with that handle
+fn with_clique_tree<T>(
+    handle: u32,
+    function: impl FnOnce(&CliqueTree) -> Result<T, ProblemGenError>,
+) -> Result<T, ProblemGenError> {
+    CLIQUE_TREES.with(|clique_trees| {
+        let clique_trees = clique_trees.borrow();
+        let clique_tree = clique_trees
+            .get(handle as usize)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| {
+                ProblemGenError::InvalidParameters(format!("no clique tree with handle {}", handle))
+            })?;
+        function(clique_tree)
+    })
+}
+
+fn to_js_error(error: ProblemGenError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+///Construct a clique tree and return its handle, see construct_clique_tree
+#[wasm_bindgen]
+pub fn construct(
+    m: u32,
+    k: u32,
+    o: u32,
+    b: u32,
+    codomain_function_name: &str,
+    seed: u64,
+) -> Result<JsValue, JsValue> {
+    construct_clique_tree(m, k, o, b, codomain_function_name, seed)
+        .map(JsValue::from)
+        .map_err(to_js_error)
+}
+
+///Calculate the fitness of the solution for the clique tree with the given handle, see evaluate_clique_tree
+#[wasm_bindgen]
+pub fn evaluate(handle: u32, solution: &[i32]) -> Result<f64, JsValue> {
+    evaluate_clique_tree(handle, solution).map_err(to_js_error)
+}
+
+///Get the score of the global optimum of the clique tree with the given handle
+#[wasm_bindgen]
+pub fn global_optimum_score(handle: u32) -> Result<f64, JsValue> {
+    get_clique_tree_global_optimum_score(handle).map_err(to_js_error)
+}
+
+///Free the clique tree with the given handle, see free_clique_tree
+#[wasm_bindgen]
+pub fn free(handle: u32) -> Result<(), JsValue> {
+    free_clique_tree(handle).map_err(to_js_error)
+}
@@ -0,0 +1,1523 @@
+use rand::Rng;
+
+use problem_generator::problem::{
+    clique_tree::{
+        get_possible_substrings, index_to_solution, solution_to_index,
+        transform_substring_vector_to_index, CliqueTree, InputParameters,
+        SolutionFit, DEFAULT_FITNESS_EPSILON, MAX_ENUMERATED_PROBLEM_SIZE,
+        MAX_MATERIALIZED_GLOBAL_OPTIMA, MIN_PARALLEL_LEAVES, Topology,
+    },
+    codomain::generate_codomain,
+    codomain_subclasses::CodomainFunction,
+    configuration::get_rng,
+    error::ProblemGenError,
+    evaluation::CliqueTreeEvaluator,
+    problem_generation::Problem,
+};
+
+#[test]
+fn reconstructed_separators_match_constructed() {
+    for &(m, k, o, b) in &[(10, 4, 2, 3), (7, 3, 1, 2), (6, 5, 0, 2)] {
+        let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
+        let mut rng = get_rng(Some(2398));
+        let (cliques, separators) = CliqueTree::construct(&input_parameters, &mut rng);
+
+        assert_eq!(
+            CliqueTree::reconstruct_separators(&input_parameters, &cliques),
+            separators
+        );
+    }
+}
+
+#[test]
+fn problem_codomain_tree_has_separators_and_optima() {
+    let input_parameters = InputParameters::new_from_primitives(8, 4, 2, 2);
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &CodomainFunction::Random, &mut rng);
+    let clique_tree = CliqueTree::new(
+        input_parameters.clone(),
+        CodomainFunction::Random,
+        codomain.clone(),
+        &mut rng,
+    );
+
+    let problem = Problem {
+        input_parameters,
+        glob_optima_score: 0.0,
+        glob_optima_strings: Vec::new(),
+        cliques: clique_tree.cliques.clone(),
+    };
+    let reconstructed = CliqueTree::construct_from_problem_codomain(problem, codomain);
+
+    assert_eq!(reconstructed.separators, clique_tree.separators);
+    assert_eq!(reconstructed.glob_optima_strings, clique_tree.glob_optima_strings);
+    assert_eq!(reconstructed.glob_optima_score, clique_tree.glob_optima_score);
+}
+
+#[test]
+fn constant_codomain_global_optima_count_saturates() {
+    let input_parameters = InputParameters::new_from_primitives(40, 4, 0, 1);
+    let codomain = vec![vec![1.0; 1 << 4]; 40];
+
+    //16^40 = 2^160 global optima, which does not fit in a u128
+    assert_eq!(
+        CliqueTree::count_global_optima_separable(
+            &input_parameters,
+            &codomain,
+            DEFAULT_FITNESS_EPSILON
+        ),
+        u128::MAX
+    );
+
+    let mut rng = get_rng(Some(2398));
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        codomain,
+        &mut rng,
+    );
+    assert!(clique_tree.glob_optima_strings.len() <= MAX_MATERIALIZED_GLOBAL_OPTIMA);
+    assert!(clique_tree.glob_optima_truncated());
+    assert_eq!(clique_tree.glob_optima_score, 40.0);
+}
+
+#[test]
+fn separable_global_optima_count_exact() {
+    let input_parameters = InputParameters::new_from_primitives(3, 2, 0, 1);
+    let codomain = vec![vec![1.0; 1 << 2]; 3];
+
+    assert_eq!(
+        CliqueTree::count_global_optima_separable(
+            &input_parameters,
+            &codomain,
+            DEFAULT_FITNESS_EPSILON
+        ),
+        64
+    );
+
+    let mut rng = get_rng(Some(2398));
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        codomain,
+        &mut rng,
+    );
+    assert_eq!(clique_tree.glob_optima_strings.len(), 64);
+    assert!(!clique_tree.glob_optima_truncated());
+}
+
+fn generate_clique_tree(
+    input_parameters: InputParameters,
+    codomain_function: CodomainFunction,
+    seed: u64,
+) -> CliqueTree {
+    let mut rng = get_rng(Some(seed));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    CliqueTree::new(input_parameters, codomain_function, codomain, &mut rng)
+}
+
+///Calculate the fitness of every possible solution
+fn brute_force_fitnesses(clique_tree: &CliqueTree) -> Vec<f64> {
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut number_evaluations = 0;
+    get_possible_substrings(problem_size)
+        .iter()
+        .map(|solution| clique_tree.calculate_fitness(solution, &mut number_evaluations))
+        .collect()
+}
+
+///Check the global optimum score and the global optima of the clique tree against the fitnesses of all solutions
+fn assert_global_optima_match_brute_force(clique_tree: &CliqueTree) {
+    let fitnesses = brute_force_fitnesses(clique_tree);
+    let highest_fitness = fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let number_global_optima = fitnesses
+        .iter()
+        .filter(|&&fitness| (fitness - highest_fitness).abs() < DEFAULT_FITNESS_EPSILON)
+        .count();
+    assert!((clique_tree.glob_optima_score - highest_fitness).abs() < 1e-9);
+    assert_eq!(clique_tree.glob_optima_strings.len(), number_global_optima);
+
+    let mut number_evaluations = 0;
+    for glob_optimum in &clique_tree.glob_optima_strings {
+        let fitness = clique_tree.calculate_fitness(glob_optimum, &mut number_evaluations);
+        assert!((fitness - highest_fitness).abs() < 1e-9);
+    }
+}
+
+///Construct the clique tree for the codomain of the codomain function shifted by the given amount, such that every codomain value is negative,
+/// and check its global optima against the fitnesses of all solutions
+fn shifted_negative_clique_tree(
+    input_parameters: InputParameters,
+    codomain_function: CodomainFunction,
+    shift: f64,
+) -> CliqueTree {
+    let mut rng = get_rng(Some(2398));
+    let codomain: Vec<Vec<f64>> =
+        generate_codomain(&input_parameters, &codomain_function, &mut rng)
+            .into_iter()
+            .map(|clique_codomain| {
+                clique_codomain
+                    .into_iter()
+                    .map(|value| value + shift)
+                    .collect()
+            })
+            .collect();
+    assert!(codomain.iter().flatten().all(|&value| value < 0.0));
+
+    let clique_tree = CliqueTree::new(input_parameters, codomain_function, codomain, &mut rng);
+    assert_global_optima_match_brute_force(&clique_tree);
+    clique_tree
+}
+
+#[test]
+fn representative_optimum_is_smallest_and_reproducible() {
+    let input_parameters = InputParameters::new_from_primitives(5, 3, 1, 2);
+    let generate_tied_clique_tree = |seed| {
+        let mut rng = get_rng(Some(seed));
+        //Codomain values from only three levels, so that there are many ties
+        let codomain: Vec<Vec<f64>> = (0..input_parameters.m)
+            .map(|_| (0..8).map(|_| rng.gen_range(0..3) as f64).collect())
+            .collect();
+        CliqueTree::new(input_parameters.clone(), CodomainFunction::Random, codomain, &mut rng)
+    };
+
+    let clique_tree = generate_tied_clique_tree(2398);
+    assert_eq!(
+        clique_tree.glob_optima_strings,
+        generate_tied_clique_tree(2398).glob_optima_strings
+    );
+
+    //The optima are sorted and equal to the solutions with the highest fitness, of which the first is the smallest
+    let fitnesses = brute_force_fitnesses(&clique_tree);
+    let solutions = get_possible_substrings(input_parameters.problem_size());
+    let brute_force_optima: Vec<Vec<u32>> = solutions
+        .into_iter()
+        .zip(fitnesses)
+        .filter(|(_, fitness)| (fitness - clique_tree.glob_optima_score).abs() < DEFAULT_FITNESS_EPSILON)
+        .map(|(solution, _)| solution)
+        .collect();
+    assert!(brute_force_optima.len() > 1);
+    let mut sorted_optima = clique_tree.glob_optima_strings.clone();
+    sorted_optima.sort();
+    assert_eq!(clique_tree.glob_optima_strings, sorted_optima);
+    assert_eq!(
+        clique_tree.glob_optima_strings[0],
+        *brute_force_optima.iter().min().unwrap()
+    );
+}
+
+#[test]
+fn separable_min_fitness_is_sum_of_clique_minima() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 0, 1),
+        CodomainFunction::Random,
+        2398,
+    );
+
+    let sum_of_minima: f64 = clique_tree
+        .codomain_values
+        .iter()
+        .map(|clique_codomain| {
+            clique_codomain
+                .iter()
+                .cloned()
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum();
+
+    assert!((clique_tree.min_fitness() - sum_of_minima).abs() < 1e-10);
+    assert_eq!(
+        clique_tree.fitness_range(),
+        (clique_tree.min_fitness(), clique_tree.glob_optima_score)
+    );
+}
+
+#[test]
+fn overlapping_fitness_range_matches_brute_force() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(5, 3, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+
+    let fitnesses = brute_force_fitnesses(&clique_tree);
+    let min = fitnesses.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    let (min_fitness, max_fitness) = clique_tree.fitness_range();
+    assert!((min_fitness - min).abs() < 1e-10);
+    assert!((max_fitness - max).abs() < 1e-10);
+}
+
+#[test]
+fn structure_seed_fixes_structure_independent_of_codomain_seed() {
+    let input_parameters = InputParameters::new_from_primitives(8, 4, 2, 2);
+
+    let clique_tree =
+        CliqueTree::new_with_seeds(input_parameters.clone(), CodomainFunction::Random, 1, 2398);
+    let other_codomain_clique_tree =
+        CliqueTree::new_with_seeds(input_parameters.clone(), CodomainFunction::Random, 2, 2398);
+    let other_structure_clique_tree =
+        CliqueTree::new_with_seeds(input_parameters, CodomainFunction::Random, 1, 2399);
+
+    assert_eq!(clique_tree.cliques, other_codomain_clique_tree.cliques);
+    assert_eq!(
+        clique_tree.separators,
+        other_codomain_clique_tree.separators
+    );
+    assert_ne!(
+        clique_tree.codomain_values,
+        other_codomain_clique_tree.codomain_values
+    );
+
+    assert_eq!(
+        clique_tree.codomain_values,
+        other_structure_clique_tree.codomain_values
+    );
+    assert_ne!(clique_tree.cliques, other_structure_clique_tree.cliques);
+}
+
+///Construct a separable problem where each clique's codomain value is the number of ones in its substring
+fn generate_onemax_clique_tree(m: u32, k: u32) -> CliqueTree {
+    let input_parameters = InputParameters::new_from_primitives(m, k, 0, 1);
+    let clique_codomain: Vec<f64> = (0..(1u32 << k))
+        .map(|substring_index| substring_index.count_ones() as f64)
+        .collect();
+    let codomain = vec![clique_codomain; m as usize];
+    let mut rng = get_rng(Some(2398));
+    CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        codomain,
+        &mut rng,
+    )
+}
+
+#[test]
+fn onemax_fitness_distance_correlation_is_negative() {
+    let clique_tree = generate_onemax_clique_tree(10, 3);
+    let mut rng = get_rng(Some(2398));
+
+    let fdc = clique_tree.fitness_distance_correlation(1000, &mut rng);
+    assert!(fdc < -0.99, "fdc was {}", fdc);
+}
+
+#[test]
+fn onemax_random_walk_autocorrelation_is_high() {
+    let clique_tree = generate_onemax_clique_tree(10, 3);
+    let mut rng = get_rng(Some(2398));
+
+    //For OneMax, the autocorrelation at lag 1 is expected to be 1 - 2/n
+    let autocorrelation = clique_tree.random_walk_autocorrelation(10000, 1, &mut rng);
+    assert!(
+        (autocorrelation - (1.0 - 2.0 / 30.0)).abs() < 0.05,
+        "autocorrelation was {}",
+        autocorrelation
+    );
+    assert!(clique_tree.random_walk_autocorrelation(10000, 10, &mut rng) < autocorrelation);
+}
+
+#[test]
+fn evaluate_partial_fully_assigned_equals_fitness() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(5, 3, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut number_evaluations = 0;
+
+    for solution in get_possible_substrings(problem_size) {
+        let assignment: Vec<Option<u32>> = solution.iter().map(|&bit| Some(bit)).collect();
+        let fitness = clique_tree.calculate_fitness(&solution, &mut number_evaluations);
+        assert_eq!(
+            clique_tree.evaluate_partial(&assignment),
+            (fitness, fitness)
+        );
+    }
+}
+
+#[test]
+fn evaluator_fitness_equals_clique_tree_fitness() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(5, 3, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let evaluator = CliqueTreeEvaluator::from(&clique_tree);
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut number_evaluations = 0;
+
+    for solution in get_possible_substrings(problem_size) {
+        let solutionfit = evaluator.evaluate(solution.clone(), &mut number_evaluations);
+        assert_eq!(
+            solutionfit.fitness,
+            clique_tree.calculate_fitness(&solution, &mut number_evaluations)
+        );
+        for index_mutation in 0..problem_size {
+            assert_eq!(
+                evaluator.calculate_fitness_delta(&solutionfit, &mut number_evaluations, index_mutation),
+                clique_tree.calculate_fitness_delta(&solutionfit, &mut number_evaluations, index_mutation)
+            );
+        }
+    }
+}
+
+#[test]
+fn evaluate_partial_bounds_completions() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(5, 3, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut number_evaluations = 0;
+
+    //Leave the first three variables unassigned
+    let assignment: Vec<Option<u32>> = (0..problem_size)
+        .map(|i| if i < 3 { None } else { Some(i % 2) })
+        .collect();
+    let (lower_bound, upper_bound) = clique_tree.evaluate_partial(&assignment);
+
+    for completion in get_possible_substrings(3) {
+        let solution: Vec<u32> = assignment
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| bit.unwrap_or_else(|| completion[i]))
+            .collect();
+        let fitness = clique_tree.calculate_fitness(&solution, &mut number_evaluations);
+        assert!(lower_bound <= fitness && fitness <= upper_bound);
+    }
+}
+
+#[test]
+fn separable_interaction_graph_is_union_of_cliques() {
+    let (m, k) = (5, 4);
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(m, k, 0, 1),
+        CodomainFunction::Random,
+        2398,
+    );
+    let (number_of_variables, edges) = clique_tree.interaction_graph();
+
+    assert_eq!(number_of_variables, (m * k) as usize);
+    assert_eq!(edges.len(), (m * k * (k - 1) / 2) as usize);
+    //Every edge connects two variables of the same clique
+    for (u, v) in edges {
+        assert!(u < v);
+        assert!(clique_tree
+            .cliques
+            .iter()
+            .any(|clique| clique.contains(&u) && clique.contains(&v)));
+    }
+}
+
+#[test]
+fn write_global_optima_respects_capacity() {
+    //A constant codomain makes every solution a global optimum
+    let input_parameters = InputParameters::new_from_primitives(3, 2, 0, 1);
+    let codomain = vec![vec![1.0; 4]; 3];
+    let mut rng = get_rng(Some(2398));
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        codomain,
+        &mut rng,
+    );
+    assert_eq!(clique_tree.glob_optima_strings.len(), 64);
+
+    let mut buffers = vec![vec![2; 6]; 5];
+    let mut destinations: Vec<&mut [u32]> = buffers
+        .iter_mut()
+        .map(|buffer| buffer.as_mut_slice())
+        .collect();
+    let number_written = clique_tree.write_global_optima(&mut destinations).unwrap();
+
+    assert_eq!(number_written, 5);
+    assert_eq!(&buffers[..], &clique_tree.glob_optima_strings[..5]);
+
+    //A destination with the wrong length is rejected, without writing to any destination
+    let mut buffers = vec![vec![2; 6], vec![2; 5]];
+    let mut destinations: Vec<&mut [u32]> = buffers
+        .iter_mut()
+        .map(|buffer| buffer.as_mut_slice())
+        .collect();
+    assert!(matches!(
+        clique_tree.write_global_optima(&mut destinations),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert_eq!(buffers, vec![vec![2; 6], vec![2; 5]]);
+}
+
+#[test]
+fn solution_at_distance_has_exact_hamming_distance() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(8, 4, 2, 2),
+        CodomainFunction::DeceptiveTrap,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut rng = get_rng(Some(42));
+
+    for d in 0..=problem_size {
+        let solution = clique_tree.solution_at_distance(0, d, &mut rng);
+        let distance = solution
+            .iter()
+            .zip(clique_tree.glob_optima_strings[0].iter())
+            .filter(|(bit, optimum_bit)| bit != optimum_bit)
+            .count();
+        assert_eq!(distance, d as usize);
+    }
+}
+
+///Pack a solution into u64 words, with variable i at bit (i % 64) of word (i / 64)
+fn pack_solution(solution: &[u32]) -> Vec<u64> {
+    let mut bits = vec![0; solution.len().div_ceil(64)];
+    for (i, &bit) in solution.iter().enumerate() {
+        bits[i / 64] |= (bit as u64) << (i % 64);
+    }
+    bits
+}
+
+#[test]
+fn packed_fitness_equals_fitness() {
+    //Use a problem size over 64, so that the solution spans multiple words
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(40, 5, 3, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    assert!(problem_size > 64);
+    let mut rng = get_rng(Some(42));
+
+    for d in 0..problem_size {
+        let solution = clique_tree.solution_at_distance(0, d, &mut rng);
+        let mut number_evaluations = 0;
+        assert_eq!(
+            clique_tree.calculate_fitness_packed(
+                &pack_solution(&solution),
+                problem_size,
+                &mut number_evaluations
+            ),
+            clique_tree.calculate_fitness(&solution, &mut number_evaluations)
+        );
+        assert_eq!(number_evaluations, 2);
+    }
+}
+
+#[test]
+fn children_and_parent_of_binary_tree() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(7, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+
+    assert_eq!(clique_tree.children(0), vec![1, 2]);
+    assert_eq!(clique_tree.children(2), vec![5, 6]);
+    assert!(clique_tree.children(3).is_empty());
+    assert_eq!(clique_tree.parent(0), None);
+    assert_eq!(clique_tree.parent(3), Some(1));
+    assert_eq!(clique_tree.parent(6), Some(2));
+
+    //Every child shares its separator variables with its parent
+    for child in 1..7 {
+        let parent = clique_tree.parent(child).unwrap();
+        assert!(clique_tree.children(parent).contains(&child));
+        for variable_index in &clique_tree.separators[child as usize] {
+            assert!(clique_tree.cliques[parent as usize].contains(variable_index));
+        }
+    }
+}
+
+#[test]
+fn apply_bit_flip_equals_fitness_of_flipped_solution() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut rng = get_rng(Some(42));
+    let solution = clique_tree.solution_at_distance(0, problem_size / 2, &mut rng);
+    let mut number_evaluations = 0;
+    let fitness = clique_tree.calculate_fitness(&solution, &mut number_evaluations);
+    let current_solutionfit = SolutionFit { solution, fitness };
+
+    for index in 0..problem_size {
+        let flipped_solutionfit =
+            clique_tree.apply_bit_flip(&current_solutionfit, index, &mut number_evaluations);
+
+        let mut flipped_solution = current_solutionfit.solution.clone();
+        flipped_solution[index as usize] = 1 - flipped_solution[index as usize];
+        assert_eq!(flipped_solutionfit.solution, flipped_solution);
+        let flipped_fitness =
+            clique_tree.calculate_fitness(&flipped_solution, &mut number_evaluations);
+        assert!((flipped_solutionfit.fitness - flipped_fitness).abs() < 1e-9);
+    }
+    assert_eq!(number_evaluations, 1 + 2 * problem_size);
+}
+
+#[test]
+fn try_flip_and_revert_flip_restore_solutionfit() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut rng = get_rng(Some(42));
+    let solution = clique_tree.solution_at_distance(0, problem_size / 2, &mut rng);
+    let mut number_evaluations = 0;
+    let fitness = clique_tree.calculate_fitness(&solution, &mut number_evaluations);
+    let original_solutionfit = SolutionFit { solution, fitness };
+
+    let mut solutionfit = original_solutionfit.clone();
+    for index in 0..problem_size {
+        let flipped_solutionfit =
+            clique_tree.apply_bit_flip(&original_solutionfit, index, &mut number_evaluations);
+
+        let previous_fitness =
+            clique_tree.try_flip(&mut solutionfit, index, &mut number_evaluations);
+        assert_eq!(previous_fitness, original_solutionfit.fitness);
+        assert_eq!(solutionfit.solution, flipped_solutionfit.solution);
+        assert_eq!(solutionfit.fitness, flipped_solutionfit.fitness);
+
+        clique_tree.revert_flip(&mut solutionfit, index, previous_fitness);
+        assert_eq!(solutionfit.solution, original_solutionfit.solution);
+        assert_eq!(
+            solutionfit.fitness.to_bits(),
+            original_solutionfit.fitness.to_bits()
+        );
+    }
+    assert_eq!(number_evaluations, 1 + 2 * problem_size);
+}
+
+#[test]
+fn input_parameters_from_compact_str() {
+    let expected = InputParameters::new_from_primitives(10, 3, 1, 2);
+    assert_eq!(
+        InputParameters::from_compact_str("10x3x1x2").unwrap(),
+        expected
+    );
+    assert_eq!(
+        InputParameters::from_compact_str("m=10,k=3,o=1,b=2").unwrap(),
+        expected
+    );
+    assert_eq!(
+        InputParameters::from_compact_str("b=2, o=1, k=3, M=10").unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn input_parameters_from_malformed_compact_str() {
+    for malformed in &[
+        "10x3x1",
+        "10x3x1x2x5",
+        "m=10,k=3,o=1",
+        "m=10,k=3,o=1,b=2,m=4",
+        "m=10,k=3,o=1,q=2",
+    ] {
+        assert!(
+            matches!(
+                InputParameters::from_compact_str(malformed),
+                Err(ProblemGenError::InvalidParameters(_))
+            ),
+            "{} should be rejected as invalid",
+            malformed
+        );
+    }
+    for non_numeric in &["10x3xax2", "m=10,k=three,o=1,b=2", "10x3x-1x2"] {
+        assert!(
+            matches!(
+                InputParameters::from_compact_str(non_numeric),
+                Err(ProblemGenError::Parse(_))
+            ),
+            "{} should be rejected as unparseable",
+            non_numeric
+        );
+    }
+}
+
+#[test]
+fn separable_problem_has_singleton_components() {
+    let m = 5;
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(m, 4, 0, 1),
+        CodomainFunction::Random,
+        2398,
+    );
+    assert!(clique_tree.is_separable());
+    let expected: Vec<Vec<u32>> = (0..m).map(|clique_index| vec![clique_index]).collect();
+    assert_eq!(clique_tree.connected_components(), expected);
+}
+
+#[test]
+fn overlapping_tree_has_one_component() {
+    let m = 7;
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(m, 4, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    assert!(!clique_tree.is_separable());
+    assert_eq!(
+        clique_tree.connected_components(),
+        vec![(0..m).collect::<Vec<u32>>()]
+    );
+}
+
+#[test]
+fn constant_codomain_statistics() {
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 1, 2);
+    let codomain = vec![vec![0.25; 1 << 3]; 4];
+    let mut rng = get_rng(Some(2398));
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        codomain,
+        &mut rng,
+    );
+
+    let statistics = clique_tree.codomain_statistics();
+    assert_eq!(statistics.min, 0.25);
+    assert_eq!(statistics.max, 0.25);
+    assert_eq!(statistics.mean, 0.25);
+    assert_eq!(statistics.std_dev, 0.0);
+    assert_eq!(statistics.distinct_values, 1);
+}
+
+#[test]
+fn nk_q_codomain_statistics_lie_in_unit_interval() {
+    let q = 4;
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(10, 4, 1, 2),
+        CodomainFunction::NKq { q },
+        2398,
+    );
+
+    let statistics = clique_tree.codomain_statistics();
+    assert!(statistics.min >= 0.0);
+    assert!(statistics.max <= 1.0);
+    assert!(statistics.min <= statistics.mean && statistics.mean <= statistics.max);
+    assert!(statistics.std_dev > 0.0);
+    assert!(statistics.distinct_values <= q as usize);
+}
+
+#[test]
+fn content_hash_depends_on_seed() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 2, 2);
+    let clique_tree =
+        generate_clique_tree(input_parameters.clone(), CodomainFunction::Random, 2398);
+    let same_seed_clique_tree =
+        generate_clique_tree(input_parameters.clone(), CodomainFunction::Random, 2398);
+    let other_seed_clique_tree =
+        generate_clique_tree(input_parameters, CodomainFunction::Random, 2399);
+
+    assert_eq!(
+        clique_tree.content_hash(),
+        same_seed_clique_tree.content_hash()
+    );
+    assert_ne!(
+        clique_tree.content_hash(),
+        other_seed_clique_tree.content_hash()
+    );
+}
+
+#[test]
+fn content_hash_ignores_floating_point_noise() {
+    let input_parameters = InputParameters::new_from_primitives(3, 3, 0, 1);
+    let codomain = vec![vec![0.1 + 0.2; 1 << 3]; 3];
+    let noiseless_codomain = vec![vec![0.3; 1 << 3]; 3];
+    let clique_tree = CliqueTree::new(
+        input_parameters.clone(),
+        CodomainFunction::Unknown,
+        codomain,
+        &mut get_rng(Some(2398)),
+    );
+    let noiseless_clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Unknown,
+        noiseless_codomain,
+        &mut get_rng(Some(2398)),
+    );
+
+    assert_eq!(
+        clique_tree.content_hash(),
+        noiseless_clique_tree.content_hash()
+    );
+}
+
+#[test]
+fn restricted_clique_argmax_matches_separable_global_optimum() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(4, 3, 0, 1),
+        CodomainFunction::Random,
+        2398,
+    );
+    let (variables, codomain) = clique_tree.restrict_to_clique(0);
+    assert_eq!(variables, clique_tree.cliques[0]);
+    assert_eq!(codomain.len(), 1 << 3);
+
+    let argmax = (0..codomain.len())
+        .max_by(|&i, &j| codomain[i].partial_cmp(&codomain[j]).unwrap())
+        .unwrap();
+    let global_optimum = &clique_tree.glob_optima_strings[0];
+    let optimum_substring: Vec<u32> = variables
+        .iter()
+        .map(|&variable_index| global_optimum[variable_index as usize])
+        .collect();
+    assert_eq!(
+        argmax as u32,
+        transform_substring_vector_to_index(&optimum_substring)
+    );
+}
+
+#[test]
+fn subtree_is_standalone_problem() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(7, 4, 1, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    //The subtree rooted at clique 1 contains cliques 1, 3, and 4
+    let subtree = clique_tree.subtree(1);
+    assert_eq!(
+        subtree.input_parameters,
+        InputParameters::new_from_primitives(3, 4, 1, 2)
+    );
+    assert_eq!(
+        subtree.codomain_values,
+        vec![
+            clique_tree.codomain_values[1].clone(),
+            clique_tree.codomain_values[3].clone(),
+            clique_tree.codomain_values[4].clone(),
+        ]
+    );
+    assert_eq!(subtree.cliques[0], vec![0, 1, 2, 3]);
+    assert_eq!(subtree.separators[1].len(), 1);
+    assert_eq!(subtree.separators[2].len(), 1);
+
+    let fitnesses = brute_force_fitnesses(&subtree);
+    assert_eq!(
+        fitnesses.len(),
+        1 << subtree.input_parameters.problem_size()
+    );
+    let max_fitness = fitnesses.iter().cloned().fold(f64::MIN, f64::max);
+    assert!((max_fitness - subtree.glob_optima_score).abs() < 1e-9);
+}
+
+#[test]
+fn smaller_fitness_epsilon_distinguishes_close_fitnesses() {
+    //Two nk-q values of the same clique that differ by less than the default epsilon
+    let input_parameters = InputParameters::new_from_primitives(2, 2, 1, 1);
+    let codomain = vec![vec![0.0, 0.25, 0.5, 0.5 + 1e-12], vec![0.0; 4]];
+    let mut rng = get_rng(Some(2398));
+    let mut clique_tree = CliqueTree::new(
+        input_parameters.clone(),
+        CodomainFunction::NKq { q: 1 << 30 },
+        codomain.clone(),
+        &mut rng,
+    );
+    assert_eq!(clique_tree.fitness_epsilon, DEFAULT_FITNESS_EPSILON);
+    assert_eq!(clique_tree.glob_optima_strings.len(), 4);
+
+    clique_tree.set_fitness_epsilon(1e-14);
+    assert_eq!(clique_tree.glob_optima_strings.len(), 2);
+    assert_eq!(clique_tree.glob_optima_score, 0.5 + 1e-12);
+
+    //Passing the epsilon on construction gives the same global optima
+    let clique_tree_with_epsilon = CliqueTree::new_with_fitness_epsilon(
+        input_parameters,
+        CodomainFunction::NKq { q: 1 << 30 },
+        codomain,
+        1e-14,
+        &mut get_rng(Some(2398)),
+    );
+    assert_eq!(clique_tree_with_epsilon.fitness_epsilon, 1e-14);
+    assert_eq!(
+        clique_tree_with_epsilon.glob_optima_strings,
+        clique_tree.glob_optima_strings
+    );
+
+    let mut number_evaluations = 0;
+    let mut worse_solution = clique_tree.glob_optima_strings[0].clone();
+    worse_solution[clique_tree.cliques[0][1] as usize] = 0;
+    let fitness = clique_tree.calculate_fitness(&worse_solution, &mut number_evaluations);
+    assert!(!clique_tree.is_global_optimum(&SolutionFit {
+        solution: worse_solution,
+        fitness,
+    }));
+}
+
+#[test]
+fn parallel_global_optima_equal_sequential_for_large_tree() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(2000, 5, 2, 3),
+        CodomainFunction::Random,
+        2398,
+    );
+    let number_of_leaves = (0..2000)
+        .filter(|&clique_index| clique_tree.children(clique_index).is_empty())
+        .count();
+    assert!(number_of_leaves >= MIN_PARALLEL_LEAVES);
+
+    let parallel_optima = CliqueTree::calculate_global_optima(
+        &clique_tree.input_parameters,
+        &clique_tree.codomain_function,
+        &clique_tree.codomain_values,
+        &clique_tree.cliques,
+        &clique_tree.separators,
+        DEFAULT_FITNESS_EPSILON,
+    );
+
+    let sequential_optima = CliqueTree::calculate_global_optima_sequential(
+        &clique_tree.input_parameters,
+        &clique_tree.codomain_function,
+        &clique_tree.codomain_values,
+        &clique_tree.cliques,
+        &clique_tree.separators,
+        DEFAULT_FITNESS_EPSILON,
+    );
+
+    assert_eq!(parallel_optima, sequential_optima);
+}
+
+#[test]
+fn constructed_structure_is_valid_tree_for_partial_last_level() {
+    for m in 1..40 {
+        for k in 1..6 {
+            for o in 0..k {
+                for b in 1..5 {
+                    let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
+                    let mut rng = get_rng(Some(u64::from(m * 1000 + k * 100 + o * 10 + b)));
+                    let (cliques, separators) = CliqueTree::construct(&input_parameters, &mut rng);
+                    let parameters_string = format!("m={} k={} o={} b={}", m, k, o, b);
+
+                    assert_eq!(cliques.len(), m as usize, "{}", parameters_string);
+                    assert_eq!(separators.len(), m as usize, "{}", parameters_string);
+                    assert!(cliques.iter().all(|clique| clique.len() == k as usize));
+
+                    //Every non-root clique has exactly one parent, with which it shares exactly o variables,
+                    // and every other variable of the clique is new to the tree
+                    let mut seen_variables: Vec<u32> = cliques[0].clone();
+                    let mut edges = 0;
+                    for child in 1..m {
+                        let parents: Vec<u32> = (0..m)
+                            .filter(|&parent| {
+                                CliqueTree::get_child_indices(&input_parameters, parent)
+                                    .any(|index| index == child)
+                            })
+                            .collect();
+                        let parent = CliqueTree::get_parent_index(&input_parameters, child)
+                            .expect("non-root clique has a parent");
+                        assert_eq!(parents, vec![parent], "{}", parameters_string);
+                        assert!(parent < child, "{}", parameters_string);
+                        edges += 1;
+
+                        let separator = &separators[child as usize];
+                        assert_eq!(separator.len(), o as usize, "{}", parameters_string);
+                        for variable_index in &cliques[child as usize] {
+                            if separator.contains(variable_index) {
+                                assert!(cliques[parent as usize].contains(variable_index));
+                            } else {
+                                assert!(
+                                    !seen_variables.contains(variable_index),
+                                    "{}",
+                                    parameters_string
+                                );
+                                seen_variables.push(*variable_index);
+                            }
+                        }
+                    }
+                    assert_eq!(edges, m - 1, "{}", parameters_string);
+                    assert_eq!(
+                        seen_variables.len() as u32,
+                        input_parameters.problem_size(),
+                        "{}",
+                        parameters_string
+                    );
+
+                    //The tree is connected: all cliques are reachable from the root
+                    let mut visited = vec![false; m as usize];
+                    let mut stack = vec![0];
+                    while let Some(clique_index) = stack.pop() {
+                        assert!(!visited[clique_index as usize], "{}", parameters_string);
+                        visited[clique_index as usize] = true;
+                        stack.extend(CliqueTree::get_child_indices(
+                            &input_parameters,
+                            clique_index,
+                        ));
+                    }
+                    assert!(visited.iter().all(|&v| v), "{}", parameters_string);
+
+                    assert_eq!(
+                        CliqueTree::reconstruct_separators(&input_parameters, &cliques),
+                        separators,
+                        "{}",
+                        parameters_string
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fitness_with_indices_sums_codomain_values() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(10, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut rng = get_rng(Some(42));
+
+    for d in 0..problem_size {
+        let solution = clique_tree.solution_at_distance(0, d, &mut rng);
+        let (fitness, clique_substring_indices) =
+            clique_tree.calculate_fitness_with_indices(&solution);
+        let mut number_evaluations = 0;
+        assert_eq!(
+            fitness,
+            clique_tree.calculate_fitness(&solution, &mut number_evaluations)
+        );
+
+        assert_eq!(clique_substring_indices.len(), clique_tree.cliques.len());
+        let sum: f64 = clique_substring_indices
+            .iter()
+            .enumerate()
+            .map(|(clique_index, &index)| clique_tree.codomain_values[clique_index][index as usize])
+            .sum();
+        assert!((sum - fitness).abs() < 1e-9);
+
+        //The cached indices give the same delta as recomputing them
+        let solutionfit = SolutionFit { solution, fitness };
+        for index_mutation in 0..problem_size {
+            assert!(
+                (clique_tree.calculate_fitness_delta_with_indices(
+                    &solutionfit,
+                    &clique_substring_indices,
+                    &mut number_evaluations,
+                    index_mutation
+                ) - clique_tree.calculate_fitness_delta(
+                    &solutionfit,
+                    &mut number_evaluations,
+                    index_mutation
+                ))
+                .abs()
+                    < 1e-9
+            );
+        }
+    }
+}
+
+#[test]
+fn solutions_by_fitness_starts_at_global_optimum_and_does_not_increase() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(4, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+
+    let solutionfits: Vec<SolutionFit> = clique_tree.solutions_by_fitness().collect();
+    assert_eq!(solutionfits.len(), 1 << problem_size);
+
+    assert!(clique_tree.is_global_optimum(&solutionfits[0]));
+    assert!(clique_tree
+        .glob_optima_strings
+        .contains(&solutionfits[0].solution));
+    assert!(solutionfits
+        .windows(2)
+        .all(|pair| pair[0].fitness >= pair[1].fitness));
+
+    let mut fitnesses: Vec<f64> = brute_force_fitnesses(&clique_tree);
+    fitnesses.sort_by(|a, b| b.total_cmp(a));
+    let sorted_fitnesses: Vec<f64> = solutionfits
+        .iter()
+        .map(|solutionfit| solutionfit.fitness)
+        .collect();
+    assert_eq!(sorted_fitnesses, fitnesses);
+}
+
+#[test]
+fn fitness_histogram_counts_all_solutions() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(4, 3, 1, 2),
+        CodomainFunction::NKq { q: 4 },
+        2398,
+    );
+    let problem_size = clique_tree.get_problem_size();
+
+    let histogram = clique_tree.fitness_histogram(10);
+    assert_eq!(histogram.len(), 10);
+    assert_eq!(
+        histogram.iter().map(|&(_, _, count)| count).sum::<usize>(),
+        1 << problem_size
+    );
+    let &(_, high, optimum_bin_count) = histogram.last().unwrap();
+    assert!((high - clique_tree.glob_optima_score).abs() < DEFAULT_FITNESS_EPSILON);
+    assert!(optimum_bin_count >= clique_tree.glob_optima_strings.len());
+    for bins in histogram.windows(2) {
+        assert_eq!(bins[0].1, bins[1].0);
+    }
+}
+
+#[test]
+#[should_panic]
+fn solutions_by_fitness_rejects_large_problems() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(30, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    assert!(clique_tree.get_problem_size() > MAX_ENUMERATED_PROBLEM_SIZE);
+    let _solutionfits = clique_tree.solutions_by_fitness();
+}
+
+#[test]
+fn o_equal_to_k_is_rejected() {
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 3, 2);
+    assert!(matches!(
+        input_parameters.validate(),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(InputParameters::new_from_primitives(4, 3, 2, 2).validate().is_ok());
+
+    let mut lines = "4 3 3 2".lines();
+    assert!(InputParameters::from_line_iterator(&mut lines).is_err());
+}
+
+#[test]
+fn zero_cliques_and_zero_branching_factor_are_rejected() {
+    for input_parameters in &[
+        InputParameters::new_from_primitives(0, 3, 1, 2),
+        InputParameters::new_from_primitives(4, 3, 1, 0),
+    ] {
+        assert!(matches!(
+            input_parameters.validate(),
+            Err(ProblemGenError::InvalidParameters(_))
+        ));
+    }
+    //Separable problems are constructed as a chain, so they don't need a branching factor
+    assert!(InputParameters::new_from_primitives(4, 3, 0, 0)
+        .validate()
+        .is_ok());
+}
+
+#[test]
+#[should_panic(expected = "o (3) should be smaller than k (3)")]
+fn construct_panics_for_o_equal_to_k() {
+    let mut rng = get_rng(Some(2398));
+    CliqueTree::construct(&InputParameters::new_from_primitives(4, 3, 3, 2), &mut rng);
+}
+
+#[test]
+fn all_negative_codomain_has_least_negative_optimum() {
+    let q = 3;
+    for &(m, k, o, b) in &[(5, 3, 1, 2), (4, 3, 0, 1)] {
+        //Shift the values from -q..=q to -2q-1..=-1
+        let clique_tree = shifted_negative_clique_tree(
+            InputParameters::new_from_primitives(m, k, o, b),
+            CodomainFunction::NKqSigned { q },
+            -((q + 1) as f64),
+        );
+        assert!(clique_tree.glob_optima_score < 0.0);
+    }
+}
+
+#[test]
+fn negative_codomain_optima_match_brute_force() {
+    for &(m, k, o, b) in &[(7, 4, 2, 2), (6, 3, 1, 3), (4, 4, 0, 1)] {
+        //Random values in [0, 1) shifted to [-2, -1)
+        let clique_tree = shifted_negative_clique_tree(
+            InputParameters::new_from_primitives(m, k, o, b),
+            CodomainFunction::Random,
+            -2.0,
+        );
+
+        let fitnesses = brute_force_fitnesses(&clique_tree);
+        let highest_fitness = fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let lowest_fitness = fitnesses.iter().cloned().fold(f64::INFINITY, f64::min);
+        assert!((clique_tree.min_fitness() - lowest_fitness).abs() < 1e-9);
+
+        let sequential_optima = CliqueTree::calculate_global_optima_sequential(
+            &clique_tree.input_parameters,
+            &clique_tree.codomain_function,
+            &clique_tree.codomain_values,
+            &clique_tree.cliques,
+            &clique_tree.separators,
+            DEFAULT_FITNESS_EPSILON,
+        );
+        let mut number_evaluations = 0;
+        for (glob_optimum, score) in &sequential_optima {
+            assert!((score - highest_fitness).abs() < 1e-9);
+            let fitness = clique_tree.calculate_fitness(glob_optimum, &mut number_evaluations);
+            assert!((fitness - highest_fitness).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn mutate_codomain_updates_global_optima() {
+    let mut clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(5, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let original_score = clique_tree.glob_optima_score;
+    let original_optima = clique_tree.glob_optima_strings.clone();
+    let clique_index = 2;
+    let clique_substring_index = |clique_tree: &CliqueTree, solution: &[u32]| {
+        let clique_substring: Vec<u32> = clique_tree.cliques[clique_index]
+            .iter()
+            .map(|&variable_index| solution[variable_index as usize])
+            .collect();
+        transform_substring_vector_to_index(&clique_substring) as usize
+    };
+
+    //Make a substring that is not part of the global optimum much better than all other substrings
+    let optimal_substring_index = clique_substring_index(&clique_tree, &original_optima[0]);
+    let substring_index = optimal_substring_index ^ 1;
+    let original_value = clique_tree.codomain_values[clique_index][substring_index];
+    clique_tree.set_codomain_value(clique_index, substring_index, 100.0);
+
+    let fitnesses = brute_force_fitnesses(&clique_tree);
+    let highest_fitness = fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!((clique_tree.glob_optima_score - highest_fitness).abs() < 1e-9);
+    assert!(clique_tree.glob_optima_score > original_score + 90.0);
+    for global_optimum in &clique_tree.glob_optima_strings {
+        assert_eq!(
+            clique_substring_index(&clique_tree, global_optimum),
+            substring_index
+        );
+    }
+
+    //Reverting the change in a batch restores the original global optima
+    clique_tree.mutate_codomain(&[(clique_index, substring_index, original_value)]);
+    assert_eq!(clique_tree.glob_optima_score, original_score);
+    assert_eq!(clique_tree.glob_optima_strings, original_optima);
+}
+
+#[test]
+fn solutionfit_evaluate_equals_calculate_fitness() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let problem_size = clique_tree.input_parameters.problem_size();
+    let mut rng = get_rng(Some(42));
+    let mut number_evaluations = 0;
+
+    for distance in 0..problem_size {
+        let solution = clique_tree.solution_at_distance(0, distance, &mut rng);
+        let fitness = clique_tree.calculate_fitness(&solution, &mut number_evaluations);
+        let solutionfit =
+            SolutionFit::evaluate(&clique_tree, solution.clone(), &mut number_evaluations);
+        assert_eq!(solutionfit.solution, solution);
+        assert_eq!(solutionfit.fitness, fitness);
+    }
+    assert_eq!(number_evaluations, 2 * problem_size);
+
+    let global_optimum = clique_tree.glob_optima_strings[0].clone();
+    let solutionfit = SolutionFit::new(global_optimum.clone(), clique_tree.glob_optima_score);
+    assert!(clique_tree.is_global_optimum(&solutionfit));
+    assert!(clique_tree.is_global_optimum(&SolutionFit::evaluate(
+        &clique_tree,
+        global_optimum,
+        &mut number_evaluations
+    )));
+}
+
+#[test]
+fn new_seeded_builds_same_problem_from_multiple_threads() {
+    let input_parameters = InputParameters::new_from_primitives(8, 4, 2, 2);
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let input_parameters = input_parameters.clone();
+            std::thread::spawn(move || {
+                CliqueTree::new_seeded(input_parameters, CodomainFunction::NKq { q: 2 }, 2398)
+            })
+        })
+        .collect();
+    let clique_trees: Vec<CliqueTree> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    assert_eq!(clique_trees[0].cliques, clique_trees[1].cliques);
+    assert_eq!(clique_trees[0].codomain_values, clique_trees[1].codomain_values);
+    assert_eq!(
+        clique_trees[0].glob_optima_strings,
+        clique_trees[1].glob_optima_strings
+    );
+    assert_eq!(
+        clique_trees[0].glob_optima_score,
+        clique_trees[1].glob_optima_score
+    );
+
+    //The same as generating with a random number generator seeded with the seed
+    let expected = generate_clique_tree(input_parameters, CodomainFunction::NKq { q: 2 }, 2398);
+    assert_eq!(clique_trees[0].glob_optima_strings, expected.glob_optima_strings);
+}
+
+#[test]
+fn expected_random_fitness_matches_empirical_mean() {
+    let input_parameters = InputParameters::new_from_primitives(8, 4, 2, 2);
+    let clique_tree = generate_clique_tree(input_parameters, CodomainFunction::Random, 2398);
+    let problem_size = clique_tree.get_problem_size() as usize;
+
+    let mut rng = get_rng(Some(2398));
+    let mut number_evaluations = 0;
+    let number_samples = 20000;
+    let empirical_mean = (0..number_samples)
+        .map(|_| {
+            let solution: Vec<u32> = (0..problem_size).map(|_| rng.gen_range(0..2)).collect();
+            clique_tree.calculate_fitness(&solution, &mut number_evaluations)
+        })
+        .sum::<f64>()
+        / number_samples as f64;
+
+    //The fitness is a sum of 8 clique values in [0, 1), so the standard error of the mean is below 0.02
+    assert!((clique_tree.expected_random_fitness() - empirical_mean).abs() < 0.05);
+}
+
+#[test]
+fn o_per_level_optima_match_brute_force() {
+    //Two levels below the root: cliques 1 and 2 share 2 variables with the root, cliques 3 and 4 share 1 variable with clique 1
+    let input_parameters = InputParameters::new_from_primitives(5, 4, 1, 2)
+        .with_o_per_level(vec![2, 1])
+        .unwrap();
+    assert_eq!(input_parameters.problem_size(), 14);
+    let clique_tree = generate_clique_tree(input_parameters, CodomainFunction::Random, 2398);
+
+    let separator_sizes: Vec<usize> = clique_tree.separators.iter().map(Vec::len).collect();
+    assert_eq!(separator_sizes, vec![0, 2, 2, 1, 1]);
+    assert_eq!(
+        CliqueTree::reconstruct_separators(&clique_tree.input_parameters, &clique_tree.cliques),
+        clique_tree.separators
+    );
+
+    assert_global_optima_match_brute_force(&clique_tree);
+}
+
+#[test]
+fn o_per_level_not_smaller_than_k_returns_error() {
+    let result = InputParameters::new_from_primitives(5, 4, 1, 2).with_o_per_level(vec![2, 4]);
+    assert!(matches!(result, Err(ProblemGenError::InvalidParameters(_))));
+}
+
+#[test]
+fn variable_clique_membership_matches_clique_scan() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(10, 5, 2, 3),
+        CodomainFunction::Random,
+        2398,
+    );
+    let membership = clique_tree.variable_clique_membership();
+    assert_eq!(membership.len(), clique_tree.get_problem_size() as usize);
+    assert_eq!(membership, clique_tree.variable_cliques());
+
+    let mut rng = get_rng(Some(2398));
+    let solution: Vec<u32> = (0..clique_tree.get_problem_size())
+        .map(|_| rng.gen_range(0..2))
+        .collect();
+    let mut number_evaluations = 0;
+    let solutionfit = SolutionFit::evaluate(&clique_tree, solution, &mut number_evaluations);
+
+    for (variable_index, clique_indices) in membership.iter().enumerate() {
+        let scanned_clique_indices: Vec<u32> = (0..clique_tree.cliques.len() as u32)
+            .filter(|&clique_index| {
+                clique_tree.cliques[clique_index as usize].contains(&(variable_index as u32))
+            })
+            .collect();
+        assert_eq!(*clique_indices, scanned_clique_indices);
+
+        //The delta evaluation only visits these cliques, and still matches a full evaluation of the mutated solution
+        let delta_fitness = clique_tree.calculate_fitness_delta(
+            &solutionfit,
+            &mut number_evaluations,
+            variable_index as u32,
+        );
+        let mut mutated_solution = solutionfit.solution.clone();
+        mutated_solution[variable_index] = 1 - mutated_solution[variable_index];
+        let fitness = clique_tree.calculate_fitness(&mutated_solution, &mut number_evaluations);
+        assert!((delta_fitness - fitness).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn topologies_have_expected_structure_and_optima() {
+    let m = 5;
+    for (topology, expected_children) in [
+        (
+            Topology::Balanced,
+            vec![vec![1, 2], vec![3, 4], vec![], vec![], vec![]],
+        ),
+        (
+            Topology::Path,
+            vec![vec![1], vec![2], vec![3], vec![4], vec![]],
+        ),
+        (
+            Topology::Star,
+            vec![vec![1, 2, 3, 4], vec![], vec![], vec![], vec![]],
+        ),
+    ] {
+        let input_parameters =
+            InputParameters::new_from_primitives(m, 3, 1, 2).with_topology(topology);
+        let clique_tree = generate_clique_tree(input_parameters, CodomainFunction::Random, 2398);
+
+        let children: Vec<Vec<u32>> = (0..m).map(|i| clique_tree.children(i)).collect();
+        assert_eq!(children, expected_children);
+        for (parent_index, parent_children) in children.iter().enumerate() {
+            for &child_index in parent_children {
+                assert_eq!(clique_tree.parent(child_index), Some(parent_index as u32));
+                //The separator of a child consists of variables of its parent
+                assert!(clique_tree.separators[child_index as usize]
+                    .iter()
+                    .all(|variable| clique_tree.cliques[parent_index].contains(variable)));
+            }
+        }
+
+        assert_global_optima_match_brute_force(&clique_tree);
+    }
+
+    assert_eq!("star".parse(), Ok(Topology::Star));
+    assert!("ring".parse::<Topology>().is_err());
+}
+
+#[test]
+fn cloned_tree_keeps_optima_and_is_independent() {
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let mut cloned_tree = clique_tree.clone();
+    assert_eq!(cloned_tree.cliques, clique_tree.cliques);
+    assert_eq!(cloned_tree.codomain_values, clique_tree.codomain_values);
+    assert_eq!(cloned_tree.glob_optima_strings, clique_tree.glob_optima_strings);
+    assert_eq!(cloned_tree.glob_optima_score, clique_tree.glob_optima_score);
+
+    //Changing the clone does not change the original
+    cloned_tree.mutate_codomain(&[(0, 0, 10.0)]);
+    assert_ne!(cloned_tree.codomain_values[0][0], clique_tree.codomain_values[0][0]);
+    assert_ne!(cloned_tree.glob_optima_score, clique_tree.glob_optima_score);
+}
+
+#[test]
+fn solution_index_round_trip() {
+    let mut rng = get_rng(Some(2398));
+    for n in [0, 1, 5, 12, 31, 40] {
+        for _ in 0..20 {
+            let solution: Vec<u32> = (0..n).map(|_| rng.gen_range(0..2)).collect();
+            let index = solution_to_index(&solution);
+            assert_eq!(index_to_solution(index, n), solution);
+        }
+    }
+
+    //For small solutions, the index is the same as the substring index
+    for (index, substring) in get_possible_substrings(4).iter().enumerate() {
+        assert_eq!(solution_to_index(substring), index as u64);
+        assert_eq!(
+            solution_to_index(substring),
+            transform_substring_vector_to_index(substring) as u64
+        );
+        assert_eq!(&index_to_solution(index as u64, 4), substring);
+    }
+}
+
+#[test]
+fn solution_index_at_63_variables() {
+    let all_ones = vec![1; 63];
+    assert_eq!(solution_to_index(&all_ones), (1 << 63) - 1);
+    assert_eq!(index_to_solution((1 << 63) - 1, 63), all_ones);
+
+    let mut first_one = vec![0; 63];
+    first_one[0] = 1;
+    assert_eq!(solution_to_index(&first_one), 1 << 62);
+    assert_eq!(index_to_solution(1 << 62, 63), first_one);
+}
+
+#[test]
+fn describe_summarizes_clique_tree() {
+    let input_parameters = InputParameters::new_from_primitives(2, 2, 0, 1);
+    let codomain_values = vec![vec![0.0, 0.0, 0.0, 1.0], vec![0.0, 2.0, 0.0, 0.0]];
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Random,
+        codomain_values,
+        &mut get_rng(Some(2398)),
+    );
+
+    let description = clique_tree.describe();
+    assert!(description.contains("Parameters: M = 2, k = 2, o = 0, b = 1\n"));
+    assert!(description.contains("Problem size (n): 4\n"));
+    assert!(description.contains("Number of cliques: 2\n"));
+    assert!(description.contains("Codomain function: random\n"));
+    assert!(description.contains("Global optimum score: 3\n"));
+    assert!(description.contains("Number of global optima: 1\n"));
+    let first_global_optimum: String = clique_tree.glob_optima_strings[0]
+        .iter()
+        .map(|bit| bit.to_string())
+        .collect();
+    assert!(description.contains(&format!("First global optimum: {}\n", first_global_optimum)));
+}
+
+#[test]
+fn subfunctions_pair_cliques_with_codomain_values() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 2, 2);
+    let clique_tree = generate_clique_tree(input_parameters, CodomainFunction::Random, 2398);
+
+    assert_eq!(clique_tree.subfunctions().count(), 6);
+    for (clique_index, (variables, codomain_values)) in clique_tree.subfunctions().enumerate() {
+        assert_eq!(variables, clique_tree.cliques[clique_index].as_slice());
+        assert_eq!(codomain_values, clique_tree.codomain_values[clique_index].as_slice());
+    }
+}
+
+#[test]
+fn onemax_global_optimum_basin_is_whole_space() {
+    let clique_tree = generate_onemax_clique_tree(8, 4);
+    let mut rng = get_rng(Some(2398));
+
+    let local_optimum = clique_tree.random_local_optimum(&mut rng);
+    assert_eq!(local_optimum.solution, clique_tree.glob_optima_strings[0]);
+
+    let global_optimum = clique_tree.glob_optima_strings[0].clone();
+    let basin_size = clique_tree.estimate_basin_size(&global_optimum, 100, &mut rng);
+    assert_eq!(basin_size, 1.0);
+
+    let zeros = vec![0; clique_tree.get_problem_size() as usize];
+    assert_eq!(clique_tree.estimate_basin_size(&zeros, 20, &mut rng), 0.0);
+}
+
+#[test]
+fn clique_separator_pairs_have_separators_of_size_o() {
+    let separable_clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(6, 4, 0, 1),
+        CodomainFunction::Random,
+        2398,
+    );
+    let pairs = separable_clique_tree.clique_separator_pairs();
+    assert_eq!(pairs.len(), 6);
+    assert!(pairs.iter().all(|(_, separator)| separator.is_empty()));
+
+    let clique_tree = generate_clique_tree(
+        InputParameters::new_from_primitives(7, 4, 2, 2),
+        CodomainFunction::Random,
+        2398,
+    );
+    let pairs = clique_tree.clique_separator_pairs();
+    assert_eq!(pairs.len(), 7);
+    assert!(pairs[0].1.is_empty());
+    for (clique_index, (clique, separator)) in pairs.iter().enumerate().skip(1) {
+        assert_eq!(separator.len(), 2);
+        assert_eq!(*clique, clique_tree.cliques[clique_index].as_slice());
+        assert!(separator.iter().all(|variable_index| clique.contains(variable_index)));
+    }
+}
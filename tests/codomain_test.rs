@@ -0,0 +1,810 @@
+use rand::Rng;
+use rand_chacha::ChaChaRng;
+use structopt::StructOpt;
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters, DEFAULT_FITNESS_EPSILON},
+    codomain::{
+        self, check_codomain_function, generate_clique_codomain, generate_codomain,
+        generate_codomain_flat, generate_codomain_per_clique, generate_codomain_with_options,
+        generate_codomain_with_target_optima, generate_with_planted_optimum, nest_flat_codomain,
+        CodomainOpt, GenerationOptions,
+    },
+    codomain_registry::{register_codomain_generator, CodomainGenerator},
+    codomain_subclasses::{
+        codomain_is_clique_invariant, generate_correlated_trap, list_codomain_functions,
+        CodomainFunction,
+    },
+    configuration::get_rng,
+    error::ProblemGenError,
+};
+
+struct AllHalfGenerator;
+
+impl CodomainGenerator for AllHalfGenerator {
+    fn generate(&self, input_parameters: &InputParameters, _rng: &mut ChaChaRng) -> Vec<Vec<f64>> {
+        vec![vec![0.5; 1 << input_parameters.k]; input_parameters.m as usize]
+    }
+
+    fn io_name(&self) -> String {
+        "all-0.5".to_owned()
+    }
+}
+
+#[test]
+fn custom_codomain_generator_registered() {
+    register_codomain_generator(AllHalfGenerator);
+
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 1, 2);
+    let codomain_function = CodomainFunction::Custom {
+        name: "all-0.5".to_owned(),
+    };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    assert_eq!(codomain.len(), 4);
+    for clique_codomain in &codomain {
+        assert_eq!(clique_codomain.len(), 8);
+        assert!(clique_codomain.iter().all(|&value| value == 0.5));
+    }
+    assert_eq!(codomain_function.to_io_string(), "custom-all-0.5");
+}
+
+#[test]
+fn unregistered_custom_codomain_returns_error() {
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 1, 2);
+    let codomain_function = CodomainFunction::Custom {
+        name: "not-registered".to_owned(),
+    };
+    assert!(matches!(
+        check_codomain_function(&codomain_function, &input_parameters),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        check_codomain_function(
+            &CodomainFunction::Mixture {
+                components: vec![(1.0, CodomainFunction::Trap), (1.0, codomain_function)],
+            },
+            &input_parameters
+        ),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+
+    //The codomain generator reports the error instead of panicking
+    let output_dir = tempfile::tempdir().unwrap();
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "4",
+        "3",
+        "1",
+        "2",
+        output_dir.path().join("codomain.txt").to_str().unwrap(),
+        "custom",
+        "not-registered",
+    ])
+    .unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+}
+
+#[test]
+fn complement_optimum_symmetrizes_codomain() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 2, 2);
+    let generation_options = GenerationOptions {
+        complement_optimum: true,
+        ..Default::default()
+    };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain_with_options(
+        &input_parameters,
+        &CodomainFunction::Random,
+        &generation_options,
+        &mut rng,
+    );
+
+    let complement_mask = (1 << input_parameters.k) - 1;
+    for clique_codomain in &codomain {
+        for substring_index in 0..clique_codomain.len() {
+            assert_eq!(
+                clique_codomain[substring_index],
+                clique_codomain[complement_mask - substring_index]
+            );
+        }
+    }
+
+    //The complement of the global optimum is a global optimum as well
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        CodomainFunction::Random,
+        codomain,
+        &mut rng,
+    );
+    for glob_optimum in &clique_tree.glob_optima_strings {
+        let complement: Vec<u32> = glob_optimum.iter().map(|bit| 1 - bit).collect();
+        assert!(clique_tree.glob_optima_strings.contains(&complement));
+    }
+}
+
+#[test]
+fn plateau_trap_distance_band_is_constant() {
+    let (k, w) = (6, 3);
+    let input_parameters = InputParameters::new_from_primitives(1, k, 0, 1);
+    let codomain_function = CodomainFunction::PlateauTrap { w };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    let clique_codomain = &codomain[0];
+
+    //The local optimum is the only substring with value 1.0, and the deceptive attractor is its complement
+    let optimum_index = clique_codomain
+        .iter()
+        .position(|&value| value == 1.0)
+        .unwrap();
+    let deceptor_index = optimum_index ^ ((1 << k) - 1);
+    let distance_to_deceptor =
+        |substring_index: usize| (substring_index ^ deceptor_index).count_ones();
+
+    let plateau_value = clique_codomain[deceptor_index];
+    for (substring_index, &value) in clique_codomain.iter().enumerate() {
+        let distance = distance_to_deceptor(substring_index);
+        if distance < w {
+            assert_eq!(value, plateau_value);
+        } else {
+            assert!(value > plateau_value);
+        }
+    }
+    assert_eq!(codomain_function.to_io_string(), "plateau-trap-3");
+    assert_eq!(
+        CodomainFunction::from_line("plateau-trap 3").unwrap(),
+        codomain_function
+    );
+
+    //A plateau wider than k is reported as an error, also as a component of a mixture and by the codomain generator
+    let input_parameters = InputParameters::new_from_primitives(3, 4, 1, 1);
+    let wide_plateau = CodomainFunction::PlateauTrap { w: 10 };
+    assert!(matches!(
+        check_codomain_function(&wide_plateau, &input_parameters),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        check_codomain_function(
+            &CodomainFunction::Mixture {
+                components: vec![(1.0, CodomainFunction::Random), (1.0, wide_plateau)],
+            },
+            &input_parameters
+        ),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    let output_dir = tempfile::tempdir().unwrap();
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "3",
+        "4",
+        "1",
+        "1",
+        output_dir.path().join("codomain.txt").to_str().unwrap(),
+        "plateau-trap",
+        "10",
+    ])
+    .unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+}
+
+#[test]
+fn deceptive_trap_scaled_endpoints_equal_configured_values() {
+    let k = 4;
+    let (deceptor_value, optimum_value) = (0.6, 2.0);
+    let input_parameters = InputParameters::new_from_primitives(3, k, 0, 1);
+    let codomain_function = CodomainFunction::DeceptiveTrapScaled {
+        deceptor_value,
+        optimum_value,
+    };
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut get_rng(Some(2398)));
+
+    for clique_codomain in &codomain {
+        //The local optimum has the optimum value, and the deceptive attractor, its complement, has the deceptor value
+        let optimum_index = clique_codomain
+            .iter()
+            .position(|&value| value == optimum_value)
+            .unwrap();
+        let deceptor_index = optimum_index ^ ((1 << k) - 1);
+        assert_eq!(clique_codomain[deceptor_index], deceptor_value);
+        for (substring_index, &value) in clique_codomain.iter().enumerate() {
+            let distance = (substring_index ^ deceptor_index).count_ones();
+            if distance < k {
+                let expected_value = deceptor_value * (1.0 - distance as f64 / k as f64);
+                assert!((value - expected_value).abs() < 1e-9);
+            }
+        }
+    }
+
+    //With the default values, it is the deceptive trap
+    let default_scaled = CodomainFunction::DeceptiveTrapScaled {
+        deceptor_value: 0.9,
+        optimum_value: 1.0,
+    };
+    assert_eq!(
+        generate_codomain(&input_parameters, &default_scaled, &mut get_rng(Some(2398))),
+        generate_codomain(
+            &input_parameters,
+            &CodomainFunction::DeceptiveTrap,
+            &mut get_rng(Some(2398))
+        )
+    );
+
+    assert_eq!(codomain_function.to_io_string(), "deceptive-trap-scaled-0.6-2");
+    assert_eq!(
+        CodomainFunction::from_line("deceptive-trap-scaled 0.6 2").unwrap(),
+        codomain_function
+    );
+}
+
+#[test]
+fn step_trap_values_are_constant_per_step() {
+    let (k, step_size) = (4, 2);
+    let input_parameters = InputParameters::new_from_primitives(1, k, 0, 1);
+    let codomain_function = CodomainFunction::StepTrap { step_size };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    let clique_codomain = &codomain[0];
+
+    //The local optimum is the only substring with value 1.0, and the deceptive attractor is its complement
+    let optimum_index = clique_codomain
+        .iter()
+        .position(|&value| value == 1.0)
+        .unwrap();
+    let deceptor_index = optimum_index ^ ((1 << k) - 1);
+
+    //Distances 0 and 1 form the first step, distances 2 and 3 the second step, and distance 4 is the local optimum
+    let expected_values_by_distance = [0.0, 0.0, 0.45, 0.45, 1.0];
+    for (substring_index, &value) in clique_codomain.iter().enumerate() {
+        let distance = (substring_index ^ deceptor_index).count_ones() as usize;
+        assert!((value - expected_values_by_distance[distance]).abs() < 1e-9);
+    }
+    assert_eq!(codomain_function.to_io_string(), "step-trap-2");
+    assert_eq!(
+        CodomainFunction::from_line("step-trap 2").unwrap(),
+        codomain_function
+    );
+
+    //A step size of 0 is reported as an error, also as a component of a mixture and by the codomain generator
+    assert!(matches!(
+        CodomainFunction::from_line("step-trap 0"),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        CodomainFunction::Mixture {
+            components: vec![
+                (1.0, CodomainFunction::Random),
+                (1.0, CodomainFunction::StepTrap { step_size: 0 })
+            ],
+        }
+        .validate(),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    let output_dir = tempfile::tempdir().unwrap();
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "3",
+        "4",
+        "1",
+        "1",
+        output_dir.path().join("codomain.txt").to_str().unwrap(),
+        "step-trap",
+        "0",
+    ])
+    .unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+}
+
+#[test]
+fn trap_dist_optimum_is_at_opt_distance_from_attractor() {
+    let (k, opt_distance) = (5, 2);
+    let input_parameters = InputParameters::new_from_primitives(20, k, 0, 1);
+    let codomain_function = CodomainFunction::DeceptiveTrapDist { opt_distance };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    for clique_codomain in &codomain {
+        //The local optimum is the only substring with value 1.0, and the deceptive attractor the only one with value 0.9
+        let optimum_indices: Vec<usize> = (0..clique_codomain.len())
+            .filter(|&index| clique_codomain[index] == 1.0)
+            .collect();
+        let deceptor_indices: Vec<usize> = (0..clique_codomain.len())
+            .filter(|&index| clique_codomain[index] == 0.9)
+            .collect();
+        assert_eq!(optimum_indices.len(), 1);
+        assert_eq!(deceptor_indices.len(), 1);
+        assert_eq!(
+            (optimum_indices[0] ^ deceptor_indices[0]).count_ones(),
+            opt_distance
+        );
+
+        //The values decrease from the attractor to 0 at the distance of the optimum
+        let expected_values_by_distance = [0.9, 0.45, 0.0, 0.0, 0.0, 0.0];
+        for (substring_index, &value) in clique_codomain.iter().enumerate() {
+            if substring_index != optimum_indices[0] {
+                let distance = (substring_index ^ deceptor_indices[0]).count_ones() as usize;
+                assert!((value - expected_values_by_distance[distance]).abs() < 1e-9);
+            }
+        }
+    }
+    assert_eq!(codomain_function.to_io_string(), "deceptive-trap-dist-2");
+    assert_eq!(
+        CodomainFunction::from_line("deceptive-trap-dist 2").unwrap(),
+        codomain_function
+    );
+}
+
+#[test]
+fn trap_dist_rejects_opt_distance_larger_than_k() {
+    let input_parameters = InputParameters::new_from_primitives(2, 3, 0, 1);
+    for opt_distance in [0, 4] {
+        assert!(matches!(
+            check_codomain_function(
+                &CodomainFunction::DeceptiveTrapDist { opt_distance },
+                &input_parameters
+            ),
+            Err(ProblemGenError::InvalidParameters(_))
+        ));
+    }
+    assert!(check_codomain_function(
+        &CodomainFunction::from_line("mixture 1:deceptive-trap-dist:4").unwrap(),
+        &input_parameters
+    )
+    .is_err());
+    assert!(check_codomain_function(
+        &CodomainFunction::DeceptiveTrapDist { opt_distance: 3 },
+        &input_parameters
+    )
+    .is_ok());
+
+    //The codomain generator reports the error instead of panicking
+    let output_dir = tempfile::tempdir().unwrap();
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "2",
+        "3",
+        "0",
+        "1",
+        output_dir.path().join("codomain.txt").to_str().unwrap(),
+        "deceptive-trap-dist",
+        "4",
+    ])
+    .unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+}
+
+#[test]
+fn list_codomain_functions_contains_every_variant() {
+    let list = list_codomain_functions();
+    let codomain_functions = [
+        CodomainFunction::Random,
+        CodomainFunction::Trap,
+        CodomainFunction::DeceptiveTrap,
+        CodomainFunction::NKq { q: 4 },
+        CodomainFunction::NKqSigned { q: 4 },
+        CodomainFunction::NKp { p: 0.5 },
+        CodomainFunction::RandomDeceptiveTrap { p_deceptive: 0.5 },
+        CodomainFunction::DeceptiveTrapScaled {
+            deceptor_value: 0.8,
+            optimum_value: 1.0,
+        },
+        CodomainFunction::DeceptiveTrapDist { opt_distance: 2 },
+        CodomainFunction::PlateauTrap { w: 2 },
+        CodomainFunction::StepTrap { step_size: 2 },
+        CodomainFunction::RoyalRoad { block_reward: 2.0 },
+        CodomainFunction::MaxCut {
+            edge_probability: 0.5,
+        },
+        CodomainFunction::GaussianRandom {
+            mean: 1.0,
+            stddev: 0.5,
+        },
+        CodomainFunction::Mixture {
+            components: vec![(1.0, CodomainFunction::Trap)],
+        },
+        CodomainFunction::FromFile {
+            codomain_file_path: "codomain.txt".into(),
+        },
+        CodomainFunction::Custom {
+            name: "all-0.5".to_owned(),
+        },
+    ];
+
+    for codomain_function in &codomain_functions {
+        //The name is the first word of the string representation used in configuration files
+        let display_string = codomain_function.to_string();
+        let name = display_string.split(' ').next().unwrap();
+        assert!(
+            list.lines()
+                .any(|line| line.starts_with(&format!("{} ", name))),
+            "{} is not listed",
+            name
+        );
+    }
+    assert_eq!(list.lines().count(), codomain_functions.len());
+}
+
+#[test]
+fn clique_codomain_regenerated_from_recorded_seed() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 1, 2);
+    let codomain_function = CodomainFunction::Random;
+    let mut rng = get_rng(Some(2398));
+    let (codomain, clique_seeds) =
+        generate_codomain_per_clique(&input_parameters, &codomain_function, &mut rng).unwrap();
+    assert_eq!(codomain.len(), 6);
+    assert_eq!(clique_seeds.len(), 6);
+
+    let clique_codomain =
+        generate_clique_codomain(&input_parameters, &codomain_function, clique_seeds[3]).unwrap();
+    assert_eq!(clique_codomain, codomain[3]);
+    assert_ne!(codomain[2], codomain[3]);
+
+    //The codomain of a single clique can't be read from a codomain file
+    let from_file = CodomainFunction::from_line("from-file codomain.txt").unwrap();
+    assert!(matches!(
+        generate_codomain_per_clique(&input_parameters, &from_file, &mut rng),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        generate_clique_codomain(&input_parameters, &from_file, clique_seeds[3]),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
+
+#[test]
+fn mixture_with_single_trap_component_equals_trap() {
+    let input_parameters = InputParameters::new_from_primitives(5, 4, 1, 2);
+    let codomain_function = CodomainFunction::Mixture {
+        components: vec![(1.0, CodomainFunction::Trap)],
+    };
+    let mut rng = get_rng(Some(2398));
+    let mixture_codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    let trap_codomain = generate_codomain(&input_parameters, &CodomainFunction::Trap, &mut rng);
+    assert_eq!(mixture_codomain, trap_codomain);
+}
+
+#[test]
+fn mixture_string_representations_round_trip() {
+    let codomain_function = CodomainFunction::Mixture {
+        components: vec![
+            (0.25, CodomainFunction::Trap),
+            (0.75, CodomainFunction::NKq { q: 4 }),
+        ],
+    };
+    assert_eq!(
+        codomain_function.to_string(),
+        "mixture 0.25:trap 0.75:nk-q:4"
+    );
+    assert_eq!(
+        codomain_function.to_io_string(),
+        "mixture-0.25-trap-0.75-nk-q-4"
+    );
+    assert_eq!(
+        CodomainFunction::from_line("mixture 0.25:trap 0.75:nk-q:4").unwrap(),
+        codomain_function
+    );
+    assert!(CodomainFunction::from_line("mixture -0.25:trap").is_err());
+    assert!(CodomainFunction::from_line("mixture trap").is_err());
+    assert!(CodomainFunction::from_line("mixture 1:from-file:codomain.txt").is_err());
+    assert!(matches!(
+        CodomainFunction::from_line("mixture 0:trap"),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        CodomainFunction::from_line("mixture"),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
+
+#[test]
+fn royal_road_rewards_only_all_ones_blocks() {
+    let (m, k, block_reward) = (6, 4, 2.5);
+    let input_parameters = InputParameters::new_from_primitives(m, k, 1, 2);
+    let codomain_function = CodomainFunction::RoyalRoad { block_reward };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    for clique_codomain in &codomain {
+        let nonzero_indices: Vec<usize> = clique_codomain
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value != 0.0)
+            .map(|(substring_index, _)| substring_index)
+            .collect();
+        assert_eq!(nonzero_indices, vec![(1 << k) - 1]);
+        assert_eq!(clique_codomain[(1 << k) - 1], block_reward);
+    }
+
+    let clique_tree = CliqueTree::new(
+        input_parameters,
+        codomain_function.clone(),
+        codomain,
+        &mut rng,
+    );
+    let problem_size = (m - 1) * (k - 1) + k;
+    assert_eq!(
+        clique_tree.glob_optima_strings,
+        vec![vec![1; problem_size as usize]]
+    );
+    assert_eq!(clique_tree.glob_optima_score, m as f64 * block_reward);
+
+    assert_eq!(codomain_function.to_io_string(), "royal-road-2.5");
+    assert_eq!(
+        CodomainFunction::from_line("royal-road 2.5").unwrap(),
+        codomain_function
+    );
+}
+
+#[test]
+fn gaussian_random_has_empirical_mean_and_stddev() {
+    let (mean, stddev) = (2.0, 0.5);
+    let input_parameters = InputParameters::new_from_primitives(64, 10, 1, 2);
+    let codomain_function = CodomainFunction::GaussianRandom { mean, stddev };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    let values: Vec<f64> = codomain.into_iter().flatten().collect();
+    assert_eq!(values.len(), 64 << 10);
+    let empirical_mean = values.iter().sum::<f64>() / values.len() as f64;
+    let empirical_variance = values
+        .iter()
+        .map(|value| (value - empirical_mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    assert!((empirical_mean - mean).abs() < 0.01);
+    assert!((empirical_variance.sqrt() - stddev).abs() < 0.01);
+
+    assert_eq!(codomain_function.to_io_string(), "gaussian-2-0.5");
+    assert_eq!(
+        CodomainFunction::from_line("gaussian-random 2 0.5").unwrap(),
+        codomain_function
+    );
+
+    //A negative standard deviation is reported as an error
+    assert!(matches!(
+        CodomainFunction::GaussianRandom {
+            mean,
+            stddev: -stddev
+        }
+        .validate(),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
+
+#[test]
+fn max_cut_single_edge_scores_cut_substrings() {
+    let input_parameters = InputParameters::new_from_primitives(1, 2, 0, 1);
+    let codomain_function = CodomainFunction::MaxCut {
+        edge_probability: 1.0,
+    };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    //00 and 11 do not cut the edge, 01 and 10 do
+    assert_eq!(codomain, vec![vec![0.0, 1.0, 1.0, 0.0]]);
+
+    assert_eq!(codomain_function.to_io_string(), "max-cut-1");
+    assert_eq!(
+        CodomainFunction::from_line("max-cut 1").unwrap(),
+        codomain_function
+    );
+}
+
+#[test]
+fn max_cut_complement_has_same_value() {
+    let k = 6;
+    let input_parameters = InputParameters::new_from_primitives(5, k, 2, 2);
+    let codomain_function = CodomainFunction::MaxCut {
+        edge_probability: 0.5,
+    };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    for clique_codomain in &codomain {
+        for substring_index in 0..(1 << k) {
+            let complement_index = !substring_index & ((1 << k) - 1);
+            assert_eq!(
+                clique_codomain[substring_index],
+                clique_codomain[complement_index]
+            );
+        }
+    }
+}
+
+#[test]
+fn nk_q_signed_generates_unnormalized_integers() {
+    let q = 3;
+    let input_parameters = InputParameters::new_from_primitives(10, 4, 1, 2);
+    let codomain_function = CodomainFunction::NKqSigned { q };
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+
+    let values: Vec<f64> = codomain.into_iter().flatten().collect();
+    assert!(values
+        .iter()
+        .all(|&value| value.fract() == 0.0 && value.abs() <= q as f64));
+    assert!(values.contains(&-(q as f64)));
+    assert!(values.contains(&(q as f64)));
+
+    assert_eq!(codomain_function.to_io_string(), "nk-q-signed-3");
+    assert_eq!(
+        CodomainFunction::from_line("nk-q-signed 3").unwrap(),
+        codomain_function
+    );
+}
+
+#[test]
+fn flat_codomain_equals_nested_codomain() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 1, 2);
+    for codomain_function in [
+        CodomainFunction::NKp { p: 0.25 },
+        CodomainFunction::Random,
+        CodomainFunction::DeceptiveTrap,
+        CodomainFunction::from_line("mixture 1:random 2:deceptive-trap").unwrap(),
+    ] {
+        let codomain = generate_codomain(
+            &input_parameters,
+            &codomain_function,
+            &mut get_rng(Some(2398)),
+        );
+        let (flat_codomain, stride) = generate_codomain_flat(
+            &input_parameters,
+            &codomain_function,
+            &mut get_rng(Some(2398)),
+        );
+
+        assert_eq!(stride, 16);
+        assert_eq!(flat_codomain.len(), 6 * 16);
+        for (clique_index, clique_codomain) in codomain.iter().enumerate() {
+            for (substring_index, value) in clique_codomain.iter().enumerate() {
+                assert_eq!(
+                    flat_codomain[clique_index * stride + substring_index],
+                    *value
+                );
+            }
+        }
+        assert_eq!(nest_flat_codomain(&flat_codomain, stride), codomain);
+    }
+}
+
+#[test]
+fn target_optima_on_separable_problem() {
+    let input_parameters = InputParameters::new_from_primitives(2, 3, 0, 1);
+    let mut rng = get_rng(Some(2398));
+    let (codomain, achieved_optima) =
+        generate_codomain_with_target_optima(&input_parameters, 4, &mut rng);
+    assert_eq!(achieved_optima, 4);
+
+    let clique_tree = CliqueTree::new(
+        input_parameters.clone(),
+        CodomainFunction::Random,
+        codomain,
+        &mut rng,
+    );
+    assert_eq!(clique_tree.glob_optima_strings.len(), 4);
+    assert_eq!(clique_tree.glob_optima_score, 2.0);
+
+    //A prime larger than 2^k can not be achieved with 2 cliques
+    let (_codomain, achieved_optima) =
+        generate_codomain_with_target_optima(&input_parameters, 11, &mut rng);
+    assert_ne!(achieved_optima, 11);
+}
+
+#[test]
+fn clique_invariant_codomains_have_identical_cliques() {
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 1, 2);
+    let mut rng = get_rng(Some(2398));
+
+    for codomain_function in &[
+        CodomainFunction::Trap,
+        CodomainFunction::RoyalRoad { block_reward: 2.0 },
+    ] {
+        assert!(codomain_is_clique_invariant(codomain_function));
+        let codomain = generate_codomain(&input_parameters, codomain_function, &mut rng);
+        let (per_clique_codomain, _clique_seeds) =
+            generate_codomain_per_clique(&input_parameters, codomain_function, &mut rng).unwrap();
+        assert_eq!(codomain.len(), 6);
+        assert!(codomain.iter().all(|clique_codomain| *clique_codomain == codomain[0]));
+        assert_eq!(per_clique_codomain, codomain);
+    }
+
+    let codomain_function = CodomainFunction::Random;
+    assert!(!codomain_is_clique_invariant(&codomain_function));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    for i in 0..codomain.len() {
+        for j in (i + 1)..codomain.len() {
+            assert_ne!(codomain[i], codomain[j]);
+        }
+    }
+}
+
+#[test]
+fn correlated_trap_deceptors_agree_on_separator() {
+    let input_parameters = InputParameters::new_from_primitives(2, 4, 1, 1);
+    let k = input_parameters.k as usize;
+    let mut rng = get_rng(Some(2398));
+    let (cliques, separators) = CliqueTree::construct(&input_parameters, &mut rng);
+    let codomain = generate_correlated_trap(&cliques, &separators, &mut rng);
+
+    //The local deceptor is the only substring with value 0.9, at distance 0 of the deceptor
+    let local_deceptors: Vec<Vec<u32>> = codomain
+        .iter()
+        .map(|clique_codomain| {
+            let deceptor_index = clique_codomain
+                .iter()
+                .position(|&value| (value - 0.9).abs() < 1e-9)
+                .unwrap();
+            (0..k)
+                .map(|j| (deceptor_index >> (k - j - 1)) as u32 & 1)
+                .collect()
+        })
+        .collect();
+
+    let separator_variable = separators[1][0];
+    let parent_position = cliques[0].iter().position(|&v| v == separator_variable).unwrap();
+    let child_position = cliques[1].iter().position(|&v| v == separator_variable).unwrap();
+    assert_eq!(
+        local_deceptors[0][parent_position],
+        local_deceptors[1][child_position]
+    );
+
+    //As the deceptors agree, the inverses of the deceptors form the global optimum with the highest value in every clique
+    let global_optima = CliqueTree::calculate_global_optima(
+        &input_parameters,
+        &CodomainFunction::Unknown,
+        &codomain,
+        &cliques,
+        &separators,
+        DEFAULT_FITNESS_EPSILON,
+    );
+    assert_eq!(global_optima.len(), 1);
+    assert!((global_optima[0].1 - 2.0).abs() < 1e-9);
+    for (clique, local_deceptor) in cliques.iter().zip(&local_deceptors) {
+        for (&variable_index, &deceptor_bit) in clique.iter().zip(local_deceptor) {
+            assert_eq!(global_optima[0].0[variable_index as usize], 1 - deceptor_bit);
+        }
+    }
+}
+
+#[test]
+fn planted_target_is_unique_global_optimum() {
+    let mut rng = get_rng(Some(2398));
+    for codomain_function in [
+        CodomainFunction::Random,
+        CodomainFunction::DeceptiveTrap,
+        CodomainFunction::NKq { q: 2 },
+    ] {
+        for &(m, k, o, b) in &[(8, 4, 2, 2), (6, 3, 0, 1), (10, 5, 1, 3)] {
+            let input_parameters = InputParameters::new_from_primitives(m, k, o, b);
+            let target: Vec<u32> = (0..input_parameters.problem_size())
+                .map(|_| rng.gen_range(0..2))
+                .collect();
+
+            let clique_tree =
+                generate_with_planted_optimum(&input_parameters, &target, &codomain_function, &mut rng)
+                    .unwrap();
+
+            assert_eq!(clique_tree.glob_optima_strings, vec![target.clone()]);
+            let mut number_evaluations = 0;
+            assert!(
+                (clique_tree.calculate_fitness(&target, &mut number_evaluations)
+                    - clique_tree.glob_optima_score)
+                    .abs()
+                    < 1e-9
+            );
+        }
+    }
+
+    //The target should have the length of the problem size
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 1, 2);
+    assert!(generate_with_planted_optimum(
+        &input_parameters,
+        &[0, 1, 0],
+        &CodomainFunction::Random,
+        &mut rng
+    )
+    .is_err());
+}
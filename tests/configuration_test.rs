@@ -0,0 +1,237 @@
+use std::fs;
+
+use rand::RngCore;
+
+use problem_generator::problem::{
+    clique_tree::InputParameters,
+    codomain_subclasses::CodomainFunction,
+    configuration::{
+        get_rng, get_rng_logged, shuffle, ConfigurationParameters, OverlapFraction, ParameterSteps,
+    },
+    error::ProblemGenError,
+};
+
+const PROBLEM_SIZE_CONFIGURATION: &str = "N 10 200
+k 5 6
+o 1 2
+b 1 3
+deceptive-trap
+";
+
+fn read_configuration(contents: &str) -> ConfigurationParameters {
+    read_configuration_file("configuration.txt", contents).unwrap()
+}
+
+fn read_configuration_file(
+    file_name: &str,
+    contents: &str,
+) -> Result<ConfigurationParameters, ProblemGenError> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let configuration_file_path = temp_dir.path().join(file_name);
+    fs::write(&configuration_file_path, contents).unwrap();
+    ConfigurationParameters::from_file(&configuration_file_path)
+}
+
+#[test]
+fn max_configurations_caps_iteration() {
+    let uncapped_configurations: Vec<_> = read_configuration(PROBLEM_SIZE_CONFIGURATION)
+        .into_iter()
+        .collect();
+    assert!(uncapped_configurations.len() > 7);
+
+    let configuration_parameters = read_configuration(&format!(
+        "{}max_configurations 7\n",
+        PROBLEM_SIZE_CONFIGURATION
+    ));
+    assert_eq!(configuration_parameters.max_configurations, Some(7));
+    let capped_configurations: Vec<_> = configuration_parameters.into_iter().collect();
+
+    assert_eq!(capped_configurations, uncapped_configurations[..7]);
+}
+
+#[test]
+fn omitting_max_configurations_iterates_all_configurations() {
+    let configuration_parameters = read_configuration(PROBLEM_SIZE_CONFIGURATION);
+    assert_eq!(configuration_parameters.max_configurations, None);
+
+    //M from 3 up to (excluding) 50, with two values of b each
+    let m_range = configuration_parameters.m_end - configuration_parameters.m_begin;
+    assert_eq!(
+        configuration_parameters.into_iter().count(),
+        (m_range * 2) as usize
+    );
+}
+
+#[test]
+fn shuffle_matches_recorded_permutation() {
+    let mut values: Vec<u32> = (0..10).collect();
+    shuffle(&mut values, &mut get_rng(Some(2398)));
+    assert_eq!(values, vec![7, 9, 6, 2, 0, 8, 4, 1, 3, 5]);
+}
+
+#[test]
+fn toml_configuration_equals_line_based_configuration() {
+    let line_based_configuration = read_configuration("M 3 5\nk 3 5\no 1 2\nb 1 3\nnk-q 4\n");
+    let toml_configuration = read_configuration_file(
+        "configuration.toml",
+        r#"
+codomain_functions = ["nk-q 4"]
+
+[ranges]
+m = { begin = 3, end = 5 }
+k = { begin = 3, end = 5 }
+o = { begin = 1, end = 2 }
+b = { begin = 1, end = 3 }
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(toml_configuration, line_based_configuration);
+    assert_eq!(
+        toml_configuration.codomain_function,
+        CodomainFunction::NKq { q: 4 }
+    );
+}
+
+#[test]
+fn toml_configuration_with_steps_and_max_configurations() {
+    let configuration_parameters = read_configuration_file(
+        "configuration.toml",
+        r#"
+codomain_functions = ["deceptive-trap"]
+max_configurations = 5
+
+[ranges.m]
+begin = 2
+end = 9
+step = 3
+
+[ranges.k]
+begin = 4
+end = 5
+
+[ranges.o]
+begin = 0
+end = 3
+step = 2
+
+[ranges.b]
+begin = 1
+end = 2
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(configuration_parameters.max_configurations, Some(5));
+    assert_eq!(
+        configuration_parameters.steps,
+        ParameterSteps {
+            m: 3,
+            k: 1,
+            o: 2,
+            b: 1
+        }
+    );
+    let configurations: Vec<InputParameters> = configuration_parameters.into_iter().collect();
+    assert_eq!(
+        configurations,
+        vec![
+            InputParameters::new_from_primitives(2, 4, 0, 1),
+            InputParameters::new_from_primitives(2, 4, 2, 1),
+            InputParameters::new_from_primitives(5, 4, 0, 1),
+            InputParameters::new_from_primitives(5, 4, 2, 1),
+            InputParameters::new_from_primitives(8, 4, 0, 1),
+        ]
+    );
+}
+
+#[test]
+fn malformed_toml_configuration_is_rejected() {
+    //Missing range for b
+    let result = read_configuration_file(
+        "configuration.toml",
+        "codomain_functions = [\"trap\"]\n[ranges]\nm = { begin = 3, end = 5 }\nk = { begin = 3, end = 5 }\no = { begin = 1, end = 2 }\n",
+    );
+    assert!(matches!(result, Err(ProblemGenError::Parse(_))));
+
+    //Two codomain functions
+    let result = read_configuration_file(
+        "configuration.toml",
+        "codomain_functions = [\"trap\", \"random\"]\n[ranges]\nm = { begin = 3, end = 5 }\nk = { begin = 3, end = 5 }\no = { begin = 1, end = 2 }\nb = { begin = 1, end = 2 }\n",
+    );
+    assert!(matches!(result, Err(ProblemGenError::InvalidParameters(_))));
+}
+
+///Get the (k, o) pairs of the configuration with a single value of M and b
+fn get_k_o_pairs(configuration_parameters: ConfigurationParameters) -> Vec<(u32, u32)> {
+    configuration_parameters
+        .into_iter()
+        .map(|input_parameters| (input_parameters.k, input_parameters.o))
+        .collect()
+}
+
+#[test]
+fn fractional_overlap_expands_to_absolute_o_per_k() {
+    let configuration_parameters =
+        read_configuration("M 2 3\nk 2 7\no 0.0 0.5\nb 1 2\ndeceptive-trap\n");
+    assert_eq!(
+        configuration_parameters.o_fraction,
+        Some(OverlapFraction {
+            begin: 0.0,
+            end: 0.5
+        })
+    );
+    assert_eq!(
+        get_k_o_pairs(configuration_parameters),
+        vec![
+            (2, 0),
+            (3, 0),
+            (3, 1),
+            (4, 0),
+            (4, 1),
+            (5, 0),
+            (5, 1),
+            (5, 2),
+            (6, 0),
+            (6, 1),
+            (6, 2)
+        ]
+    );
+
+    //The values of k without any o in the fractional range are skipped, and o always stays smaller than k
+    let configuration_parameters =
+        read_configuration("M 2 3\nk 2 6\no 0.6 1.0\nb 1 2\ndeceptive-trap\n");
+    assert_eq!(
+        get_k_o_pairs(configuration_parameters),
+        vec![(3, 2), (4, 3), (5, 3), (5, 4)]
+    );
+}
+
+#[test]
+fn invalid_fractional_overlap_is_rejected() {
+    assert!(matches!(
+        read_configuration_file(
+            "configuration.txt",
+            "M 2 3\nk 2 6\no 0.0 1.5\nb 1 2\ndeceptive-trap\n"
+        ),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        read_configuration_file(
+            "configuration.txt",
+            "N 10 20\nk 5 6\no 0.2 0.4\nb 1 2\ndeceptive-trap\n"
+        ),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
+
+#[test]
+fn logged_seed_reproduces_random_stream() {
+    let (mut rng, seed) = get_rng_logged(None);
+    let mut reproduced_rng = get_rng(Some(seed));
+    let values: Vec<u64> = (0..8).map(|_| rng.next_u64()).collect();
+    let reproduced_values: Vec<u64> = (0..8).map(|_| reproduced_rng.next_u64()).collect();
+    assert_eq!(values, reproduced_values);
+
+    assert_eq!(get_rng_logged(Some(2398)).1, 2398);
+}
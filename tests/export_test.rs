@@ -0,0 +1,135 @@
+use std::{collections::HashSet, fs};
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters},
+    codomain::generate_codomain,
+    codomain_subclasses::CodomainFunction,
+    configuration::get_rng,
+    error::ProblemGenError,
+    export::GraphFormat,
+};
+
+fn generate_clique_tree(codomain_function: CodomainFunction) -> CliqueTree {
+    let input_parameters = InputParameters::new_from_primitives(3, 3, 1, 2);
+    let mut rng = get_rng(Some(2398));
+    let codomain = generate_codomain(&input_parameters, &codomain_function, &mut rng);
+    CliqueTree::new(input_parameters, codomain_function, codomain, &mut rng)
+}
+
+#[test]
+fn nk_q_wcnf_export_counts() {
+    let q = 4;
+    let clique_tree = generate_clique_tree(CodomainFunction::NKq { q });
+    let temp_dir = tempfile::tempdir().unwrap();
+    let wcnf_file_path = temp_dir.path().join("problem.wcnf");
+    clique_tree.to_wcnf(&wcnf_file_path).unwrap();
+
+    let contents = fs::read_to_string(wcnf_file_path).unwrap();
+    let mut lines = contents.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(' ').collect();
+    let clauses: Vec<Vec<i64>> = lines
+        .map(|line| {
+            line.split(' ')
+                .map(|value| value.parse().unwrap())
+                .collect()
+        })
+        .collect();
+
+    //Every substring with a value below the highest value of its clique gives one clause
+    let expected_number_clauses: usize = clique_tree
+        .codomain_values
+        .iter()
+        .map(|clique_codomain| {
+            let highest_value = clique_codomain.iter().cloned().fold(f64::MIN, f64::max);
+            clique_codomain
+                .iter()
+                .filter(|&&value| value < highest_value)
+                .count()
+        })
+        .sum();
+    assert_eq!(header[..2], ["p", "wcnf"]);
+    assert_eq!(header[2], "7");
+    assert_eq!(header[3], expected_number_clauses.to_string());
+    assert_eq!(clauses.len(), expected_number_clauses);
+    for clause in &clauses {
+        //Weight, k literals, and the terminating 0
+        assert_eq!(clause.len(), 5);
+        assert_eq!(clause[4], 0);
+    }
+
+    //The weight of the clauses falsified by a global optimum is the distance of its fitness to the sum of clique maxima
+    let global_optimum = &clique_tree.glob_optima_strings[0];
+    let falsified_weight: i64 = clauses
+        .iter()
+        .filter(|clause| {
+            clause[1..4].iter().all(|&literal| {
+                let bit = global_optimum[literal.unsigned_abs() as usize - 1];
+                (literal > 0) == (bit == 0)
+            })
+        })
+        .map(|clause| clause[0])
+        .sum();
+    let sum_clique_maxima: f64 = clique_tree
+        .codomain_values
+        .iter()
+        .map(|clique_codomain| clique_codomain.iter().cloned().fold(f64::MIN, f64::max))
+        .sum();
+    let expected_weight = (sum_clique_maxima - clique_tree.glob_optima_score) * (q - 1) as f64;
+    assert_eq!(falsified_weight, expected_weight.round() as i64);
+}
+
+#[test]
+fn non_integer_codomain_wcnf_export_fails() {
+    let clique_tree = generate_clique_tree(CodomainFunction::Random);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let result = clique_tree.to_wcnf(&temp_dir.path().join("problem.wcnf"));
+    assert!(matches!(result, Err(ProblemGenError::InvalidParameters(_))));
+}
+
+#[test]
+fn interaction_graph_export_edge_counts() {
+    let clique_tree = generate_clique_tree(CodomainFunction::Random);
+    //The cliques overlap, so pairs of separator variables occur in multiple cliques and are only counted once
+    let intra_clique_pairs: HashSet<(u32, u32)> = clique_tree
+        .cliques
+        .iter()
+        .flat_map(|clique| {
+            clique.iter().flat_map(move |&u| {
+                clique
+                    .iter()
+                    .filter(move |&&v| u < v)
+                    .map(move |&v| (u, v))
+            })
+        })
+        .collect();
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let edge_list_file_path = temp_dir.path().join("problem.edgelist");
+    clique_tree
+        .write_interaction_graph(&edge_list_file_path, GraphFormat::EdgeList)
+        .unwrap();
+    let edges: HashSet<(u32, u32)> = fs::read_to_string(edge_list_file_path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let (u, v) = line.split_once(' ').unwrap();
+            (u.parse().unwrap(), v.parse().unwrap())
+        })
+        .collect();
+    assert_eq!(edges, intra_clique_pairs);
+
+    let dimacs_file_path = temp_dir.path().join("problem.dimacs");
+    clique_tree
+        .write_interaction_graph(&dimacs_file_path, GraphFormat::Dimacs)
+        .unwrap();
+    let contents = fs::read_to_string(dimacs_file_path).unwrap();
+    let mut lines = contents.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!("p edge 7 {}", intra_clique_pairs.len())
+    );
+    assert_eq!(lines.filter(|line| line.starts_with("e ")).count(), intra_clique_pairs.len());
+
+    assert_eq!("dimacs".parse(), Ok(GraphFormat::Dimacs));
+    assert!("dot".parse::<GraphFormat>().is_err());
+}
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 use std::fs;
+use std::process::Command;
 
 use problem_generator::{self, problem::problem_generation::{run_opt, ProblemOpt, ProblemCommand::ConfigurationFolder}};
 
@@ -13,8 +14,13 @@ fn deceptive_trap_generated() {
     };
 
     let problem_opt = ProblemOpt {
-        problem_command: problem_command,
-        seed: Some(2398)
+        problem_command,
+        seed: Some(2398),
+        quiet: false,
+        output_dir: None,
+        codomain_dir: None,
+        problem_dir: None,
+        generation_options: Default::default(),
     };
 
     run_opt(problem_opt).unwrap();
@@ -86,4 +92,23 @@ fn deceptive_trap_generated() {
         .expect("Could not read codomain result from problem generation");
 
     assert_eq!(codomain_actual_output, codomain_expected_output);
-}
\ No newline at end of file
+}
+
+#[test]
+fn quiet_generation_prints_nothing() {
+    let output_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = output_dir.path().join("codomain.txt");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_codomain_generator"))
+        .args(["--quiet", "-s", "2398", "instance", "4", "3", "1", "2"])
+        .arg(&codomain_file_path)
+        .arg("random")
+        .env("RUST_LOG", "debug")
+        .output()
+        .expect("Could not run the codomain generator");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+    assert!(codomain_file_path.exists());
+}
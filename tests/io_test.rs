@@ -0,0 +1,747 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+
+use structopt::StructOpt;
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters},
+    codomain::{
+        self, read_codomain, read_codomain_buffered, read_codomain_from_reader,
+        read_codomain_values_only, read_codomain_with_known_parameters, write_codomain_to_writer, CodomainCommand, CodomainOpt,
+        GenerationOptions, DEFAULT_FLUSH_EVERY,
+    },
+    codomain_subclasses::CodomainFunction,
+    configuration::get_rng,
+    error::ProblemGenError,
+    io::{
+        get_clique_tree_from_codomain_file_with_parameters,
+        get_clique_trees_paths_from_codomain_folder, get_configuration_files,
+        get_folders_file_triples,
+    },
+    problem_generation::{self, ProblemOpt},
+};
+
+const SMALL_EXAMPLE_CODOMAIN: &str =
+    "./data/small_example/codomain_files/deceptive-trap_10_5_1_2_0.txt";
+
+#[test]
+fn load_generated_codomain_file() {
+    let clique_tree = CliqueTree::load(Path::new(SMALL_EXAMPLE_CODOMAIN), Some(2398)).unwrap();
+
+    assert_eq!(
+        clique_tree.input_parameters,
+        InputParameters::new_from_primitives(10, 5, 1, 2)
+    );
+    assert_eq!(
+        clique_tree.codomain_function,
+        CodomainFunction::DeceptiveTrap
+    );
+    assert_eq!(clique_tree.codomain_values.len(), 10);
+    assert_eq!(clique_tree.codomain_values[0][0], 0.36);
+}
+
+#[test]
+fn try_from_codomain_file_without_codomain_function() {
+    //Strip the codomain function line from the generated codomain file
+    let contents = fs::read_to_string(SMALL_EXAMPLE_CODOMAIN).unwrap();
+    let contents_without_function: String = contents
+        .lines()
+        .skip(1)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    fs::write(&codomain_file_path, contents_without_function).unwrap();
+
+    let clique_tree = CliqueTree::try_from(codomain_file_path.as_path()).unwrap();
+    let generated_clique_tree =
+        CliqueTree::load(Path::new(SMALL_EXAMPLE_CODOMAIN), Some(2398)).unwrap();
+
+    assert_eq!(clique_tree.codomain_function, CodomainFunction::Unknown);
+    assert_eq!(
+        clique_tree.codomain_values,
+        generated_clique_tree.codomain_values
+    );
+}
+
+///Write the contents to a codomain file in a temporary directory, and try to load it
+fn load_codomain_contents(contents: &str) -> Result<CliqueTree, ProblemGenError> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    fs::write(&codomain_file_path, contents).unwrap();
+    CliqueTree::load(&codomain_file_path, Some(2398))
+}
+
+#[test]
+fn load_codomain_file_with_malformed_parameters() {
+    let result = load_codomain_contents("deceptive-trap\n2 2 x 1\n0.5\n");
+    assert!(matches!(result, Err(ProblemGenError::Parse(_))));
+
+    let result = load_codomain_contents("deceptive-trap\n2 2 0\n0.5\n");
+    assert!(matches!(result, Err(ProblemGenError::InvalidParameters(_))));
+}
+
+#[test]
+fn load_codomain_file_with_unknown_codomain_function() {
+    let result = load_codomain_contents("not-a-function\n1 1 0 1\n0.5\n0.2\n");
+    assert!(matches!(
+        result,
+        Err(ProblemGenError::UnknownCodomainFunction(_))
+    ));
+}
+
+#[test]
+fn load_codomain_file_with_too_few_values() {
+    let result = load_codomain_contents("deceptive-trap\n2 2 0 1\n0.5\n0.2\n0.1\n");
+    assert!(matches!(
+        result,
+        Err(ProblemGenError::CodomainDimensionMismatch {
+            expected: 8,
+            found: 3
+        })
+    ));
+}
+
+///Generate a codomain file with a checksum header, and return its contents
+fn generate_codomain_file_with_checksum() -> String {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    let codomain_opt = CodomainOpt {
+        codomain_command: CodomainCommand::Instance {
+            m: 4,
+            k: 3,
+            o: 1.0,
+            overlap_ratio: false,
+            b: 2,
+            output_file_path: codomain_file_path.clone(),
+            codomain_function: CodomainFunction::Random,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: None,
+        skip_existing: false,
+        generation_options: GenerationOptions {
+            codomain_checksum: true,
+            ..Default::default()
+        },
+    };
+    codomain::run_opt(codomain_opt).unwrap();
+    fs::read_to_string(codomain_file_path).unwrap()
+}
+
+#[test]
+fn codomain_file_with_checksum_is_verified() {
+    let contents = generate_codomain_file_with_checksum();
+    assert!(contents.starts_with("#codomain-file 1 "));
+
+    let clique_tree = load_codomain_contents(&contents).unwrap();
+    assert_eq!(clique_tree.codomain_function, CodomainFunction::Random);
+    assert_eq!(clique_tree.codomain_values.len(), 4);
+
+    //Without the header, the file is read as version 0, without checksum
+    let contents_without_header: String = contents
+        .lines()
+        .skip(1)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let clique_tree_without_header = load_codomain_contents(&contents_without_header).unwrap();
+    assert_eq!(
+        clique_tree.codomain_values,
+        clique_tree_without_header.codomain_values
+    );
+}
+
+#[test]
+fn corrupted_codomain_file_fails_checksum() {
+    let contents = generate_codomain_file_with_checksum();
+    //Replace the first codomain value, on the line after the header, codomain function and input parameters
+    let corrupted_contents: String = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 3 {
+                "0.5\n".to_owned()
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect();
+
+    let result = load_codomain_contents(&corrupted_contents);
+    assert!(matches!(
+        result,
+        Err(ProblemGenError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn truncated_codomain_file_with_checksum_is_detected() {
+    let contents = generate_codomain_file_with_checksum();
+    let truncated_contents: String = contents
+        .lines()
+        .take(10)
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    let result = load_codomain_contents(&truncated_contents);
+    assert!(matches!(
+        result,
+        Err(ProblemGenError::TruncatedFile {
+            expected: 32,
+            found: 7
+        })
+    ));
+}
+
+#[test]
+fn buffered_codomain_reading_equals_reading_whole_file() {
+    let contents = generate_codomain_file_with_checksum();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    let input_parameters = InputParameters::new_from_primitives(4, 3, 1, 2);
+
+    //With header
+    fs::write(&codomain_file_path, &contents).unwrap();
+    assert_eq!(
+        read_codomain_buffered(&input_parameters, &codomain_file_path, 2).unwrap(),
+        read_codomain(&input_parameters, &codomain_file_path, 2).unwrap()
+    );
+
+    //Without header
+    let contents_without_header: String = contents
+        .lines()
+        .skip(1)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    fs::write(&codomain_file_path, &contents_without_header).unwrap();
+    assert_eq!(
+        read_codomain_buffered(&input_parameters, &codomain_file_path, 2).unwrap(),
+        read_codomain(&input_parameters, &codomain_file_path, 2).unwrap()
+    );
+
+    //Truncated with header
+    let truncated_contents: String = contents
+        .lines()
+        .take(10)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    fs::write(&codomain_file_path, &truncated_contents).unwrap();
+    assert!(matches!(
+        read_codomain_buffered(&input_parameters, &codomain_file_path, 2),
+        Err(ProblemGenError::TruncatedFile {
+            expected: 32,
+            found: 7
+        })
+    ));
+}
+
+#[test]
+fn values_only_codomain_is_read_regardless_of_layout() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    let input_parameters = InputParameters::new_from_primitives(2, 2, 1, 1);
+
+    //Eight values on three lines, with extra whitespace and trailing blank lines
+    fs::write(
+        &codomain_file_path,
+        "0.1 0.2  0.3\n\t0.4 0.5\n0.6 0.7   0.8  \n\n\n",
+    )
+    .unwrap();
+    assert_eq!(
+        read_codomain_values_only(&codomain_file_path, &input_parameters).unwrap(),
+        vec![vec![0.1, 0.2, 0.3, 0.4], vec![0.5, 0.6, 0.7, 0.8]]
+    );
+
+    fs::write(&codomain_file_path, "0.1 0.2 0.3 0.4 0.5").unwrap();
+    assert!(matches!(
+        read_codomain_values_only(&codomain_file_path, &input_parameters),
+        Err(ProblemGenError::CodomainDimensionMismatch {
+            expected: 8,
+            found: 5
+        })
+    ));
+}
+
+#[test]
+fn codomain_from_file_is_attached_to_generated_structure() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let values_file_path = temp_dir.path().join("published.txt");
+    fs::write(&values_file_path, "0.1 0.2 0.3 0.4\n0.5 0.6 0.7 0.8\n").unwrap();
+    let input_parameters = InputParameters::new_from_primitives(2, 2, 1, 1);
+
+    let mut rng = get_rng(Some(2398));
+    let clique_tree =
+        CliqueTree::from_parameters_and_codomain_file(input_parameters.clone(), &values_file_path, &mut rng)
+            .unwrap();
+    //The all-ones string takes the last value of every clique
+    let mut number_evaluations = 0;
+    let fitness = clique_tree.calculate_fitness(&[1, 1, 1], &mut number_evaluations);
+    assert!((fitness - (0.4 + 0.8)).abs() < 1e-12);
+
+    //The same codomain file can be used from the command line
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "2",
+        "2",
+        "1",
+        "1",
+        codomain_file_path.to_str().unwrap(),
+        "from-file",
+        values_file_path.to_str().unwrap(),
+    ])
+    .unwrap();
+    codomain::run_opt(codomain_opt).unwrap();
+    let loaded_clique_tree = CliqueTree::load(&codomain_file_path, Some(2398)).unwrap();
+    assert_eq!(loaded_clique_tree.codomain_values, clique_tree.codomain_values);
+    assert_eq!(
+        loaded_clique_tree.codomain_function,
+        CodomainFunction::FromFile {
+            codomain_file_path: values_file_path.clone()
+        }
+    );
+
+    //A codomain file with the wrong number of values is reported as an error
+    assert!(CliqueTree::from_parameters_and_codomain_file(
+        InputParameters::new_from_primitives(3, 2, 1, 1),
+        &values_file_path,
+        &mut rng
+    )
+    .is_err());
+}
+
+#[test]
+fn unreadable_codomain_file_in_folder_returns_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let configuration = format!(
+        "M 2 3\nk 2 3\no 1 2\nb 1 2\nfrom-file {}\n",
+        temp_dir.path().join("missing.txt").display()
+    );
+    for folder_name in &["codomain_generation", "problem_generation"] {
+        fs::create_dir(temp_dir.path().join(folder_name)).unwrap();
+        fs::write(temp_dir.path().join(folder_name).join("published.txt"), &configuration).unwrap();
+    }
+
+    let codomain_opt = CodomainOpt::folder(vec![temp_dir.path().to_path_buf()], Some(2398));
+    assert!(codomain::run_opt(codomain_opt).is_err());
+
+    let problem_opt = ProblemOpt::configuration_folder(vec![temp_dir.path().to_path_buf()], 1, Some(2398));
+    assert!(problem_generation::run_opt(problem_opt).is_err());
+}
+
+///Reader that generates the lines of a codomain file on the fly, so that the file contents never exist in memory as a whole.
+/// The value on line i is (i % 1000) / 1000.
+struct SyntheticCodomainReader {
+    number_of_values: usize,
+    next_value: usize,
+    pending_line: Vec<u8>,
+    max_read_size: usize,
+}
+
+impl Read for SyntheticCodomainReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.max_read_size = self.max_read_size.max(buffer.len());
+        if self.pending_line.is_empty() {
+            if self.next_value == self.number_of_values {
+                return Ok(0);
+            }
+            self.pending_line =
+                format!("{}\n", (self.next_value % 1000) as f64 / 1000.0).into_bytes();
+            self.next_value += 1;
+        }
+        let size = buffer.len().min(self.pending_line.len());
+        buffer[..size].copy_from_slice(&self.pending_line[..size]);
+        self.pending_line.drain(..size);
+        Ok(size)
+    }
+}
+
+#[test]
+fn read_large_synthetic_codomain_with_bounded_buffer() {
+    let input_parameters = InputParameters::new_from_primitives(256, 10, 1, 2);
+    let number_of_values = 256 << 10;
+    let mut synthetic_reader = SyntheticCodomainReader {
+        number_of_values,
+        next_value: 0,
+        pending_line: Vec::new(),
+        max_read_size: 0,
+    };
+
+    let codomain = read_codomain_from_reader(
+        BufReader::with_capacity(64, &mut synthetic_reader),
+        &input_parameters,
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(codomain.len(), 256);
+    for (clique_index, clique_codomain) in codomain.iter().enumerate() {
+        assert_eq!(clique_codomain.len(), 1 << 10);
+        for (substring_index, &value) in clique_codomain.iter().enumerate() {
+            let line_index = (clique_index << 10) + substring_index;
+            assert_eq!(value, (line_index % 1000) as f64 / 1000.0);
+        }
+    }
+    //All values are read, but never more than the buffer capacity at a time
+    assert_eq!(synthetic_reader.next_value, number_of_values);
+    assert!(synthetic_reader.max_read_size <= 64);
+}
+
+#[test]
+fn clique_tree_bytes_round_trip_without_recalculating_optima() {
+    let mut clique_tree = CliqueTree::new_with_seeds(
+        InputParameters::new_from_primitives(10, 5, 2, 2),
+        CodomainFunction::NKq { q: 4 },
+        2398,
+        2399,
+    );
+    //The stored global optima are used as is, so a changed score is preserved by the round trip
+    clique_tree.glob_optima_score += 1.0;
+
+    let reloaded_clique_tree = CliqueTree::from_bytes(&clique_tree.to_bytes()).unwrap();
+
+    assert_eq!(
+        reloaded_clique_tree.input_parameters,
+        clique_tree.input_parameters
+    );
+    assert_eq!(
+        reloaded_clique_tree.codomain_function,
+        clique_tree.codomain_function
+    );
+    assert_eq!(reloaded_clique_tree.cliques, clique_tree.cliques);
+    assert_eq!(reloaded_clique_tree.separators, clique_tree.separators);
+    assert_eq!(
+        reloaded_clique_tree.codomain_values,
+        clique_tree.codomain_values
+    );
+    assert_eq!(
+        reloaded_clique_tree.glob_optima_strings,
+        clique_tree.glob_optima_strings
+    );
+    assert_eq!(
+        reloaded_clique_tree.glob_optima_score,
+        clique_tree.glob_optima_score
+    );
+    assert_eq!(
+        reloaded_clique_tree.fitness_epsilon,
+        clique_tree.fitness_epsilon
+    );
+}
+
+#[test]
+fn malformed_clique_tree_bytes_are_rejected() {
+    let clique_tree = CliqueTree::new_with_seeds(
+        InputParameters::new_from_primitives(3, 3, 1, 2),
+        CodomainFunction::DeceptiveTrap,
+        2398,
+        2399,
+    );
+    let bytes = clique_tree.to_bytes();
+
+    assert!(matches!(
+        CliqueTree::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ProblemGenError::Parse(_))
+    ));
+    assert!(matches!(
+        CliqueTree::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+        Err(ProblemGenError::Parse(_))
+    ));
+    assert!(matches!(
+        CliqueTree::from_bytes(b"not a clique tree"),
+        Err(ProblemGenError::Parse(_))
+    ));
+}
+
+#[test]
+fn o_per_level_is_kept_in_bytes_and_rejected_in_text_files() {
+    let input_parameters = InputParameters::new_from_primitives(5, 4, 1, 2)
+        .with_o_per_level(vec![2, 1])
+        .unwrap();
+    let clique_tree =
+        CliqueTree::new_with_seeds(input_parameters.clone(), CodomainFunction::Random, 2398, 2399);
+    let uniform_clique_tree = CliqueTree::new_with_seeds(
+        InputParameters::new_from_primitives(5, 4, 1, 2),
+        CodomainFunction::Random,
+        2398,
+        2399,
+    );
+    assert_ne!(clique_tree.content_hash(), uniform_clique_tree.content_hash());
+
+    let reloaded_clique_tree = CliqueTree::from_bytes(&clique_tree.to_bytes()).unwrap();
+    assert_eq!(reloaded_clique_tree.input_parameters, input_parameters);
+    assert_eq!(reloaded_clique_tree.get_problem_size(), 14);
+    assert_eq!(reloaded_clique_tree.content_hash(), clique_tree.content_hash());
+
+    //The parameters line of the problem and codomain files has no room for o per level
+    let temp_dir = tempfile::tempdir().unwrap();
+    let problem_file_path = temp_dir.path().join("problem.txt");
+    assert!(clique_tree.write_problem_file(&problem_file_path).is_err());
+    assert!(!problem_file_path.exists());
+    let mut bytes = Vec::new();
+    assert!(write_codomain_to_writer(
+        &mut bytes,
+        &input_parameters,
+        &CodomainFunction::Random,
+        &clique_tree.codomain_values,
+        false,
+        DEFAULT_FLUSH_EVERY,
+    )
+    .is_err());
+}
+
+#[test]
+fn codomain_folder_files_are_sorted_numerically() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    for instance_number in [11, 2, 10] {
+        fs::copy(
+            SMALL_EXAMPLE_CODOMAIN,
+            temp_dir
+                .path()
+                .join(format!("deceptive-trap_10_5_1_2_{}.txt", instance_number)),
+        )
+        .unwrap();
+    }
+
+    let mut rng = get_rng(Some(2398));
+    let clique_trees_paths =
+        get_clique_trees_paths_from_codomain_folder(temp_dir.path(), true, &mut rng).unwrap();
+    let file_names: Vec<String> = clique_trees_paths
+        .iter()
+        .map(|(_, path)| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+
+    assert_eq!(
+        file_names,
+        vec![
+            "deceptive-trap_10_5_1_2_2.txt",
+            "deceptive-trap_10_5_1_2_10.txt",
+            "deceptive-trap_10_5_1_2_11.txt",
+        ]
+    );
+}
+
+///Create a folder with the problem_generation, codomain_files and problems folders for the passed configuration names
+fn create_folder_with_configurations(folder_path: &Path, configuration_names: &[&str]) {
+    for configuration_name in configuration_names {
+        let problem_generation_folder = folder_path.join("problem_generation");
+        fs::create_dir_all(&problem_generation_folder).unwrap();
+        fs::write(
+            problem_generation_folder.join(format!("{}.txt", configuration_name)),
+            "",
+        )
+        .unwrap();
+        fs::create_dir_all(folder_path.join("codomain_files").join(configuration_name)).unwrap();
+        fs::create_dir_all(folder_path.join("problems").join(configuration_name)).unwrap();
+    }
+}
+
+#[test]
+fn folders_file_triples_are_matched_by_name() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    //"trap-large.txt" sorts before "trap.txt", while the folder "trap" sorts before "trap-large"
+    create_folder_with_configurations(temp_dir.path(), &["trap", "trap-large"]);
+    fs::write(temp_dir.path().join("codomain_files").join(".DS_Store"), "").unwrap();
+
+    let folder_file_triples = get_folders_file_triples(temp_dir.path(), None, None, false).unwrap();
+
+    assert_eq!(folder_file_triples.len(), 2);
+    for (configuration_file, codomain_folder, problem_folder) in folder_file_triples {
+        let configuration_name = configuration_file.file_stem().unwrap();
+        assert_eq!(codomain_folder.file_name().unwrap(), configuration_name);
+        assert_eq!(problem_folder.file_name().unwrap(), configuration_name);
+    }
+}
+
+#[test]
+fn folders_file_triples_with_missing_folder_return_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    create_folder_with_configurations(temp_dir.path(), &["trap", "trap-large"]);
+    fs::remove_dir(temp_dir.path().join("problems").join("trap-large")).unwrap();
+
+    assert!(get_folders_file_triples(temp_dir.path(), None, None, false).is_err());
+}
+
+#[test]
+fn empty_configuration_folders_return_descriptive_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    for folder_name in &["problem_generation", "codomain_generation", "codomain_files", "problems"] {
+        fs::create_dir(temp_dir.path().join(folder_name)).unwrap();
+    }
+    fs::write(temp_dir.path().join("problem_generation").join(".DS_Store"), "").unwrap();
+
+    let error = get_folders_file_triples(temp_dir.path(), None, None, false).unwrap_err();
+    assert!(error.to_string().starts_with("no configuration files found in"));
+    assert!(error.to_string().contains("problem_generation"));
+
+    let error = get_configuration_files(&temp_dir.path().join("codomain_generation")).unwrap_err();
+    assert!(error.to_string().starts_with("no configuration files found in"));
+
+    let error = codomain::run_opt(CodomainOpt::folder(vec![temp_dir.path().to_path_buf()], Some(2398)))
+        .unwrap_err();
+    assert!(error.to_string().starts_with("no configuration files found in"));
+
+    let error = get_configuration_files(&temp_dir.path().join("missing")).unwrap_err();
+    assert!(error.to_string().contains("does not exist"));
+}
+
+#[test]
+fn overlap_ratio_determines_o() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let codomain_file_path = temp_dir.path().join("codomain.txt");
+    let codomain_file = codomain_file_path.to_str().unwrap();
+
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "--overlap-ratio",
+        "4",
+        "4",
+        "0.5",
+        "2",
+        codomain_file,
+        "deceptive-trap",
+    ])
+    .unwrap();
+    codomain::run_opt(codomain_opt).unwrap();
+
+    let clique_tree = CliqueTree::load(&codomain_file_path, Some(2398)).unwrap();
+    assert_eq!(
+        clique_tree.input_parameters,
+        InputParameters::new_from_primitives(4, 4, 2, 2)
+    );
+
+    //Without --overlap-ratio, O should be an integer, and the ratio should result in an o smaller than k
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "4",
+        "4",
+        "0.5",
+        "2",
+        codomain_file,
+        "deceptive-trap",
+    ])
+    .unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+    assert!(codomain::get_overlap_from_ratio(0.9, 4).is_err());
+
+    //o equal to k is rejected before generating anything
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "instance",
+        "4",
+        "4",
+        "4",
+        "2",
+        codomain_file,
+        "deceptive-trap",
+    ])
+    .unwrap();
+    fs::remove_file(&codomain_file_path).unwrap();
+    assert!(codomain::run_opt(codomain_opt).is_err());
+    assert!(!codomain_file_path.exists());
+}
+
+///Writer that counts the number of write calls, to check how often the codomain writer writes
+#[derive(Default)]
+struct CountingWriter {
+    bytes: Vec<u8>,
+    number_writes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.number_writes += 1;
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn write_small_example_codomain(flush_every: usize) -> CountingWriter {
+    let input_parameters = InputParameters::new_from_primitives(10, 5, 1, 2);
+    let codomain = read_codomain(&input_parameters, Path::new(SMALL_EXAMPLE_CODOMAIN), 2).unwrap();
+    let mut writer = CountingWriter::default();
+    write_codomain_to_writer(
+        &mut writer,
+        &input_parameters,
+        &CodomainFunction::DeceptiveTrap,
+        &codomain,
+        false,
+        flush_every,
+    )
+    .unwrap();
+    writer
+}
+
+#[test]
+fn flushing_codomain_in_chunks_gives_identical_output() {
+    let original = fs::read(SMALL_EXAMPLE_CODOMAIN).unwrap();
+    let per_value = write_small_example_codomain(1);
+    let chunked = write_small_example_codomain(DEFAULT_FLUSH_EVERY);
+    let uneven = write_small_example_codomain(7);
+
+    assert_eq!(per_value.bytes, original);
+    assert_eq!(chunked.bytes, original);
+    assert_eq!(uneven.bytes, original);
+    //flush_every 0 is treated as 1
+    assert_eq!(write_small_example_codomain(0).bytes, original);
+
+    //10 cliques of 2^5 values: one write per value (the header lines are written with the first), versus a single write
+    assert_eq!(per_value.number_writes, 10 * 32);
+    assert_eq!(chunked.number_writes, 1);
+    assert!(uneven.number_writes < per_value.number_writes);
+}
+
+#[test]
+fn legacy_codomain_without_parameters_line_is_read() {
+    let input_parameters = InputParameters::new_from_primitives(10, 5, 1, 2);
+    let codomain = read_codomain(&input_parameters, Path::new(SMALL_EXAMPLE_CODOMAIN), 2).unwrap();
+
+    //Remove the input parameters line to get the legacy layout
+    let contents = fs::read_to_string(SMALL_EXAMPLE_CODOMAIN).unwrap();
+    let mut lines: Vec<&str> = contents.lines().collect();
+    lines.remove(1);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let legacy_file_path = temp_dir.path().join("legacy.txt");
+    fs::write(&legacy_file_path, lines.join("\n") + "\n").unwrap();
+
+    let full_layout_codomain =
+        read_codomain_with_known_parameters(&input_parameters, Path::new(SMALL_EXAMPLE_CODOMAIN))
+            .unwrap();
+    let legacy_layout_codomain =
+        read_codomain_with_known_parameters(&input_parameters, &legacy_file_path).unwrap();
+    assert_eq!(full_layout_codomain, codomain);
+    assert_eq!(legacy_layout_codomain, codomain);
+
+    let clique_tree = get_clique_tree_from_codomain_file_with_parameters(
+        &legacy_file_path,
+        input_parameters.clone(),
+        &mut get_rng(Some(2398)),
+    )
+    .unwrap();
+    assert_eq!(clique_tree.codomain_function, CodomainFunction::DeceptiveTrap);
+    assert_eq!(clique_tree.codomain_values, codomain);
+
+    //Parameters in the file that differ from the given ones are rejected
+    let other_input_parameters = InputParameters::new_from_primitives(10, 5, 2, 2);
+    assert!(matches!(
+        read_codomain_with_known_parameters(&other_input_parameters, Path::new(SMALL_EXAMPLE_CODOMAIN)),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+    //Invalid given parameters are rejected before the file is read
+    assert!(matches!(
+        get_clique_tree_from_codomain_file_with_parameters(
+            &legacy_file_path,
+            InputParameters::new_from_primitives(10, 5, 5, 2),
+            &mut get_rng(Some(2398)),
+        ),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
@@ -0,0 +1,921 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use structopt::StructOpt;
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters, Topology},
+    codomain::{
+        self, generate_codomain_with_options, read_codomain, CodomainCommand, CodomainOpt,
+        GenerationOptions,
+    },
+    codomain_subclasses::CodomainFunction,
+    configuration::{get_instance_rng, get_rng, ConfigurationParameters},
+    error::ProblemGenError,
+    problem_generation::{
+        analyze_problems_from_folder, generate_configuration, read_problem_from_file, run_opt,
+        run_opt_with_summary, verify_problem_file,
+        write_problem_to_file_with_separators, ProblemCommand, ProblemOpt,
+    },
+};
+
+const CONFIGURATION: &str = "M 3 4
+k 3 4
+o 1 2
+b 2 3
+deceptive-trap
+";
+
+fn count_files(folder_path: &Path) -> usize {
+    fs::read_dir(folder_path).unwrap().count()
+}
+
+#[test]
+fn output_dir_overrides_configuration_folder_layout() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let problem_opt = ProblemOpt {
+        problem_command: ProblemCommand::ConfigurationFolder {
+            folder_paths: vec![input_dir.path().to_path_buf()],
+            number_of_problems_to_generate: 2,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: Some(output_dir.path().to_path_buf()),
+        codomain_dir: None,
+        problem_dir: None,
+        generation_options: Default::default(),
+    };
+    run_opt(problem_opt).unwrap();
+
+    assert_eq!(count_files(&output_dir.path().join("codomain_files/trap")), 2);
+    assert_eq!(count_files(&output_dir.path().join("problems/trap")), 2);
+    //Nothing but the configuration folder should be in the input folder
+    assert_eq!(count_files(input_dir.path()), 1);
+}
+
+///Count the files in the folder and all its subfolders
+fn count_files_recursively(folder_path: &Path) -> usize {
+    fs::read_dir(folder_path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .map(|path| {
+            if path.is_dir() {
+                count_files_recursively(&path)
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+#[test]
+fn run_summary_counts_match_files_on_disk() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let mut problem_opt = ProblemOpt::configuration_folder(
+        vec![input_dir.path().to_path_buf()],
+        2,
+        Some(2398),
+    );
+    problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+    let summary = run_opt_with_summary(problem_opt).unwrap();
+
+    assert_eq!(
+        summary.problems_generated,
+        count_files(&output_dir.path().join("problems/trap"))
+    );
+    assert_eq!(summary.files_written, count_files_recursively(output_dir.path()));
+    assert_eq!(summary.files_written, 2 * summary.problems_generated);
+}
+
+#[test]
+fn codomain_dir_and_problem_dir_override_folder_layout() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let codomain_dir = tempfile::tempdir().unwrap();
+    let problem_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let mut problem_opt = ProblemOpt::configuration_folder(
+        vec![input_dir.path().to_path_buf()],
+        2,
+        Some(2398),
+    );
+    problem_opt.codomain_dir = Some(codomain_dir.path().to_path_buf());
+    problem_opt.problem_dir = Some(problem_dir.path().to_path_buf());
+    run_opt(problem_opt).unwrap();
+
+    assert_eq!(count_files(&codomain_dir.path().join("trap")), 2);
+    assert_eq!(count_files(&problem_dir.path().join("trap")), 2);
+    //Nothing but the configuration folder should be in the input folder
+    assert_eq!(count_files(input_dir.path()), 1);
+
+    //The problems are read from the same directories
+    let mut verify_opt = ProblemOpt::verify(input_dir.path().to_path_buf(), None, true);
+    verify_opt.codomain_dir = Some(codomain_dir.path().to_path_buf());
+    verify_opt.problem_dir = Some(problem_dir.path().to_path_buf());
+    run_opt(verify_opt).unwrap();
+
+    let analyses = analyze_problems_from_folder(
+        input_dir.path(),
+        Some(codomain_dir.path()),
+        Some(problem_dir.path()),
+        true,
+        10,
+        &mut get_rng(Some(2398)),
+    )
+    .unwrap();
+    assert_eq!(analyses.len(), 2);
+    assert!(analyses
+        .iter()
+        .all(|analysis| analysis.problem_path.starts_with(problem_dir.path())));
+}
+
+#[test]
+fn output_dir_allows_flat_configuration_file() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let configuration_file_path = input_dir.path().join("trap.txt");
+    fs::write(&configuration_file_path, CONFIGURATION).unwrap();
+
+    let codomain_opt = CodomainOpt {
+        codomain_command: CodomainCommand::File {
+            file_path: configuration_file_path,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: Some(output_dir.path().to_path_buf()),
+        skip_existing: false,
+        generation_options: Default::default(),
+    };
+    codomain::run_opt(codomain_opt).unwrap();
+
+    assert_eq!(count_files(&output_dir.path().join("codomain_files/trap")), 25);
+}
+
+///Generate the codomain files for the configuration file into the output folder, with seed 2398
+fn generate_codomain_files(configuration_file_path: &Path, output_dir: &Path, skip_existing: bool) {
+    let codomain_opt = CodomainOpt {
+        codomain_command: CodomainCommand::File {
+            file_path: configuration_file_path.to_path_buf(),
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: Some(output_dir.to_path_buf()),
+        skip_existing,
+        generation_options: Default::default(),
+    };
+    codomain::run_opt(codomain_opt).unwrap();
+}
+
+#[test]
+fn skip_existing_keeps_valid_files_and_stays_reproducible() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let full_output_dir = tempfile::tempdir().unwrap();
+    let resumed_output_dir = tempfile::tempdir().unwrap();
+
+    let configuration_file_path = input_dir.path().join("trap.txt");
+    fs::write(&configuration_file_path, CONFIGURATION).unwrap();
+    generate_codomain_files(&configuration_file_path, full_output_dir.path(), false);
+
+    //Pre-create a valid file with constant values, and a truncated file
+    let resumed_codomain_folder = resumed_output_dir.path().join("codomain_files/trap");
+    fs::create_dir_all(&resumed_codomain_folder).unwrap();
+    let existing_contents = format!("deceptive-trap\n3 3 1 2\n{}", "0.5\n".repeat(24));
+    let existing_file_path = resumed_codomain_folder.join("deceptive-trap_3_3_1_2_7.txt");
+    fs::write(&existing_file_path, &existing_contents).unwrap();
+    let truncated_file_path = resumed_codomain_folder.join("deceptive-trap_3_3_1_2_3.txt");
+    fs::write(&truncated_file_path, "deceptive-trap\n3 3 1 2\n0.5\n").unwrap();
+
+    generate_codomain_files(&configuration_file_path, resumed_output_dir.path(), true);
+
+    assert_eq!(count_files(&resumed_codomain_folder), 25);
+    assert_eq!(
+        fs::read_to_string(&existing_file_path).unwrap(),
+        existing_contents
+    );
+    //The truncated file is regenerated, and the files after the skipped file are the same as in the full run
+    let full_codomain_folder = full_output_dir.path().join("codomain_files/trap");
+    for file_name in &[
+        "deceptive-trap_3_3_1_2_3.txt",
+        "deceptive-trap_3_3_1_2_8.txt",
+    ] {
+        assert_eq!(
+            fs::read_to_string(resumed_codomain_folder.join(file_name)).unwrap(),
+            fs::read_to_string(full_codomain_folder.join(file_name)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn seed_per_instance_generates_instance_independently() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let configuration_file_path = input_dir.path().join("trap.txt");
+    fs::write(&configuration_file_path, CONFIGURATION).unwrap();
+
+    let generation_options = GenerationOptions {
+        seed_per_instance: true,
+        ..Default::default()
+    };
+    let codomain_opt = CodomainOpt {
+        codomain_command: CodomainCommand::File {
+            file_path: configuration_file_path,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: Some(output_dir.path().to_path_buf()),
+        skip_existing: false,
+        generation_options: generation_options.clone(),
+    };
+    codomain::run_opt(codomain_opt).unwrap();
+
+    //Generate instance 7 in isolation
+    let input_parameters = InputParameters::new_from_primitives(3, 3, 1, 2);
+    let mut instance_rng = get_instance_rng(&get_rng(Some(2398)), &input_parameters, 7);
+    let isolated_codomain = generate_codomain_with_options(
+        &input_parameters,
+        &CodomainFunction::DeceptiveTrap,
+        &generation_options,
+        &mut instance_rng,
+    );
+
+    let full_run_codomain = read_codomain(
+        &input_parameters,
+        &output_dir
+            .path()
+            .join("codomain_files/trap/deceptive-trap_3_3_1_2_7.txt"),
+        2,
+    )
+    .unwrap();
+    assert_eq!(isolated_codomain, full_run_codomain);
+}
+
+#[test]
+fn generate_configuration_in_memory() {
+    let configuration_parameters =
+        ConfigurationParameters::new(3, 5, 3, 4, 1, 2, 1, 3, CodomainFunction::DeceptiveTrap);
+    let configuration_count = configuration_parameters.clone().into_iter().count();
+    assert_eq!(configuration_count, 4);
+
+    let clique_trees = generate_configuration(&configuration_parameters, 3, Some(2398)).unwrap();
+
+    assert_eq!(clique_trees.len(), configuration_count * 3);
+    assert_eq!(
+        clique_trees[0].input_parameters,
+        InputParameters::new_from_primitives(3, 3, 1, 1)
+    );
+    assert_eq!(
+        clique_trees[11].input_parameters,
+        InputParameters::new_from_primitives(4, 3, 1, 2)
+    );
+
+    //A configuration entry with o >= k is reported as an error instead of panicking
+    let invalid_configuration_parameters =
+        ConfigurationParameters::new(3, 4, 3, 4, 3, 4, 1, 2, CodomainFunction::DeceptiveTrap);
+    assert!(matches!(
+        generate_configuration(&invalid_configuration_parameters, 1, Some(2398)),
+        Err(ProblemGenError::InvalidParameters(_))
+    ));
+}
+
+///Generate the problems for a configuration with many ties in the codomain (nk-q with q = 2),
+/// with the given fitness epsilon decimals, and return the number of global optima per problem
+fn generate_nk_q_2_problems(
+    unique_optimum: bool,
+    fitness_epsilon_decimals: Option<u32>,
+) -> Vec<usize> {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let configuration_file_path = input_dir.path().join("nk_q_2.txt");
+    fs::write(
+        &configuration_file_path,
+        "M 3 6\nk 3 4\no 1 2\nb 2 3\nnk-q 2\n",
+    )
+    .unwrap();
+    let codomain_folder_path = output_dir.path().join("codomain_files");
+    let problem_folder_path = output_dir.path().join("problems");
+    fs::create_dir(&codomain_folder_path).unwrap();
+    fs::create_dir(&problem_folder_path).unwrap();
+
+    let problem_opt = ProblemOpt {
+        problem_command: ProblemCommand::ConfigurationFile {
+            input_configuration_file_path: configuration_file_path,
+            output_codomain_folder_path: codomain_folder_path.clone(),
+            output_problem_folder_path: problem_folder_path.clone(),
+            number_of_problems_to_generate: 5,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: None,
+        codomain_dir: None,
+        problem_dir: None,
+        generation_options: GenerationOptions {
+            unique_optimum,
+            fitness_epsilon_decimals,
+            ..Default::default()
+        },
+    };
+    run_opt(problem_opt).unwrap();
+
+    let mut number_of_optima = Vec::new();
+    for entry in fs::read_dir(&problem_folder_path).unwrap() {
+        let problem_file_path = entry.unwrap().path();
+        let problem = read_problem_from_file(&problem_file_path).unwrap();
+        let codomain = read_codomain(
+            &problem.input_parameters,
+            &codomain_folder_path.join(problem_file_path.file_name().unwrap()),
+            2,
+        )
+        .unwrap();
+        number_of_optima.push(problem.glob_optima_strings.len());
+
+        //The written codomain matches the problem's global optimum
+        let glob_optima_score = problem.glob_optima_score;
+        let clique_tree = CliqueTree::construct_from_problem_codomain(problem, codomain);
+        let mut number_evaluations = 0;
+        let fitness = clique_tree
+            .calculate_fitness(&clique_tree.glob_optima_strings[0], &mut number_evaluations);
+        assert!((fitness - glob_optima_score).abs() < 1e-9);
+    }
+    number_of_optima
+}
+
+#[test]
+fn unique_optimum_generates_exactly_one_global_optimum() {
+    assert!(generate_nk_q_2_problems(false, None)
+        .iter()
+        .any(|&number_of_optima| number_of_optima > 1));
+
+    let number_of_optima = generate_nk_q_2_problems(true, None);
+    assert_eq!(number_of_optima.len(), 15);
+    assert!(number_of_optima
+        .iter()
+        .all(|&number_of_optima| number_of_optima == 1));
+}
+
+#[test]
+fn unique_optimum_with_larger_fitness_epsilon_generates_exactly_one_global_optimum() {
+    //The perturbation has to exceed the fitness epsilon of 1e-4, or the tied optima stay tied
+    let number_of_optima = generate_nk_q_2_problems(true, Some(4));
+    assert_eq!(number_of_optima.len(), 15);
+    assert!(number_of_optima
+        .iter()
+        .all(|&number_of_optima| number_of_optima == 1));
+}
+
+#[test]
+fn structure_only_matches_clique_lines_of_full_problem() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let problem_output_dir = tempfile::tempdir().unwrap();
+    let structure_output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let get_problem_opt = |output_dir: &Path, structure_only: bool| {
+        let mut problem_opt =
+            ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 2, Some(2398));
+        problem_opt.output_dir = Some(output_dir.to_path_buf());
+        problem_opt.generation_options.structure_only = structure_only;
+        problem_opt
+    };
+    run_opt(get_problem_opt(problem_output_dir.path(), false)).unwrap();
+    run_opt(get_problem_opt(structure_output_dir.path(), true)).unwrap();
+
+    let structure_folder_path = structure_output_dir.path().join("problems/trap");
+    assert_eq!(
+        count_files(&structure_folder_path),
+        count_files(&problem_output_dir.path().join("problems/trap"))
+    );
+    for entry in fs::read_dir(&structure_folder_path).unwrap() {
+        let structure_file_path = entry.unwrap().path();
+        let problem_file_path = problem_output_dir
+            .path()
+            .join("problems/trap")
+            .join(structure_file_path.file_name().unwrap());
+
+        let problem_contents = fs::read_to_string(&problem_file_path).unwrap();
+        let structure_contents = fs::read_to_string(&structure_file_path).unwrap();
+        let problem_lines: Vec<&str> = problem_contents.lines().collect();
+        let structure_lines: Vec<&str> = structure_contents.lines().collect();
+
+        let number_of_cliques = structure_lines.len() - 1;
+        assert_eq!(structure_lines[0], problem_lines[0]);
+        assert_eq!(
+            structure_lines[1..],
+            problem_lines[problem_lines.len() - number_of_cliques..]
+        );
+    }
+}
+
+#[test]
+fn structure_only_writes_no_codomain_files() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let problem_opt = ProblemOpt {
+        problem_command: ProblemCommand::ConfigurationFolder {
+            folder_paths: vec![input_dir.path().to_path_buf()],
+            number_of_problems_to_generate: 2,
+        },
+        seed: Some(2398),
+        quiet: false,
+        output_dir: Some(output_dir.path().to_path_buf()),
+        codomain_dir: None,
+        problem_dir: None,
+        generation_options: GenerationOptions {
+            structure_only: true,
+            ..Default::default()
+        },
+    };
+    run_opt(problem_opt).unwrap();
+
+    assert!(!output_dir.path().join("codomain_files").exists());
+    let structure_file_path = output_dir
+        .path()
+        .join("problems/trap/deceptive-trap_3_3_1_2_1.txt");
+    let structure_contents = fs::read_to_string(structure_file_path).unwrap();
+    assert_eq!(structure_contents.lines().count(), 4);
+    assert_eq!(structure_contents.lines().next(), Some("3 3 1 2"));
+}
+
+#[test]
+fn structure_only_rejects_o_not_smaller_than_k() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(
+        problem_generation_path.join("trap.txt"),
+        "M 3 4\nk 3 4\no 3 4\nb 2 3\ndeceptive-trap\n",
+    )
+    .unwrap();
+
+    let mut problem_opt =
+        ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 1, Some(2398));
+    problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+    problem_opt.generation_options.structure_only = true;
+    let error = run_opt(problem_opt).unwrap_err();
+    assert!(error.to_string().contains("should be smaller than k"));
+}
+
+#[test]
+fn options_are_rejected_where_they_are_not_supported() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    //The structure-only mode does not write problem files, so it can't write their separators
+    let mut problem_opt =
+        ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 1, Some(2398));
+    problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+    problem_opt.generation_options.structure_only = true;
+    problem_opt.generation_options.write_separators = true;
+    let error = run_opt(problem_opt).unwrap_err();
+    assert!(error.to_string().contains("--write-separators"));
+
+    //The structure of problems generated from codomain files is given by the codomain files
+    let mut problem_opt =
+        ProblemOpt::codomain_folder(vec![output_dir.path().to_path_buf()], true, Some(2398));
+    problem_opt.generation_options.topology = Topology::Path;
+    let error = run_opt(problem_opt).unwrap_err();
+    assert!(error.to_string().contains("--topology"));
+
+    //The codomain generator does not generate problems
+    let codomain_opt = CodomainOpt::from_iter_safe(&[
+        "codomain_generator",
+        "--unique-optimum",
+        "--fitness-epsilon-decimals",
+        "6",
+        "file",
+        problem_generation_path.join("trap.txt").to_str().unwrap(),
+    ])
+    .unwrap();
+    let error = codomain::run_opt(codomain_opt).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("--unique-optimum, --fitness-epsilon-decimals can't be used"));
+
+    //The number of decimals of the fitness epsilon can't be negative
+    assert!(ProblemOpt::from_iter_safe(&[
+        "problem_generator",
+        "--fitness-epsilon-decimals=-3",
+        "configuration_folder",
+        input_dir.path().to_str().unwrap(),
+        "1",
+    ])
+    .is_err());
+}
+
+#[test]
+fn path_topology_rejects_range_of_b() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(
+        problem_generation_path.join("trap.txt"),
+        "M 3 4\nk 3 4\no 1 2\nb 2 4\ndeceptive-trap\n",
+    )
+    .unwrap();
+
+    let get_problem_opt = || {
+        let mut problem_opt =
+            ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 1, Some(2398));
+        problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+        problem_opt.generation_options.topology = Topology::Path;
+        problem_opt
+    };
+    let error = run_opt(get_problem_opt()).unwrap_err();
+    assert!(error.to_string().contains("single value of b"));
+
+    //A single value of b is replaced by the topology
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+    run_opt(get_problem_opt()).unwrap();
+    assert!(output_dir
+        .path()
+        .join("problems/trap/deceptive-trap_3_3_1_1_0.txt")
+        .exists());
+}
+
+#[test]
+fn run_opt_with_constructed_options() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let mut problem_opt =
+        ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 2, Some(2398));
+    problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+    run_opt(problem_opt).unwrap();
+
+    assert_eq!(count_files(&output_dir.path().join("problems/trap")), 2);
+
+    //Generate a single codomain file, and generate a problem for it
+    let codomain_file_path = output_dir.path().join("codomain.txt");
+    let problem_file_path = output_dir.path().join("problem.txt");
+    codomain::run_opt(CodomainOpt::instance(
+        InputParameters::new_from_primitives(4, 3, 1, 2),
+        codomain_file_path.clone(),
+        CodomainFunction::DeceptiveTrap,
+        Some(2398),
+    ))
+    .unwrap();
+    run_opt(ProblemOpt::codomain_file(
+        codomain_file_path,
+        problem_file_path.clone(),
+        true,
+        Some(2398),
+    ))
+    .unwrap();
+
+    let problem = read_problem_from_file(&problem_file_path).unwrap();
+    assert_eq!(
+        problem.input_parameters,
+        InputParameters::new_from_primitives(4, 3, 1, 2)
+    );
+}
+
+#[test]
+fn exceeding_max_codomain_entries_returns_error() {
+    let output_dir = tempfile::tempdir().unwrap();
+
+    //The default maximum rejects a codomain of 4 * 2^40 values before allocating it
+    let codomain_file_path = output_dir.path().join("codomain.txt");
+    let error = codomain::run_opt(CodomainOpt::instance(
+        InputParameters::new_from_primitives(4, 40, 1, 2),
+        codomain_file_path.clone(),
+        CodomainFunction::Random,
+        Some(2398),
+    ))
+    .unwrap_err();
+    assert!(error.to_string().contains("M=4 and k=40"));
+    assert!(!codomain_file_path.exists());
+
+    //A k that overflows the number of values is rejected as well
+    assert!(codomain::run_opt(CodomainOpt::instance(
+        InputParameters::new_from_primitives(4, 70, 1, 2),
+        codomain_file_path.clone(),
+        CodomainFunction::Random,
+        Some(2398),
+    ))
+    .is_err());
+
+    //The maximum can be lowered, so that small configurations are rejected too
+    let input_dir = tempfile::tempdir().unwrap();
+    let configuration_file_path = input_dir.path().join("trap.txt");
+    fs::write(&configuration_file_path, CONFIGURATION).unwrap();
+    let mut problem_opt = ProblemOpt::configuration_file(
+        configuration_file_path,
+        output_dir.path().join("codomain_files"),
+        output_dir.path().join("problems"),
+        1,
+        Some(2398),
+    );
+    problem_opt.generation_options.max_codomain_entries = Some(8);
+    let error = run_opt(problem_opt).unwrap_err();
+    assert!(error.to_string().contains("exceeds the maximum of 8"));
+}
+
+#[test]
+fn write_separators_appends_separators_of_construct() {
+    let output_dir = tempfile::tempdir().unwrap();
+    let input_parameters = InputParameters::new_from_primitives(5, 4, 2, 2);
+
+    let clique_tree =
+        CliqueTree::new_with_seeds(input_parameters.clone(), CodomainFunction::Random, 1, 2398);
+    let problem_file_path = output_dir.path().join("problem.txt");
+    write_problem_to_file_with_separators(&clique_tree, &problem_file_path).unwrap();
+
+    let (cliques, separators) = CliqueTree::construct(&input_parameters, &mut get_rng(Some(2398)));
+    let problem_contents = fs::read_to_string(&problem_file_path).unwrap();
+    let problem_lines: Vec<&str> = problem_contents.lines().collect();
+
+    //The last M lines contain the separators, the root's separator is empty
+    let number_of_cliques = input_parameters.m as usize;
+    let separator_lines = &problem_lines[problem_lines.len() - number_of_cliques..];
+    assert_eq!(separator_lines[0], "");
+    for (separator_line, separator) in separator_lines.iter().zip(&separators) {
+        let written_separator: Vec<u32> = separator_line
+            .split_whitespace()
+            .map(|variable_index| variable_index.parse().unwrap())
+            .collect();
+        assert_eq!(&written_separator, separator);
+    }
+
+    //The separators do not change how the rest of the problem file is read
+    let problem = read_problem_from_file(&problem_file_path).unwrap();
+    assert_eq!(problem.cliques, cliques);
+    assert_eq!(problem.glob_optima_strings, clique_tree.glob_optima_strings);
+}
+
+#[test]
+fn selected_instances_match_full_run() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let full_output_dir = tempfile::tempdir().unwrap();
+    let configuration_file_path = input_dir.path().join("trap.txt");
+    fs::write(&configuration_file_path, CONFIGURATION).unwrap();
+    generate_codomain_files(&configuration_file_path, full_output_dir.path(), false);
+    let full_codomain_folder = full_output_dir.path().join("codomain_files/trap");
+
+    for generation_options in [
+        GenerationOptions {
+            instances: vec![3, 7],
+            ..Default::default()
+        },
+        GenerationOptions {
+            instances: vec![3, 7, 12],
+            instances_range: Some(vec![0, 10]),
+            ..Default::default()
+        },
+    ] {
+        let output_dir = tempfile::tempdir().unwrap();
+        let mut codomain_opt = CodomainOpt::file(configuration_file_path.clone(), Some(2398));
+        codomain_opt.output_dir = Some(output_dir.path().to_path_buf());
+        codomain_opt.generation_options = generation_options;
+        codomain::run_opt(codomain_opt).unwrap();
+
+        let codomain_folder = output_dir.path().join("codomain_files/trap");
+        assert_eq!(count_files(&codomain_folder), 2);
+        for file_name in &[
+            "deceptive-trap_3_3_1_2_3.txt",
+            "deceptive-trap_3_3_1_2_7.txt",
+        ] {
+            assert_eq!(
+                fs::read_to_string(codomain_folder.join(file_name)).unwrap(),
+                fs::read_to_string(full_codomain_folder.join(file_name)).unwrap()
+            );
+        }
+    }
+
+    //The problem generator only writes the selected problems, which are the same as in a full run
+    let full_problem_opt = ProblemOpt::configuration_file(
+        configuration_file_path.clone(),
+        full_output_dir.path().join("codomains"),
+        full_output_dir.path().join("problems"),
+        5,
+        Some(2398),
+    );
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut problem_opt = ProblemOpt::configuration_file(
+        configuration_file_path,
+        output_dir.path().join("codomains"),
+        output_dir.path().join("problems"),
+        5,
+        Some(2398),
+    );
+    problem_opt.generation_options.instances_range = Some(vec![3, 5]);
+    for folder_path in &[full_output_dir.path(), output_dir.path()] {
+        fs::create_dir_all(folder_path.join("codomains")).unwrap();
+        fs::create_dir_all(folder_path.join("problems")).unwrap();
+    }
+    run_opt(full_problem_opt).unwrap();
+    run_opt(problem_opt).unwrap();
+
+    assert_eq!(count_files(&output_dir.path().join("problems")), 2);
+    for file_name in &[
+        "codomains/deceptive-trap_3_3_1_2_3.txt",
+        "problems/deceptive-trap_3_3_1_2_4.txt",
+    ] {
+        assert_eq!(
+            fs::read_to_string(output_dir.path().join(file_name)).unwrap(),
+            fs::read_to_string(full_output_dir.path().join(file_name)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn verify_passes_generated_problems_and_fails_corrupted_score() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+
+    let mut problem_opt =
+        ProblemOpt::configuration_folder(vec![input_dir.path().to_path_buf()], 2, Some(2398));
+    problem_opt.output_dir = Some(output_dir.path().to_path_buf());
+    run_opt(problem_opt).unwrap();
+
+    let problem_file_path = output_dir
+        .path()
+        .join("problems/trap/deceptive-trap_3_3_1_2_1.txt");
+    let codomain_file_path = output_dir
+        .path()
+        .join("codomain_files/trap/deceptive-trap_3_3_1_2_1.txt");
+    assert!(verify_problem_file(&problem_file_path, &codomain_file_path, true)
+        .unwrap()
+        .is_empty());
+    run_opt(ProblemOpt::verify(output_dir.path().to_path_buf(), None, true)).unwrap();
+
+    //Corrupt the global optimum score on the second line
+    let problem_contents = fs::read_to_string(&problem_file_path).unwrap();
+    let mut problem_lines: Vec<&str> = problem_contents.lines().collect();
+    problem_lines[1] = "123.5";
+    fs::write(&problem_file_path, problem_lines.join("\n")).unwrap();
+
+    let mismatches = verify_problem_file(&problem_file_path, &codomain_file_path, true).unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert!(mismatches[0].contains("123.5"));
+    assert!(run_opt(ProblemOpt::verify(
+        problem_file_path,
+        Some(codomain_file_path),
+        true
+    ))
+    .is_err());
+    let error = run_opt(ProblemOpt::verify(output_dir.path().to_path_buf(), None, true))
+        .unwrap_err();
+    assert!(error.to_string().contains("1 of 2 problem files"));
+}
+
+#[test]
+fn problem_file_round_trip() {
+    let output_dir = tempfile::tempdir().unwrap();
+    let input_parameters = InputParameters::new_from_primitives(6, 4, 2, 2);
+    let clique_tree = CliqueTree::new_seeded(input_parameters, CodomainFunction::Trap, 2398);
+    let problem_file_path = output_dir.path().join("problem.txt");
+    clique_tree.write_problem_file(&problem_file_path).unwrap();
+
+    let problem = CliqueTree::read_problem_file(&problem_file_path).unwrap();
+    assert_eq!(problem.input_parameters, clique_tree.input_parameters);
+    assert_eq!(problem.cliques, clique_tree.cliques);
+    assert_eq!(problem.glob_optima_strings, clique_tree.glob_optima_strings);
+    assert!((problem.glob_optima_score - clique_tree.glob_optima_score).abs() < 1e-9);
+
+    let read_clique_tree =
+        CliqueTree::construct_from_problem_codomain(problem, clique_tree.codomain_values.clone());
+    assert_eq!(read_clique_tree.separators, clique_tree.separators);
+}
+
+///Read all files in the folder and its subfolders, as their paths relative to the folder with their contents, sorted by path
+fn read_files_recursively(folder_path: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut files = Vec::new();
+    let mut folders = vec![folder_path.to_path_buf()];
+    while let Some(folder) = folders.pop() {
+        for entry in fs::read_dir(&folder).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                folders.push(path);
+            } else {
+                let relative_path = path.strip_prefix(folder_path).unwrap().to_path_buf();
+                files.push((relative_path, fs::read(&path).unwrap()));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[test]
+fn codomain_folder_runs_with_same_seed_are_identical() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let codomain_generation_path = input_dir.path().join("codomain_generation");
+    fs::create_dir(&codomain_generation_path).unwrap();
+    fs::write(codomain_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+    fs::write(
+        codomain_generation_path.join("random.txt"),
+        CONFIGURATION.replace("deceptive-trap", "random"),
+    )
+    .unwrap();
+
+    codomain::run_opt(CodomainOpt::folder(
+        vec![input_dir.path().to_path_buf()],
+        Some(2398),
+    ))
+    .unwrap();
+    let first_run_files = read_files_recursively(input_dir.path());
+
+    //The second run removes the generated folders (and any other folder) before generating the same files again
+    fs::create_dir_all(input_dir.path().join("stale/nested")).unwrap();
+    codomain::run_opt(CodomainOpt::folder(
+        vec![input_dir.path().to_path_buf()],
+        Some(2398),
+    ))
+    .unwrap();
+    let second_run_files = read_files_recursively(input_dir.path());
+
+    assert!(!input_dir.path().join("stale").exists());
+    assert_eq!(first_run_files.len(), 2 + 2 * 25);
+    assert_eq!(first_run_files, second_run_files);
+}
+
+#[test]
+fn analyze_writes_a_row_per_problem() {
+    let input_dir = tempfile::tempdir().unwrap();
+    let problem_generation_path = input_dir.path().join("problem_generation");
+    fs::create_dir(&problem_generation_path).unwrap();
+    fs::write(problem_generation_path.join("trap.txt"), CONFIGURATION).unwrap();
+    run_opt(ProblemOpt::configuration_folder(
+        vec![input_dir.path().to_path_buf()],
+        3,
+        Some(2398),
+    ))
+    .unwrap();
+
+    run_opt(ProblemOpt::analyze(
+        input_dir.path().to_path_buf(),
+        None,
+        true,
+        200,
+        Some(2398),
+    ))
+    .unwrap();
+
+    let contents = fs::read_to_string(input_dir.path().join("analysis.csv")).unwrap();
+    let mut lines = contents.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), 3);
+    for row in &rows {
+        assert_eq!(row.len(), header.len());
+        assert!(row[0].ends_with(".txt"));
+        assert_eq!(row[1..5], ["3", "3", "1", "2"]);
+        let glob_optima_score: f64 = row[5].parse().unwrap();
+        let number_of_global_optima: usize = row[6].parse().unwrap();
+        let expected_random_fitness: f64 = row[7].parse().unwrap();
+        let codomain_max: f64 = row[10].parse().unwrap();
+        assert!(number_of_global_optima >= 1);
+        assert!(expected_random_fitness < glob_optima_score);
+        assert!(glob_optima_score <= 3.0 * codomain_max + 1e-9);
+    }
+
+    //The analysis of the folder matches the rows of the CSV file
+    let analyses =
+        analyze_problems_from_folder(input_dir.path(), None, None, true, 200, &mut get_rng(Some(2398)))
+            .unwrap();
+    assert_eq!(analyses.len(), rows.len());
+    for (analysis, row) in analyses.iter().zip(&rows) {
+        assert_eq!(analysis.problem_path.display().to_string(), row[0]);
+    }
+}
@@ -0,0 +1,53 @@
+//Run with `cargo test --features wasm`
+#![cfg(feature = "wasm")]
+
+use problem_generator::problem::{
+    clique_tree::{CliqueTree, InputParameters},
+    codomain_subclasses::CodomainFunction,
+    wasm::{
+        construct_clique_tree, evaluate_clique_tree, free_clique_tree,
+        get_clique_tree_global_optimum_score,
+    },
+};
+
+#[test]
+fn construct_and_evaluate_tiny_problem() {
+    let handle = construct_clique_tree(3, 3, 1, 2, "nk-q 4", 2398).unwrap();
+    let clique_tree = CliqueTree::new_seeded(
+        InputParameters::new_from_primitives(3, 3, 1, 2),
+        CodomainFunction::NKq { q: 4 },
+        2398,
+    );
+
+    let global_optimum: Vec<i32> = clique_tree.glob_optima_strings[0]
+        .iter()
+        .map(|&bit| bit as i32)
+        .collect();
+    assert_eq!(
+        get_clique_tree_global_optimum_score(handle).unwrap(),
+        clique_tree.glob_optima_score
+    );
+    assert_eq!(
+        evaluate_clique_tree(handle, &global_optimum).unwrap(),
+        clique_tree.glob_optima_score
+    );
+
+    assert!(evaluate_clique_tree(handle, &[0, 1]).is_err());
+    assert!(evaluate_clique_tree(handle, &[0, 1, 2, 0, 1, 0, 1]).is_err());
+    assert!(evaluate_clique_tree(handle + 1, &global_optimum).is_err());
+    assert!(construct_clique_tree(3, 3, 3, 2, "random", 2398).is_err());
+    assert!(construct_clique_tree(0, 3, 1, 2, "random", 2398).is_err());
+    assert!(construct_clique_tree(3, 3, 1, 0, "random", 2398).is_err());
+    assert!(construct_clique_tree(3, 40, 1, 2, "random", 2398).is_err());
+    assert!(construct_clique_tree(3, 3, 1, 2, "custom not-registered", 2398).is_err());
+    assert!(construct_clique_tree(3, 3, 1, 2, "step-trap 0", 2398).is_err());
+
+    //A freed handle can't be used anymore, until it is reused for a new clique tree
+    free_clique_tree(handle).unwrap();
+    assert!(evaluate_clique_tree(handle, &global_optimum).is_err());
+    assert!(free_clique_tree(handle).is_err());
+    assert_eq!(
+        construct_clique_tree(3, 3, 1, 2, "nk-q 4", 2398).unwrap(),
+        handle
+    );
+}